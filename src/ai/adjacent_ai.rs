@@ -3,11 +3,12 @@ use hecs::Entity;
 
 use crate::{
     raws::{self, Reaction},
+    reputation,
     utils::get_mobs_at_idx,
     Attack, Faction, Map, Player, Position, State,
 };
 
-use super::{apply_energy_cost, MyTurn};
+use super::{apply_energy_cost, notices, MyTurn};
 
 pub fn adjacent_ai_system(state: &mut State) {
     let mut turn_done: Vec<Entity> = Vec::new();
@@ -63,6 +64,11 @@ pub fn adjacent_ai_system(state: &mut State) {
             turn_done.push(ent);
         }
     }
+    //mobs that haven't noticed the player don't know there's anyone to attack
+    let player = state.player_ent.unwrap();
+    attacks.retain(|(ent, target)| *target != player || notices(state, *ent, player));
+    turn_done.retain(|ent| attacks.iter().any(|(attacker, _)| attacker == ent));
+
     //todo: change this so that a random target is selected from possible enemies if there are several valid targets for an entity!
     for attack in attacks.iter() {
         let _ = state
@@ -87,7 +93,7 @@ fn evaluate(
         if let Ok(faction) = state.world.get::<&Faction>(*ent) {
             reactions.push((
                 *ent,
-                raws::faction_reaction(my_faction, &faction.name, &raws::RAWS.lock().unwrap()),
+                reputation::faction_reaction(my_faction, &faction.name, state),
             ));
         }
     });
@@ -1,42 +1,74 @@
-use bracket_lib::prelude::Point;
+use bracket_lib::prelude::{a_star_search, Point};
 use hecs::Entity;
+use serde::{Deserialize, Serialize};
 
-use crate::{statistics::BaseStatistics, Position, State};
+use crate::{map_indexing::SPATIAL_INDEX, FoV, HasMoved, Name, Position, State};
 
-use super::MyTurn;
+use super::{apply_energy_cost, ActionType, MyTurn};
 
+#[derive(Serialize, Deserialize)]
 pub struct InCombat {
     pub target: Entity,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Chasing {
     pub target: Entity,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct LastKnownPosition {
     pub pos: Point,
 }
 
+///Mobs that couldn't see anyone to go after this turn head for wherever they last saw or heard their target. Once
+/// they get there without finding anyone they lose track of them
 pub fn chase_ai_system(state: &mut State) {
     let mut turn_done = Vec::new();
+    let mut has_moved = Vec::new();
+    let mut lost_track = Vec::new();
     let mut chases_to_initiate = Vec::new();
 
-    for (entity, (_, combat, pos, stats, chase)) in state.world.query_mut::<(
+    for (entity, (_, last_known, pos, fov, combat, chasing)) in state.world.query_mut::<(
         &MyTurn,
-        &InCombat,
+        &LastKnownPosition,
         &mut Position,
-        &BaseStatistics,
+        &mut FoV,
+        Option<&InCombat>,
         Option<&Chasing>,
     )>() {
-        //
+        turn_done.push(entity);
 
-        if let Some(chasing) = chase {
-            //continue chasing
+        if let (Some(combat), None) = (combat, chasing) {
+            chases_to_initiate.push((entity, combat.target));
+        }
+
+        let path = a_star_search(
+            state.map.xy_idx(pos.x, pos.y),
+            state.map.xy_idx(last_known.pos.x, last_known.pos.y),
+            &state.map,
+        );
 
-            turn_done.push(entity);
+        //the next step might be taken up by someone else, in which case the mob waits its turn unless they're
+        // standing right where it was headed
+        if path.success && path.steps.len() > 1 {
+            let mut spatial_map = SPATIAL_INDEX.lock().unwrap();
+            if spatial_map.is_tile_blocked(path.steps[1]) {
+                if path.steps.len() == 2 {
+                    lost_track.push(entity);
+                }
+                continue;
+            }
+
+            spatial_map.set_tile_unblocked_by_entity(state.map.xy_idx(pos.x, pos.y));
+            pos.x = path.steps[1] as i32 % state.map.map_width;
+            pos.y = path.steps[1] as i32 / state.map.map_width;
+            spatial_map.set_tile_blocked_by_entity(path.steps[1]);
+
+            fov.dirty = true;
+            has_moved.push(entity);
         } else {
-            //initiate a chase
-            chases_to_initiate.push((entity, combat.target));
+            lost_track.push(entity);
         }
     }
 
@@ -44,6 +76,31 @@ pub fn chase_ai_system(state: &mut State) {
         let _ = state.world.insert_one(*ent, Chasing { target: *target });
     }
 
+    for ent in has_moved.iter() {
+        let _ = state.world.insert_one(*ent, HasMoved {});
+        apply_energy_cost(state, ActionType::Move, *ent);
+    }
+
+    for ent in lost_track.iter() {
+        let chased_player = state
+            .world
+            .get::<&Chasing>(*ent)
+            .is_ok_and(|chasing| Some(chasing.target) == state.player_ent);
+        if chased_player {
+            let name = state
+                .world
+                .get::<&Name>(*ent)
+                .map_or("Something".to_string(), |name| name.name.clone());
+            state
+                .game_log
+                .add_log(format!("The {} loses track of you.", name));
+        }
+
+        let _ = state.world.remove_one::<LastKnownPosition>(*ent);
+        let _ = state.world.remove_one::<InCombat>(*ent);
+        let _ = state.world.remove_one::<Chasing>(*ent);
+    }
+
     //ends turn for all enemies that made a movement to chase
     for ent in turn_done.iter() {
         let _ = state.world.remove_one::<MyTurn>(*ent);
@@ -0,0 +1,60 @@
+use bracket_lib::prelude::{Algorithm2D, Point};
+use hecs::Entity;
+
+use crate::{
+    components::{Confused, HasMoved},
+    effects::{affected_by, confused_direction},
+    map_indexing::SPATIAL_INDEX,
+    Player, Position, State,
+};
+
+use super::{apply_energy_cost, MyTurn};
+
+///Confused mobs spend half of their turns stumbling in a random direction
+pub fn confused_ai_system(state: &mut State) {
+    let confused = affected_by::<Confused>(state);
+    let acting = state
+        .world
+        .query_mut::<(&MyTurn, &Position)>()
+        .without::<&Player>()
+        .into_iter()
+        .filter(|(ent, _)| confused.contains(ent))
+        .map(|(ent, (_turn, pos))| (ent, *pos))
+        .collect::<Vec<_>>();
+
+    let mut moved: Vec<Entity> = Vec::new();
+    for (ent, pos) in acting.into_iter() {
+        //zero means it does what it meant to this turn
+        let direction = confused_direction(state, ent, Point::zero());
+        if direction == Point::zero() {
+            continue;
+        }
+
+        let destination = Point::new(pos.x, pos.y) + direction;
+        if !state.map.in_bounds(destination) {
+            moved.push(ent);
+            continue;
+        }
+
+        let idx = state.map.xy_idx(destination.x, destination.y);
+        let my_idx = state.map.xy_idx(pos.x, pos.y);
+        let mut spatial_map = SPATIAL_INDEX.lock().unwrap();
+
+        if !spatial_map.is_tile_blocked(idx) {
+            spatial_map.set_tile_unblocked_by_entity(my_idx);
+            spatial_map.set_tile_blocked_by_entity(idx);
+            std::mem::drop(spatial_map);
+
+            let _ = state.world.insert_one(ent, Position::from(destination));
+        }
+        //bumping into a wall still wastes the turn
+        moved.push(ent);
+    }
+
+    for ent in moved.iter() {
+        let _ = state.world.insert_one(*ent, HasMoved {});
+        let _ = state.world.remove_one::<MyTurn>(*ent);
+
+        apply_energy_cost(state, super::ActionType::Move, *ent);
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
-    hunger::hunger_system, statistics::BaseStatistics, time_system, Map, Position, ProgramState,
-    State,
+    components::{Disabled, Stunned},
+    effects::affected_by,
+    hunger::hunger_system,
+    statistics::BaseStatistics,
+    time_system, Map, Position, ProgramState, State,
 };
 
 use super::{spot_traps, Energy, MyTurn};
@@ -18,11 +21,13 @@ pub fn run_initiative(state: &mut State) -> ProgramState {
         spot_traps(state);
 
         let mut turns_to_add = Vec::new();
+        let stunned = affected_by::<Stunned>(state);
 
         //collects query into vec so it can be sorted as iterators can't directly be sorted
         let mut query = state
             .world
             .query_mut::<(&mut Energy, &BaseStatistics, &Position)>()
+            .without::<&Disabled>()
             .into_iter()
             .collect::<Vec<(hecs::Entity, (&mut Energy, &BaseStatistics, &Position))>>();
 
@@ -33,6 +38,11 @@ pub fn run_initiative(state: &mut State) -> ProgramState {
         });
 
         for (ent, (energy, stats, pos)) in query.into_iter().take(MAX_TURNS_PER_TICK) {
+            //stunned entities don't get to build up energy for when it wears off either
+            if stunned.contains(&ent) {
+                continue;
+            }
+
             if bracket_lib::geometry::DistanceAlg::Pythagoras
                 .distance2d(state.player_pos, Point::new(pos.x, pos.y))
                 < 30.
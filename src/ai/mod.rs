@@ -1,6 +1,8 @@
 mod initiative;
 use std::collections::VecDeque;
 
+use serde::{Deserialize, Serialize};
+
 use hecs::Entity;
 pub use initiative::*;
 mod adjacent_ai;
@@ -21,13 +23,22 @@ mod spot_traps;
 use spot_traps::*;
 mod chase_ai;
 pub use chase_ai::*;
+mod confused_ai;
+pub use confused_ai::*;
 mod pathing;
 pub use pathing::*;
+mod stealth;
+pub use stealth::*;
 
-use crate::{statistics::BaseStatistics, State};
+use crate::{
+    statistics::{get_encumbrance, BaseStatistics},
+    State,
+};
 
+#[derive(Serialize, Deserialize)]
 pub struct MyTurn {}
 
+#[derive(Serialize, Deserialize)]
 pub struct Energy {
     pub value: i32,
 }
@@ -39,6 +50,11 @@ pub enum ActionType {
     Pickup,
     UseItem,
     OpenDoor,
+    Hack,
+    Reload,
+    Throw,
+    Salvage,
+    Craft,
 }
 
 impl ActionType {
@@ -50,13 +66,21 @@ impl ActionType {
             ActionType::Pickup => 50,
             ActionType::OpenDoor => 50,
             ActionType::UseItem => 100,
+            ActionType::Hack => 150,
+            ActionType::Reload => 120,
+            ActionType::Throw => 100,
+            ActionType::Salvage => 150,
+            ActionType::Craft => 300,
         }
     }
 }
 
+///Carrying too much makes everything take longer
 pub fn apply_energy_cost(state: &mut State, action: ActionType, ent: Entity) {
+    let cost = action.get_cost() * get_encumbrance(state, ent).energy_cost_percent() / 100;
+
     if let Ok(mut energy) = state.world.get::<&mut Energy>(ent) {
-        energy.value -= action.get_cost();
+        energy.value -= cost;
     }
 }
 
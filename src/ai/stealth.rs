@@ -0,0 +1,237 @@
+use bracket_lib::prelude::{DistanceAlg, Point};
+use hecs::Entity;
+
+use crate::{
+    gamelog::DEBUGLOG,
+    raws::Reaction,
+    reputation,
+    statistics::{gain_skill_exp, get_encumbrance, get_skill_value, BaseStatistics, Pools, Skill},
+    Faction, Name, Player, Position, State,
+};
+
+use super::{InCombat, LastKnownPosition};
+
+///How far each kind of noise carries, in tiles
+pub const FOOTSTEP_NOISE: i32 = 3;
+pub const DOOR_NOISE: i32 = 6;
+pub const MELEE_NOISE: i32 = 5;
+pub const GUNFIRE_NOISE: i32 = 14;
+
+///Difficulty of slipping past a mob with no Intelligence modifier that isn't looking for anyone
+const BASE_PERCEPTION: i32 = 10;
+///Mobs already investigating something are harder to sneak past
+const ALERT_BONUS: i32 = 4;
+///Within this many tiles every step closer makes the player easier to spot
+const CLOSE_RANGE: i32 = 4;
+///Damage dice are multiplied by this for attacks on targets that haven't noticed the attacker
+pub const SNEAK_ATTACK_MULTIPLIER: i32 = 2;
+
+///Every mob within earshot that would attack whatever made the noise comes looking for where it came from
+pub fn make_noise(state: &mut State, source: Entity, radius: i32) {
+    let Ok(source_pos) = state
+        .world
+        .get::<&Position>(source)
+        .map(|pos| Into::<Point>::into(*pos))
+    else {
+        return;
+    };
+    let Ok(source_faction) = state.world.get::<&Faction>(source).map(|f| f.name.clone()) else {
+        return;
+    };
+
+    let hearers = state
+        .world
+        .query::<(&Faction, &Position, &Pools)>()
+        .without::<&Player>()
+        .iter()
+        .filter(|(ent, (faction, pos, _))| {
+            *ent != source
+                && DistanceAlg::Pythagoras.distance2d(source_pos, Into::<Point>::into(**pos))
+                    <= radius as f32
+                && reputation::faction_reaction(&faction.name, &source_faction, state)
+                    == Reaction::Attack
+        })
+        .map(|(ent, _)| ent)
+        .collect::<Vec<_>>();
+
+    for hearer in hearers {
+        let _ = state
+            .world
+            .insert_one(hearer, LastKnownPosition { pos: source_pos });
+    }
+}
+
+///How loud the entity is walking, trained Stealth softens footsteps but never silences them completely
+pub fn footstep_noise(state: &mut State, ent: Entity) -> i32 {
+    (FOOTSTEP_NOISE - get_skill_value(Skill::Stealth, ent, state) / 3).max(1)
+}
+
+///The watcher's roll to spot the player this AI pass, every system that asks again during the pass gets the same answer
+pub struct PerceptionRoll {
+    pub noticed: bool,
+}
+
+///Lets every watcher roll to spot the player afresh, called at the start of each AI pass
+pub fn forget_perception_rolls(state: &mut State) {
+    let rolled = state
+        .world
+        .query::<&PerceptionRoll>()
+        .iter()
+        .map(|(ent, _)| ent)
+        .collect::<Vec<_>>();
+
+    for ent in rolled {
+        let _ = state.world.remove_one::<PerceptionRoll>(ent);
+    }
+}
+
+pub fn is_aware_of(state: &State, watcher: Entity, target: Entity) -> bool {
+    state
+        .world
+        .get::<&InCombat>(watcher)
+        .is_ok_and(|combat| combat.target == target)
+}
+
+///Whether the watcher spots the target this turn. Only the player gets to sneak, with Dexterity and Stealth against
+/// the watcher's Intelligence, and once noticed they stay noticed until the watcher loses track of them. The watcher
+/// only rolls once per AI pass
+pub fn notices(state: &mut State, watcher: Entity, target: Entity) -> bool {
+    if Some(target) != state.player_ent || is_aware_of(state, watcher, target) {
+        return true;
+    }
+    if let Ok(roll) = state.world.get::<&PerceptionRoll>(watcher) {
+        return roll.noticed;
+    }
+
+    let (Ok(watcher_pos), Ok(target_pos)) = (
+        state
+            .world
+            .get::<&Position>(watcher)
+            .map(|pos| Into::<Point>::into(*pos)),
+        state
+            .world
+            .get::<&Position>(target)
+            .map(|pos| Into::<Point>::into(*pos)),
+    ) else {
+        return true;
+    };
+    let distance = DistanceAlg::Chebyshev.distance2d(watcher_pos, target_pos) as i32;
+
+    let perception = BASE_PERCEPTION
+        + state
+            .world
+            .get::<&BaseStatistics>(watcher)
+            .map_or(0, |stats| stats.intelligence.get_modifier())
+        + if state.world.get::<&LastKnownPosition>(watcher).is_ok() {
+            ALERT_BONUS
+        } else {
+            0
+        }
+        + (CLOSE_RANGE - distance).max(0);
+
+    let dex_modifier = state
+        .world
+        .get::<&BaseStatistics>(target)
+        .map_or(0, |stats| stats.dexterity.get_modifier());
+    let stealth =
+        state.rng.roll_dice(1, 20) + get_skill_value(Skill::Stealth, target, state) + dex_modifier
+            - get_encumbrance(state, target).dodge_penalty();
+
+    DEBUGLOG.add_log(format!("Stealth {} vs perception {}", stealth, perception));
+
+    let noticed = stealth < perception;
+    let _ = state.world.insert_one(watcher, PerceptionRoll { noticed });
+    if !noticed {
+        return false;
+    }
+
+    alert(state, watcher, target);
+    let name = state
+        .world
+        .get::<&Name>(watcher)
+        .map_or("Something".to_string(), |name| name.name.clone());
+    state.game_log.add_log(format!("The {} notices you!", name));
+
+    true
+}
+
+///The watcher knows exactly where the target is and won't need to be snuck past again until it loses track of them
+pub fn alert(state: &mut State, watcher: Entity, target: Entity) {
+    if !is_aware_of(state, watcher, target) {
+        let _ = state.world.insert_one(watcher, InCombat { target });
+    }
+}
+
+///How many times over the attack's damage dice are rolled, unaware targets take a sneak attack. Landing one trains
+/// Stealth
+pub fn sneak_attack_multiplier(state: &mut State, attacker: Entity, target: Entity) -> i32 {
+    let unaware = Some(attacker) == state.player_ent
+        && state.world.get::<&Faction>(target).is_ok()
+        && !is_aware_of(state, target, attacker);
+
+    if !unaware {
+        return 1;
+    }
+
+    gain_skill_exp(Skill::Stealth, 1, attacker, state);
+    state.game_log.add_log("Sneak attack!".to_string());
+    SNEAK_ATTACK_MULTIPLIER
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::{spawn_test_mob, test_state};
+
+    #[test]
+    fn noise_draws_enemies_that_can_be_sneak_attacked_until_they_notice() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+        let pos = state.player_pos;
+
+        let thug = spawn_test_mob(&mut state, "Street Thug", pos.x + 2, pos.y);
+        let _ = state.world.remove_one::<LastKnownPosition>(thug);
+
+        make_noise(&mut state, player, 1);
+        assert!(state.world.get::<&LastKnownPosition>(thug).is_err());
+        make_noise(&mut state, player, GUNFIRE_NOISE);
+        assert_eq!(
+            state.world.get::<&LastKnownPosition>(thug).unwrap().pos,
+            pos
+        );
+
+        assert_eq!(
+            sneak_attack_multiplier(&mut state, player, thug),
+            SNEAK_ATTACK_MULTIPLIER
+        );
+        alert(&mut state, thug, player);
+        assert_eq!(sneak_attack_multiplier(&mut state, player, thug), 1);
+        assert!(notices(&mut state, thug, player));
+    }
+
+    #[test]
+    fn watchers_roll_once_per_pass_to_spot_the_player() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+        let pos = state.player_pos;
+
+        let thug = spawn_test_mob(&mut state, "Street Thug", pos.x + 6, pos.y);
+
+        //keep rolling fresh passes until the player slips by, asking again in the same pass can't change that
+        let mut slipped_by = false;
+        for _ in 0..100 {
+            forget_perception_rolls(&mut state);
+            let _ = state.world.remove_one::<InCombat>(thug);
+
+            if !notices(&mut state, thug, player) {
+                slipped_by = true;
+                break;
+            }
+        }
+        assert!(slipped_by);
+        for _ in 0..20 {
+            assert!(!notices(&mut state, thug, player));
+        }
+        assert!(!is_aware_of(&state, thug, player));
+    }
+}
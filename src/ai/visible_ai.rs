@@ -1,17 +1,18 @@
 use std::{collections::HashMap, hash::Hash};
 
-use bracket_lib::prelude::Point;
+use bracket_lib::prelude::Algorithm2D;
 use hecs::Entity;
 
 use crate::{
     map_indexing::SPATIAL_INDEX,
     raws::{self, Reaction},
+    reputation,
     statistics::Pools,
     utils::{get_mob_entities_at_position, get_mobs_at_idx},
     Faction, FoV, Map, Player, Position, State, WantsToApproach, WantsToFlee,
 };
 
-use super::MyTurn;
+use super::{notices, LastKnownPosition, MyTurn};
 
 pub fn visible_ai_system(state: &mut State) {
     //stores possible targets for each entity that can see enemies
@@ -57,6 +58,23 @@ pub fn visible_ai_system(state: &mut State) {
         }
     }
 
+    //a player that sneaks past isn't a target, as far as the mob knows
+    let player_idx = state.map.xy_idx(state.player_pos.x, state.player_pos.y);
+    let mut watchers = possible_attacks
+        .iter()
+        .filter(|(_id, targets)| targets.contains(&player_idx))
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
+    //sorted so the stealth rolls come out the same for the same seed
+    watchers.sort();
+    for id in watchers {
+        if !notices(state, id, state.player_ent.unwrap()) {
+            if let Some(targets) = possible_attacks.get_mut(&id) {
+                targets.retain(|idx| *idx != player_idx);
+            }
+        }
+    }
+
     for (id, targets) in possible_attacks.iter() {
         let mut index: usize = 0;
         if targets.len() == 0 {
@@ -74,6 +92,13 @@ pub fn visible_ai_system(state: &mut State) {
                 target: targets[index] as i32,
             },
         );
+        //somewhere to go looking if the target slips out of sight
+        let _ = state.world.insert_one(
+            *id,
+            LastKnownPosition {
+                pos: state.map.index_to_point2d(targets[index]),
+            },
+        );
     }
 
     for (id, tiles) in flee_targets.iter() {
@@ -97,7 +122,7 @@ fn evaluate(
         if let Ok(faction) = state.world.get::<&Faction>(ent) {
             reactions.push((
                 idx,
-                raws::faction_reaction(my_faction, &faction.name, &raws::RAWS.lock().unwrap()),
+                reputation::faction_reaction(my_faction, &faction.name, state),
             ));
         }
     }
@@ -1,8 +1,12 @@
 use bracket_lib::{color::{BLACK, RGB, WHITE}, terminal::console};
 use hecs::{World, Entity};
-use crate::{damage_system::DamageSystem, effects::{add_effect, Targets}, statistics::{BaseStatistics, Pools}, EquipmentSlot, Equippable, Equipped, Naturals, Position, Weapon, WeaponStat};
+use std::collections::HashMap;
+use crate::{ai::{make_noise, sneak_attack_multiplier, MELEE_NOISE}, components::{OnDamaged, OnHit}, damage_system::DamageSystem, effects::{add_effect, combat_trigger, Targets}, statistics::{gain_skill_exp, get_encumbrance, get_skill_value, BaseStatistics, Pools, Skill}, EquipmentSlot, Equippable, Equipped, Naturals, Position, Weapon, WeaponStat};
 
 use super::{State, Attack, Name, TakeDamage};
+///How much a dodge roll has to beat the attack roll by
+const DODGE_DIFFICULTY : i32 = 5;
+
 pub struct AttackSystem
 {}
 
@@ -15,7 +19,8 @@ impl AttackSystem
         .expect("Failed at trying to insert attack component!\n");
     }
 
-    /// TODO: this will handle the logic of checking if an attack hits but for now just directly creates suffer TakeDamage
+    /// Resolves every pending melee attack: the attack roll against armour class, the defender's dodge and block,
+    /// criticals on a natural 20 and typed damage, with the breakdown written to the game log
     pub fn run(state : &mut State)
     {
         let mut attackers: Vec<(Entity, Position)> = Vec::new();
@@ -30,7 +35,8 @@ impl AttackSystem
 
         for (target,attacker, atkstats) in defenders_to_damage
         {
-            
+            make_noise(state, attacker, MELEE_NOISE);
+
             let query = state.world.query_one_mut::<(&BaseStatistics, &Pools)>(target).expect("");
             let stats = query.0.clone();
             let pools = query.1.clone();
@@ -75,60 +81,81 @@ impl AttackSystem
                 }
                 if !found_wep
                 {
-                    weapons.push(Weapon{uses_statistic: WeaponStat::Strength,damage_die : 4
-                        , dmg_bonus : 2, to_hit_bonus: 0, num_dmg_dice: 1});
+                    weapons.push(Weapon::unarmed());
                 }
             }
 
-            let mut to_hit_bonus = 0;
-            let mut dmg_bonus = 0;
-            let mut dmg_die = 0;
-
-            to_hit_bonus += weapons[0].to_hit_bonus;
-            dmg_bonus += weapons[0].dmg_bonus;
-            dmg_die += weapons[0].damage_die;
-            let n_dice = weapons[0].num_dmg_dice;
-            match weapons[0].uses_statistic
+            let weapon = weapons[0];
+            let stat_mod = match weapon.uses_statistic
             {
-                WeaponStat::Strength => 
-                {
-                    dmg_bonus += atkstats.strength.get_modifier();
-                    to_hit_bonus += atkstats.strength.get_modifier();
-                }
-                WeaponStat::Dexterity => 
-                {
-                    dmg_bonus += atkstats.dexterity.get_modifier();
-                    to_hit_bonus += atkstats.dexterity.get_modifier();
-                }
-            }
+                WeaponStat::Strength => atkstats.strength.get_modifier(),
+                WeaponStat::Dexterity => atkstats.dexterity.get_modifier(),
+            };
+            let to_hit_bonus = weapon.to_hit_bonus + stat_mod + get_skill_value(Skill::Melee, attacker, state);
+            let dmg_bonus = weapon.dmg_bonus + stat_mod;
+
+            let attacker_name = name_of(state, attacker);
+            let target_name = name_of(state, target);
+            let armour_class = pools.armour_class.total;
 
             let natural_roll = state.rng.roll_dice(1, 20);
-            let mut hit = false;
-            if natural_roll == 0
+            let atk_total = natural_roll + to_hit_bonus;
+            let critical = natural_roll == 20;
+            let roll_breakdown = format!("{} {:+} = {} vs AC {}", natural_roll, to_hit_bonus, atk_total, armour_class);
+
+            //a natural 1 always misses and a natural 20 always hits
+            if natural_roll == 1 || (!critical && atk_total < armour_class)
             {
-                hit = false;
-            } else if natural_roll == 20
+                state.game_log.add_log(format!("{} misses {} ({}).", attacker_name, target_name, roll_breakdown));
+                continue;
+            }
+
+            //criticals are too quick to get out of the way of
+            if !critical && AttackSystem::dodges(state, target, &stats, atk_total)
             {
-                hit = true;
-            }else 
+                state.game_log.add_log(format!("{} dodges {}'s attack ({}).", target_name, attacker_name, roll_breakdown));
+                continue;
+            }
+
+            //sneak attacks stack with criticals
+            let sneak_multiplier = sneak_attack_multiplier(state, attacker, target);
+            let n_dice = sneak_multiplier * if critical { weapon.num_dmg_dice * weapon.crit_multiplier } else { weapon.num_dmg_dice };
+            let mut dmg = state.rng.roll_dice(n_dice, weapon.damage_die) + dmg_bonus;
+
+            let blocked = AttackSystem::blocks(state, target, &stats, atk_total);
+            if blocked
             {
-                let atk_roll = natural_roll + to_hit_bonus;
-                if atk_roll >= pools.armour_class.total
-                {
-                    hit = true;
-                }
+                dmg /= 2;
             }
+            //a hit always does some damage, even a blocked one
+            let dmg = std::cmp::max(1, dmg);
 
-            if hit
+            let mut outcome = format!("{} {} {} ({}) for {} {} damage", attacker_name,
+                if critical { "critically hits" } else { "hits" }, target_name, roll_breakdown, dmg, weapon.damage_type);
+            if critical
             {
-                let mut dmg = state.rng.roll_dice(n_dice, dmg_die) + dmg_bonus;
-                
-                dmg = std::cmp::max(1, dmg);
+                outcome += &format!(", dice x{}", weapon.crit_multiplier);
+            }
+            if sneak_multiplier > 1
+            {
+                outcome += &format!(", sneak attack dice x{}", sneak_multiplier);
+            }
+            if blocked
+            {
+                outcome += ", half of it blocked";
+            }
+            state.game_log.add_log(outcome + ".");
 
-                add_effect(Some(attacker), crate::effects::EffectType::Damage { amount: dmg },
+            if dmg > 0
+            {
+                add_effect(Some(attacker), crate::effects::EffectType::Damage { amount: dmg, damage_type: Some(weapon.damage_type) },
                     Targets::Single { target: target });
-                //DamageSystem::mark_for_damage(state, target, dmg);
             }
+            //DamageSystem::mark_for_damage(state, target, dmg);
+
+            AttackSystem::fire_triggers(state, attacker, target);
+
+            gain_skill_exp(Skill::Melee, 1, attacker, state);
         }
 
 
@@ -142,6 +169,167 @@ impl AttackSystem
         }
     }
 
+    ///Defender's Dodge skill and dexterity against the attack roll, getting fully out of the way is harder than
+    /// just being missed
+    fn dodges(state : &mut State, defender : Entity, stats : &BaseStatistics, atk_total : i32) -> bool
+    {
+        let roll = state.rng.roll_dice(1, 20) + get_skill_value(Skill::Dodge, defender, state)
+            + stats.dexterity.get_modifier() - get_encumbrance(state, defender).dodge_penalty();
+
+        gain_skill_exp(Skill::Dodge, 1, defender, state);
 
+        roll >= atk_total + DODGE_DIFFICULTY
+    }
 
-}
\ No newline at end of file
+    ///Only possible with something in the off hand, a successful block halves the damage
+    fn blocks(state : &mut State, defender : Entity, stats : &BaseStatistics, atk_total : i32) -> bool
+    {
+        let has_offhand = state.world.query::<&Equipped>()
+            .iter()
+            .any(|(_ent, equip)| equip.owner == defender && equip.slot == EquipmentSlot::OffHand);
+        if !has_offhand
+        {
+            return false;
+        }
+
+        //the same stat that caps how far Block can be trained
+        let roll = state.rng.roll_dice(1, 20) + get_skill_value(Skill::Block, defender, state)
+            + stats.get_stat(Skill::Block.governing_stat()).get_modifier();
+
+        gain_skill_exp(Skill::Block, 1, defender, state);
+
+        roll >= atk_total
+    }
+
+    ///Fires the on_hit effects of the attacker and whatever they have equipped at the target, then the target's
+    /// on_damaged effects back at the attacker
+    fn fire_triggers(state : &mut State, attacker : Entity, target : Entity)
+    {
+        let mut on_hit : Vec<HashMap<String, String>> = state.world.query::<(&Equipped, &OnHit)>()
+            .iter()
+            .filter(|(_ent, (equip, _trigger))| equip.owner == attacker)
+            .map(|(_ent, (_equip, trigger))| trigger.effects.clone())
+            .collect();
+        if let Ok(trigger) = state.world.get::<&OnHit>(attacker)
+        {
+            on_hit.push(trigger.effects.clone());
+        }
+
+        let mut on_damaged : Vec<HashMap<String, String>> = state.world.query::<(&Equipped, &OnDamaged)>()
+            .iter()
+            .filter(|(_ent, (equip, _trigger))| equip.owner == target)
+            .map(|(_ent, (_equip, trigger))| trigger.effects.clone())
+            .collect();
+        if let Ok(trigger) = state.world.get::<&OnDamaged>(target)
+        {
+            on_damaged.push(trigger.effects.clone());
+        }
+
+        for effects in on_hit.iter()
+        {
+            combat_trigger(attacker, effects, target, state);
+        }
+        for effects in on_damaged.iter()
+        {
+            combat_trigger(target, effects, attacker, state);
+        }
+    }
+
+
+
+}
+
+fn name_of(state : &State, ent : Entity) -> String
+{
+    state.world.get::<&Name>(ent).map_or("Something".to_string(), |name| name.name.clone())
+}
+#[cfg(test)]
+mod tests
+{
+    use bracket_lib::random::RandomNumberGenerator;
+
+    use super::*;
+    use crate::{ai::alert, effects::run_effect_queue, spawns::spawning_system::spawn_item_equipped,
+        utils::testing::{spawn_test_mob, test_state}};
+
+    ///First seed whose opening d20 comes up as the roll
+    fn seed_rolling(roll : i32) -> u64
+    {
+        (0..).find(|seed| RandomNumberGenerator::seeded(*seed).roll_dice(1, 20) == roll).unwrap()
+    }
+
+    ///Resolves one attack with the rng seeded and returns the line it logged
+    fn attack_with_seed(state : &mut State, attacker : Entity, target : Entity, seed : u64) -> String
+    {
+        state.rng = RandomNumberGenerator::seeded(seed);
+        AttackSystem::add_attack(attacker, target, state);
+        AttackSystem::run(state);
+        run_effect_queue(state);
+
+        state.game_log.entries.iter().rev()
+            .find(|entry| entry.contains(" hits ") || entry.contains(" misses ") || entry.contains(" dodges "))
+            .cloned()
+            .unwrap()
+    }
+
+    fn logged_damage(entry : &str) -> i32
+    {
+        entry.split(" for ").nth(1).and_then(|rest| rest.split(' ').next()).unwrap().parse().unwrap()
+    }
+
+    #[test]
+    fn natural_rolls_and_blocks_decide_the_damage()
+    {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+        let pos = state.player_pos;
+
+        let main_hand = state.world.query::<&Equipped>().iter()
+            .filter(|(_ent, equip)| equip.owner == player && equip.slot == EquipmentSlot::MainHand)
+            .map(|(ent, _equip)| ent)
+            .collect::<Vec<_>>();
+        for item in main_hand
+        {
+            let _ = state.world.despawn(item);
+        }
+        spawn_item_equipped(&mut state, &"Katana".to_string(), player);
+        let katana = state.world.query::<(&Name, &Weapon)>().iter()
+            .find(|(_ent, (name, _weapon))| name.name == "Katana")
+            .map(|(_ent, (_name, weapon))| *weapon)
+            .unwrap();
+        let dmg_bonus = katana.dmg_bonus + state.world.get::<&BaseStatistics>(player).unwrap().dexterity.get_modifier();
+
+        //anything but a natural 1 hits, and the thug isn't taken by surprise
+        let thug = spawn_test_mob(&mut state, "Street Thug", pos.x + 1, pos.y);
+        {
+            let mut pools = state.world.get::<&mut Pools>(thug).unwrap();
+            pools.armour_class.total = -100;
+            pools.hitpoints.max_value = 10000;
+            pools.hitpoints.current_value = 10000;
+        }
+        alert(&mut state, thug, player);
+
+        assert!(attack_with_seed(&mut state, player, thug, seed_rolling(1)).contains(" misses "));
+
+        let seed = seed_rolling(20);
+        let entry = attack_with_seed(&mut state, player, thug, seed);
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        rng.roll_dice(1, 20);
+        let crit_dice = katana.num_dmg_dice * katana.crit_multiplier;
+        assert!(entry.contains("critically hits"));
+        assert_eq!(logged_damage(&entry), std::cmp::max(1, rng.roll_dice(crit_dice, katana.damage_die) + dmg_bonus));
+
+        //keep swinging until the shield gets in the way, the rolls before the block are the hit, the dodge and the damage
+        spawn_item_equipped(&mut state, &"Riot Shield".to_string(), thug);
+        let (seed, entry) = (0..500)
+            .filter(|seed| RandomNumberGenerator::seeded(*seed).roll_dice(1, 20) < 20)
+            .map(|seed| (seed, attack_with_seed(&mut state, player, thug, seed)))
+            .find(|(_seed, entry)| entry.contains("blocked"))
+            .unwrap();
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        rng.roll_dice(1, 20);
+        rng.roll_dice(1, 20);
+        assert_eq!(logged_damage(&entry),
+            std::cmp::max(1, (rng.roll_dice(katana.num_dmg_dice, katana.damage_die) + dmg_bonus) / 2));
+    }
+}
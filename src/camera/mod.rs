@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
 
 use bracket_lib::{
     color::{BLACK, GRAY, RGB},
@@ -9,8 +10,8 @@ use codepage_437::*;
 use encode_unicode::{StrExt, Utf16Char};
 
 use crate::{
-    particles::particle_system, renderer::rgb_to_color, Hidden, Map, Position, Renderable, State,
-    TileType,
+    particles::particle_system, raws::RAWS, renderer::rgb_to_color, Hidden, Map, Position,
+    Renderable, State, TileType,
 };
 
 mod themes;
@@ -19,8 +20,35 @@ use themes::*;
 
 const SHOW_BOUNDARIES: bool = false;
 
+lazy_static! {
+    ///Theme of the depth last drawn, only looked up and parsed again once the player reaches another depth
+    static ref LEVEL_THEME: Mutex<Option<(i32, Option<Arc<MapTheme>>)>> = Mutex::new(None);
+}
+
+fn level_theme(depth: i32) -> Option<Arc<MapTheme>> {
+    let mut cached = LEVEL_THEME.lock().unwrap();
+
+    match cached.as_ref() {
+        Some((cached_depth, theme)) if *cached_depth == depth => theme.clone(),
+        _ => {
+            let theme = RAWS
+                .lock()
+                .unwrap()
+                .get_theme_for_depth(depth)
+                .map(|theme| Arc::new(MapTheme::from_raw(&theme)));
+            *cached = Some((depth, theme.clone()));
+            theme
+        }
+    }
+}
+
+///Makes the next frame parse the theme again, for when the raws are reloaded
+pub fn forget_level_theme() {
+    *LEVEL_THEME.lock().unwrap() = None;
+}
+
 pub fn render_camera(state: &mut State) {
-    let (x_chars, y_chars) = state.renderer.map_view_size;
+    let (x_chars, y_chars) = state.renderer().map_view_size;
 
     let center_x = (x_chars / 2) as i32;
     let center_y = (y_chars / 2) as i32;
@@ -34,6 +62,8 @@ pub fn render_camera(state: &mut State) {
     let map_width = state.map.map_width - 1;
     let map_height = state.map.map_height - 1;
 
+    let theme = level_theme(state.map.depth);
+
     let mut y = 0;
     for ty in min_y..max_y {
         let mut x = 0;
@@ -41,18 +71,18 @@ pub fn render_camera(state: &mut State) {
             if tx > 0 && tx < map_width && ty > 0 && ty < map_height {
                 let idx = state.map.xy_idx(tx, ty);
                 if state.map.revealed_tiles[idx] {
-                    let (glyph, fg, bg) = tile_glyph(idx, &state.map);
+                    let (glyph, fg, bg) = tile_glyph(idx, &state.map, theme.as_deref());
                     //let dt = glyph.to_string();
                     //let data =  dt.into_cp437(&CP437_CONTROL).unwrap();
                     //let res =
                     //let mut buf = [0;3];
                     let cont = *glyph.to_cp437(&CP437_WINGDINGS).unwrap().first().unwrap();
                     state
-                        .renderer
+                        .renderer()
                         .draw_char_bg(x, y, cont, rgb_to_color(fg), rgb_to_color(bg));
                 }
             } else if SHOW_BOUNDARIES {
-                state.renderer.draw_char_bg(
+                state.renderer().draw_char_bg(
                     x,
                     y,
                     *".".to_cp437(&CP437_WINGDINGS).unwrap().first().unwrap(),
@@ -73,7 +103,7 @@ pub fn render_camera(state: &mut State) {
         .query_mut::<(&Position, &Renderable)>()
         .without::<&Hidden>()
         .into_iter()
-        .map(|ent| (ent.1 .0, ent.1 .1))
+        .map(|(_ent, (pos, render))| (*pos, render.clone()))
         .collect::<Vec<_>>();
 
     entities_to_render.sort_by_key(|a| a.1.order);
@@ -99,7 +129,7 @@ pub fn render_camera(state: &mut State) {
                     .first()
                     .unwrap();
                 state
-                    .renderer
+                    .renderer()
                     .draw_char_bg(entity_screen_x, entity_screen_y, cont, fg, bg);
             }
         }
@@ -107,7 +137,7 @@ pub fn render_camera(state: &mut State) {
 }
 
 pub fn get_screen_bounds(state: &mut State) -> (i32, i32, i32, i32) {
-    let (x_chars, y_chars) = state.renderer.map_view_size;
+    let (x_chars, y_chars) = state.renderer().map_view_size;
 
     let center_x = (x_chars / 2) as i32;
     let center_y = (y_chars / 2) as i32;
@@ -120,3 +150,34 @@ pub fn get_screen_bounds(state: &mut State) -> (i32, i32, i32, i32) {
 
     (min_x, max_x, min_y, max_y)
 }
+
+#[cfg(test)]
+mod tests {
+    use bracket_lib::random::RandomNumberGenerator;
+
+    use super::*;
+    use crate::{maps::level_generator, raws};
+
+    #[test]
+    fn level_profiles_pick_the_builder_and_theme() {
+        raws::run().unwrap();
+        let profile = RAWS.lock().unwrap().get_level_profile(5).unwrap();
+        assert_eq!(profile.theme, "office");
+
+        //the office floors are the only ones with a fixed size
+        let mut rng = RandomNumberGenerator::seeded(5);
+        let mut builder = level_generator(5, &mut rng);
+        builder.build_map(&mut rng);
+        let mut map = builder.build_data.map.clone();
+        assert_eq!((map.map_width, map.map_height), (80, 50));
+
+        let idx = map
+            .map
+            .iter()
+            .position(|tile| *tile == TileType::Floor)
+            .unwrap();
+        map.visible_tiles[idx] = true;
+        let (_glyph, fg, _bg) = tile_glyph(idx, &map, level_theme(5).as_deref());
+        assert_eq!(fg, RGB::from_hex("#8a7f6a").unwrap());
+    }
+}
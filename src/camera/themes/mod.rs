@@ -1,16 +1,50 @@
 use bracket_lib::{color::{DARKSLATEGRAY, GHOST_WHITE, GRAY, GREENYELLOW, GREY40, LIGHT_GRAY, PINK2, PINK3, RGB, SLATE_BLUE}, prelude::{to_cp437, FontCharType}};
 
-use crate::{Map, TileType};
+use std::collections::HashMap;
 
+use crate::{raws::Theme, Map, TileType};
 
 
+pub struct TileStyle
+{
+    glyph : Option<String>,
+    fg : Option<RGB>,
+    bg : Option<RGB>,
+}
+
+///A level theme from the raws with its colours already parsed so it's cheap to draw with
+pub struct MapTheme
+{
+    tiles : HashMap<TileType, TileStyle>,
+}
+
+impl MapTheme
+{
+    pub fn from_raw(theme : &Theme) -> MapTheme
+    {
+        let tiles = theme.tiles.iter()
+            .map(|(tile, style)| (*tile, TileStyle
+            {
+                glyph : style.glyph.clone(),
+                fg : style.fg.as_ref().and_then(|fg| RGB::from_hex(fg).ok()),
+                bg : style.bg.as_ref().and_then(|bg| RGB::from_hex(bg).ok()),
+            }))
+            .collect();
+
+        MapTheme { tiles }
+    }
+}
 
-pub fn tile_glyph(idx : usize, map : &Map) -> (String, RGB, RGB)
+pub fn tile_glyph(idx : usize, map : &Map, theme : Option<&MapTheme>) -> (String, RGB, RGB)
 {
-    let (glyph, mut fg, bg) = match map.depth 
+    let (mut glyph, mut fg, mut bg) = get_tile_glyph_default(idx, map);
+
+    if let Some(style) = theme.and_then(|theme| theme.tiles.get(&map.map[idx]))
     {
-        _=> {get_tile_glyph_default(idx, map)}
-    };
+        if let Some(themed) = &style.glyph { glyph = themed.clone(); }
+        if let Some(themed) = style.fg { fg = themed; }
+        if let Some(themed) = style.bg { bg = themed; }
+    }
 
     if !map.visible_tiles[idx]
     {
@@ -37,6 +71,7 @@ fn get_tile_glyph_default(idx : usize, map : &Map) -> (String, RGB, RGB)
             fg = RGB::from_f32(0., 1., 0.);
         }
         TileType::DownStairs => {glyph = '>'.to_string(); fg = RGB::from_f32(0., 1., 1.);}
+        TileType::UpStairs => {glyph = '<'.to_string(); fg = RGB::from_f32(0., 1., 1.);}
         TileType::Road => {glyph = '▓'.to_string(); fg = RGB::named(SLATE_BLUE);}
         TileType::MetalGrate => {glyph = '≡'.to_string(); fg = RGB::named(LIGHT_GRAY);}
         TileType::Concrete => {glyph = ".".to_string(); fg = RGB::named(PINK2);}
@@ -1,4 +1,4 @@
-use crate::{components, statistics::{self, Pools}, Equipped, InContainer, Position, ProgramState};
+use crate::{components::{self, HurtByPlayer, StatusEffect}, missions::{self, MissionEvent}, reputation, statistics::{self, Pools}, Equipped, Faction, InContainer, Position, ProgramState};
 
 use super::{State,Name, Player};
 use bracket_lib::terminal::console;
@@ -14,8 +14,10 @@ impl ClearDeadSystem
     {
         let mut xp_to_award = 0;
         let mut entities_to_despawn : Vec<(Entity, Position)> = Vec::new();
-        for (_id,(stats,name,player, pos))
-         in state.world.query_mut::<(&Pools,&Name,Option<&Player>, &Position)>()
+        let mut player_kills : Vec<String> = Vec::new();
+        let mut mission_kills : Vec<String> = Vec::new();
+        for (_id,(stats,name,player, pos, faction, hurt_by_player))
+         in state.world.query_mut::<(&Pools,&Name,Option<&Player>, &Position, Option<&Faction>, Option<&HurtByPlayer>)>()
          {
             if stats.hitpoints.current_value <= 0
             {
@@ -35,6 +37,16 @@ impl ClearDeadSystem
 
                         xp_to_award += xp; 
                         entities_to_despawn.push((_id, pos.clone()));
+
+                        if let (Some(faction), Some(_)) = (faction, hurt_by_player)
+                        {
+                            player_kills.push(faction.name.clone());
+                        }
+
+                        if hurt_by_player.is_some()
+                        {
+                            mission_kills.push(name.name.clone());
+                        }
                     }
                 }
             }
@@ -45,6 +57,16 @@ impl ClearDeadSystem
 
          statistics::check_level_up(state);
 
+         for faction in player_kills.iter()
+         {
+            reputation::player_killed(state, faction);
+         }
+
+         for name in mission_kills.into_iter()
+         {
+            missions::record(state, MissionEvent::Killed(name));
+         }
+
          for (entity, pos) in entities_to_despawn.iter()
          {
             let eq_items = state.world.query::<(&components::Item, &Equipped)>()
@@ -74,6 +96,16 @@ impl ClearDeadSystem
 
 
 
+            //statuses on the dead have nothing left to affect
+            let statuses = state.world.query::<&StatusEffect>()
+                .iter().filter(|(_ent, status)| status.target == *entity)
+                .map(|ent| ent.0).collect::<Vec<_>>();
+
+            for status in statuses.iter()
+            {
+                let _ = state.world.despawn(*status);
+            }
+
             //delete entity
             state.world.despawn(*entity).expect("Couldn't successfully despawn dead entity!");
          }
@@ -5,32 +5,36 @@ use bracket_lib::{
     random::DiceType,
 };
 use hecs::Entity;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::statistics::StatType;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum MovementType {
     Static,
     RandomWaypoint { path: Option<(Vec<usize>, usize)> },
     Drunk,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct AIQuips {
     pub quips: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct BlocksVisibility {}
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Door {
     pub open: bool,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Projectile {
     pub damage: DiceType,
+    pub damage_type: DamageType,
+    ///Fired at whatever the projectile hits, from the round it was loaded with
+    pub effects: HashMap<String, String>,
 }
 
 pub struct Creator {
@@ -44,64 +48,73 @@ pub struct WantsToApproach {
 pub struct WantsToFlee {
     pub indices: Vec<usize>,
 }
-#[derive(Clone, PartialEq, Eq, Deserialize, Debug)]
+#[derive(Clone, PartialEq, Eq, Deserialize, Debug, Serialize)]
 pub struct Faction {
     pub name: String,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Prop {}
 
+#[derive(Serialize, Deserialize)]
 pub struct Hidden {}
 #[derive(Clone, Copy)]
 pub struct Triggered {
     pub entity: Entity,
     pub idx: i32,
 }
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DescendFloors {
     pub num_floors: u32,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct TriggerOnEnter {}
 
+#[derive(Serialize, Deserialize)]
 pub struct SingleActivation {}
 
+#[derive(Serialize, Deserialize)]
 pub struct Trigger {}
 
 pub struct HasMoved {}
 
 pub struct WantsToRest {}
 
+#[derive(Serialize, Deserialize)]
 pub struct Password {
     pub controls_target: Entity,
     pub owner: Entity,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct PasswordProtected {
     pub difficulty_class: i32,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct GivesFood {
     pub amount: i32,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct EquipmentDirty {}
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum WeaponStat {
     Strength,
     Dexterity,
 }
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Wearable {
     pub ac_bonus: i32,
 }
+#[derive(Serialize, Deserialize)]
 pub struct Naturals {
     pub weapons: Vec<Weapon>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Weapon {
     pub uses_statistic: WeaponStat,
 
@@ -109,24 +122,118 @@ pub struct Weapon {
     pub to_hit_bonus: i32,
     pub dmg_bonus: i32,
     pub num_dmg_dice: i32,
+    ///how many times the damage dice are rolled on a natural 20
+    pub crit_multiplier: i32,
+    pub damage_type: DamageType,
+}
+
+impl Weapon {
+    ///Used when the attacker has nothing equipped and no natural weapons
+    pub fn unarmed() -> Weapon {
+        Weapon {
+            uses_statistic: WeaponStat::Strength,
+            damage_die: 4,
+            to_hit_bonus: 0,
+            dmg_bonus: 2,
+            num_dmg_dice: 1,
+            crit_multiplier: 2,
+            damage_type: DamageType::Kinetic,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DamageType {
+    Kinetic,
+    Electric,
+    Thermal,
+    Emp,
+}
+
+impl DamageType {
+    pub fn parse(value: &str) -> Option<DamageType> {
+        match value {
+            "kinetic" => Some(DamageType::Kinetic),
+            "electric" => Some(DamageType::Electric),
+            "thermal" => Some(DamageType::Thermal),
+            "emp" => Some(DamageType::Emp),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DamageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            DamageType::Kinetic => write!(f, "kinetic"),
+            DamageType::Electric => write!(f, "electric"),
+            DamageType::Thermal => write!(f, "thermal"),
+            DamageType::Emp => write!(f, "EMP"),
+        }
+    }
+}
+
+///Percentage of each damage type that is shrugged off, negative values are weaknesses. Worn items add theirs to
+/// their owner's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resistances {
+    pub values: HashMap<DamageType, i32>,
 }
 
+///Effects fired at whoever the owner (or the owner's weapon or armour) hits in melee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnHit {
+    pub effects: HashMap<String, String>,
+}
+
+///Effects fired back at whoever hits the owner (or the owner of this armour) in melee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnDamaged {
+    pub effects: HashMap<String, String>,
+}
+
+///Rounds are only fired from the magazine, reloading fills it from the ammo in the owner's quiver
+#[derive(Serialize, Deserialize)]
 pub struct RangedWeapon {
     pub range: i32,
 
     pub damage: DiceType,
+    ///Only ammo of the same caliber can be loaded
+    pub caliber: String,
+    pub magazine: i32,
+    pub rounds: i32,
+    ///The kind of round currently in the magazine
+    pub loaded: Option<AmmoRound>,
+}
+
+///A box of ammunition, goes in the quiver slot and is used up by reloading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ammo {
+    pub caliber: String,
+    pub rounds: i32,
+    pub round: AmmoRound,
+}
+
+///What each round adds to the shot, effects fire at whoever it hits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmmoRound {
+    pub name: String,
+    pub damage_bonus: i32,
+    pub damage_type: DamageType,
+    pub effects: HashMap<String, String>,
 }
+#[derive(Serialize, Deserialize)]
 pub struct Usable {}
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 ///Points towards effect spawner to activate when interacted with
 pub struct Interactable;
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct EffectSpawner {
     pub owner: Entity,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EffectSpawnerPrefab {
     pub prefab: HashMap<String, String>,
 }
@@ -136,7 +243,7 @@ pub struct WantsToEquipItem {
     pub item: Entity,
     pub slot: EquipmentSlot,
 }
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Attribute {
     pub base: i32,
     pub bonuses: i32,
@@ -169,7 +276,7 @@ impl Attribute {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub enum EquipmentSlot {
     Head,
@@ -183,45 +290,108 @@ pub enum EquipmentSlot {
     Quiver,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct GrantsAttribute {
     pub target_attribute: StatType,
     pub modifier: i32,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct StatusEffect {
     pub target: Entity,
     pub source: Entity,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GrantsStatus {
     pub effects: HashMap<String, String>,
     pub duration: Option<i32>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct EffectDuration {
     pub rounds: i32,
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+///What happens when a status with the same name lands on someone who already has it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StackingPolicy {
+    ///Resets the duration
+    Refresh,
+    ///Resets the duration and adds another stack, every stack multiplies how strong the status is
+    Intensify,
+    ///The new status is thrown away
+    Ignore,
+}
+
+impl StackingPolicy {
+    pub fn parse(value: &str) -> Option<StackingPolicy> {
+        match value {
+            "refresh" => Some(StackingPolicy::Refresh),
+            "stack" => Some(StackingPolicy::Intensify),
+            "ignore" => Some(StackingPolicy::Ignore),
+            _ => None,
+        }
+    }
+}
+
+///Statuses without a name never interact with each other
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedStatus {
+    pub name: String,
+    pub stacking: StackingPolicy,
+    pub stacks: i32,
+}
+
+///Damage dealt to the status's target at the start of every round, untyped damage ignores resistances
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DamageOverTime {
+    pub amount: i32,
+    pub damage_type: Option<DamageType>,
+}
+
+///Energy drained from the status's target at the start of every round, slowing them down
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EnergyDrain {
+    pub amount: i32,
+}
+
+///The status's target loses their turns
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Stunned;
+
+///Takes this much off the status's target's vision range
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Blinded {
+    pub range: i32,
+}
+
+///The status's target stumbles in a random direction half of the time
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Confused;
+
+///Effects fired at the status's target when it runs out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnExpire {
+    pub effects: HashMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Equipped {
     pub owner: Entity,
     pub slot: EquipmentSlot,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Equippable {
     pub slot: EquipmentSlot,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct InContainer {
     pub owner: Entity,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Renderable {
     pub glyph: String,
     pub fg: bracket_lib::color::RGB,
@@ -229,7 +399,7 @@ pub struct Renderable {
     pub order: i32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Name {
     pub name: String,
 }
@@ -250,28 +420,39 @@ impl Renderable {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct AoE {
     pub radius: i32,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct RangedTargetting {
     pub range: i32,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct DamageEffect {
     pub damage_amount: i32,
+    pub damage_type: DamageType,
 }
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Item {}
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+///In kilograms
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Weight {
+    pub value: f32,
+}
+///Base price in credits, what vendors actually charge depends on who they're dealing with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Value {
+    pub credits: i32,
+}
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct HealingEffect {
     pub healing_amount: i32,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct GrantStat {
     pub stat: StatType,
     pub amount: i32,
@@ -281,26 +462,37 @@ pub struct GrantStat {
 pub struct WantsToPickupItem {
     pub item: Entity,
 }
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Consumable {}
+#[derive(Serialize, Deserialize)]
 pub struct Monster {}
+#[derive(Serialize, Deserialize)]
+pub struct Turret {}
+///Entity has been shut down (e.g. through hacking) and doesn't get any turns
+#[derive(Serialize, Deserialize)]
+pub struct Disabled {}
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct WantsToUseItem {
     pub item: Entity,
     pub target: Option<Point>,
 }
+#[derive(Serialize, Deserialize)]
 pub struct BlocksTiles {}
 
 pub struct Attack {
     pub target: Entity,
 }
 
+///Marks something the player has hurt so that its death is put down to them
+#[derive(Serialize, Deserialize)]
+pub struct HurtByPlayer {}
+
 /// This is used to mark all the damage that an entity will take that is processed by the damage_system
 pub struct TakeDamage {
     pub damage_to_take: Vec<i32>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FoV {
     pub visible_tiles: Vec<Point>,
     pub range: i32,
@@ -0,0 +1,292 @@
+use std::collections::BTreeSet;
+
+use bracket_lib::random::DiceType;
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::Interactable,
+    raws::{self, Recipe, RAWS},
+    spawns::spawning_system::spawn_item_in_backpack,
+    statistics::{skill_check, Skill, StatType},
+    InContainer, Name, Position, State,
+};
+
+///Recipes the entity knows how to make, by name
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct KnownRecipes {
+    pub recipes: BTreeSet<String>,
+}
+
+///Props that can be picked through for parts, rolls how many of each item are found
+#[derive(Serialize, Deserialize)]
+pub struct Salvage {
+    pub yields: Vec<(String, DiceType)>,
+}
+
+///Prop that recipes needing a workbench have to be made next to
+#[derive(Serialize, Deserialize)]
+pub struct Workbench;
+
+///Schematics teach the recipe to whoever reads them
+#[derive(Serialize, Deserialize)]
+pub struct TeachesRecipe {
+    pub recipe: String,
+}
+
+impl KnownRecipes {
+    ///Every recipe the raws mark as known from the start
+    pub fn starting() -> KnownRecipes {
+        KnownRecipes {
+            recipes: raws::known_recipes(&RAWS.lock().unwrap())
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+///Known recipes in the order they're listed in the crafting window
+pub fn known_recipes(state: &State, crafter: Entity) -> Vec<Recipe> {
+    let Ok(known) = state.world.get::<&KnownRecipes>(crafter) else {
+        return Vec::new();
+    };
+    let raws = RAWS.lock().unwrap();
+
+    known
+        .recipes
+        .iter()
+        .filter_map(|name| raws.get_recipe(name))
+        .collect()
+}
+
+///Returns whether the recipe was new to the learner
+pub fn learn_recipe(state: &mut State, learner: Entity, recipe: &str) -> bool {
+    let Ok(mut known) = state.world.get::<&mut KnownRecipes>(learner) else {
+        return false;
+    };
+
+    known.recipes.insert(recipe.to_string())
+}
+
+///Salvageable prop the player is walking into at the given tile, if there is one
+pub fn salvage_at(state: &mut State, idx: usize) -> Option<Entity> {
+    let width = state.map.map_width;
+
+    state
+        .world
+        .query_mut::<(&Salvage, &Position)>()
+        .into_iter()
+        .find(|(_ent, (_, pos))| (pos.y * width + pos.x) as usize == idx)
+        .map(|(ent, _)| ent)
+}
+
+///Workbench the player is trying to use at the given tile, if there is one
+pub fn workbench_at(state: &mut State, idx: usize) -> Option<Entity> {
+    let width = state.map.map_width;
+
+    state
+        .world
+        .query_mut::<(&Interactable, &Workbench, &Position)>()
+        .into_iter()
+        .find(|(_ent, (_, _, pos))| (pos.y * width + pos.x) as usize == idx)
+        .map(|(ent, _)| ent)
+}
+
+///Whether there's a workbench on or next to the crafter's tile
+pub fn near_workbench(state: &State, crafter: Entity) -> bool {
+    let Ok(crafter_pos) = state.world.get::<&Position>(crafter).map(|pos| *pos) else {
+        return false;
+    };
+
+    state
+        .world
+        .query::<(&Workbench, &Position)>()
+        .iter()
+        .any(|(_ent, (_, pos))| {
+            (pos.x - crafter_pos.x).abs() <= 1 && (pos.y - crafter_pos.y).abs() <= 1
+        })
+}
+
+///How many items with the given name are in the owner's backpack
+pub fn carried_count(state: &State, owner: Entity, item: &str) -> i32 {
+    state
+        .world
+        .query::<(&Name, &InContainer)>()
+        .iter()
+        .filter(|(_ent, (name, cont))| cont.owner == owner && name.name == item)
+        .count() as i32
+}
+
+///Ingredients the crafter is short of along with how many more of each they need
+pub fn missing_ingredients(state: &State, crafter: Entity, recipe: &Recipe) -> Vec<(String, i32)> {
+    recipe
+        .ingredients
+        .iter()
+        .filter_map(|(ingredient, needed)| {
+            let short = needed - carried_count(state, crafter, ingredient);
+            (short > 0).then(|| (ingredient.clone(), short))
+        })
+        .collect()
+}
+
+pub fn can_craft(state: &State, crafter: Entity, recipe: &Recipe) -> bool {
+    missing_ingredients(state, crafter, recipe).is_empty()
+        && (!recipe.workbench.unwrap_or(false) || near_workbench(state, crafter))
+}
+
+///Takes the given number of the item out of the owner's backpack
+pub fn use_up(state: &mut State, owner: Entity, item: &str, amount: i32) {
+    let used = state
+        .world
+        .query::<(&Name, &InContainer)>()
+        .iter()
+        .filter(|(_ent, (name, cont))| cont.owner == owner && name.name == item)
+        .map(|(ent, _)| ent)
+        .take(amount.max(0) as usize)
+        .collect::<Vec<_>>();
+
+    for ent in used {
+        let _ = state.world.despawn(ent);
+    }
+}
+
+///Picks through the prop, putting whatever the dice turn up into the salvager's backpack
+pub fn salvage(state: &mut State, salvager: Entity, prop: Entity) {
+    let Ok(yields) = state
+        .world
+        .get::<&Salvage>(prop)
+        .map(|salvage| salvage.yields.clone())
+    else {
+        return;
+    };
+    let prop_name = state
+        .world
+        .get::<&Name>(prop)
+        .map_or("junk".to_string(), |name| name.name.clone());
+
+    let mut found = Vec::new();
+    for (item, dice) in yields.iter() {
+        let amount = state.rng.roll(*dice);
+        for _ in 0..amount {
+            spawn_item_in_backpack(state, item, salvager);
+        }
+        if amount > 0 {
+            found.push(format!("{} {}", amount, item));
+        }
+    }
+
+    let _ = state.world.despawn(prop);
+
+    if found.is_empty() {
+        state.game_log.add_log(format!(
+            "You pick through the {} but there's nothing worth keeping.",
+            prop_name
+        ));
+    } else {
+        state.game_log.add_log(format!(
+            "You pick through the {} and find {}.",
+            prop_name,
+            found.join(", ")
+        ));
+    }
+}
+
+///Has a go at making the recipe, an Intelligence Crafting check against the recipe's difficulty decides if it works.
+/// A failed attempt ruins one of the ingredients. Returns false if the crafter couldn't even start
+pub fn craft(state: &mut State, crafter: Entity, recipe_name: &str) -> bool {
+    let Some(recipe) = RAWS.lock().unwrap().get_recipe(recipe_name) else {
+        return false;
+    };
+
+    let missing = missing_ingredients(state, crafter, &recipe);
+    if !missing.is_empty() {
+        let missing = missing
+            .iter()
+            .map(|(item, amount)| format!("{} {}", amount, item))
+            .collect::<Vec<_>>()
+            .join(", ");
+        state.game_log.add_log(format!(
+            "You need another {} to make {}.",
+            missing, recipe.result
+        ));
+        return false;
+    }
+    if recipe.workbench.unwrap_or(false) && !near_workbench(state, crafter) {
+        state
+            .game_log
+            .add_log(format!("You need a workbench to make {}.", recipe.result));
+        return false;
+    }
+
+    if skill_check(
+        Skill::Crafting,
+        StatType::Intelligence,
+        crafter,
+        state,
+        recipe.difficulty,
+    ) {
+        finish_recipe(state, crafter, &recipe);
+    } else if let Some(ingredient) = recipe.ingredients.keys().next() {
+        use_up(state, crafter, ingredient, 1);
+        state.game_log.add_log(format!(
+            "You botch the {} and ruin a {}.",
+            recipe.result, ingredient
+        ));
+    }
+
+    true
+}
+
+///Swaps the ingredients for the result, anyone who made something knows what it is
+fn finish_recipe(state: &mut State, crafter: Entity, recipe: &Recipe) {
+    for (ingredient, amount) in recipe.ingredients.iter() {
+        use_up(state, crafter, ingredient, *amount);
+    }
+
+    let count = recipe.count.unwrap_or(1);
+    for _ in 0..count {
+        spawn_item_in_backpack(state, &recipe.result, crafter);
+    }
+    state.identification.identify(&recipe.result);
+
+    state
+        .game_log
+        .add_log(format!("You make {} {}.", count, recipe.result));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::test_state;
+
+    #[test]
+    fn crafting_swaps_ingredients_for_the_result() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+
+        let recipe = RAWS.lock().unwrap().get_recipe("Pistol Ammo").unwrap();
+        assert!(known_recipes(&state, player)
+            .iter()
+            .any(|known| known.name == recipe.name));
+        assert!(!can_craft(&state, player, &recipe));
+
+        for (ingredient, amount) in recipe.ingredients.iter() {
+            for _ in 0..*amount {
+                spawn_item_in_backpack(&mut state, ingredient, player);
+            }
+        }
+        assert!(missing_ingredients(&state, player, &recipe).is_empty());
+
+        let before = carried_count(&state, player, &recipe.result);
+        finish_recipe(&mut state, player, &recipe);
+
+        assert!(recipe
+            .ingredients
+            .keys()
+            .all(|ingredient| carried_count(&state, player, ingredient) == 0));
+        assert_eq!(
+            carried_count(&state, player, &recipe.result),
+            before + recipe.count.unwrap_or(1)
+        );
+    }
+}
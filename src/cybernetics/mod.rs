@@ -0,0 +1,230 @@
+use std::fmt;
+
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{GrantStat, Interactable, StatusEffect},
+    identification::{identify_item, item_name},
+    statistics::StatType,
+    InContainer, Position, State,
+};
+
+///Where on the body an implant goes, kept apart from EquipmentSlot so implants never compete with worn gear
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImplantSlot {
+    Head,
+    Eyes,
+    Torso,
+    Arms,
+    Legs,
+    Nervous,
+}
+
+impl fmt::Display for ImplantSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ImplantSlot::Head => write!(f, "head"),
+            ImplantSlot::Eyes => write!(f, "eyes"),
+            ImplantSlot::Torso => write!(f, "torso"),
+            ImplantSlot::Arms => write!(f, "arms"),
+            ImplantSlot::Legs => write!(f, "legs"),
+            ImplantSlot::Nervous => write!(f, "nervous system"),
+        }
+    }
+}
+
+///An item that can be installed at a ripper-doc, the stats are granted for as long as it stays installed and
+/// include its side effects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Implant {
+    pub slot: ImplantSlot,
+    pub stats: Vec<GrantStat>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Installed {
+    pub owner: Entity,
+    pub slot: ImplantSlot,
+}
+
+///Lets an installed implant be activated, the effects to fire live on the implant entity like a consumable's
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImplantAbility {
+    pub name: String,
+    pub cooldown: i32,
+    pub remaining: i32,
+}
+
+impl ImplantAbility {
+    pub fn is_ready(&self) -> bool {
+        self.remaining <= 0
+    }
+}
+
+///Prop that implants can be installed at
+#[derive(Serialize, Deserialize)]
+pub struct RipperDoc;
+
+pub struct WantsToInstallImplant {
+    pub implant: Entity,
+}
+
+///Installs implants for every entity with WantsToInstallImplant, one implant per body location
+pub fn implant_install_system(state: &mut State) {
+    let to_install = state
+        .world
+        .query_mut::<&WantsToInstallImplant>()
+        .into_iter()
+        .map(|(ent, wants)| (ent, wants.implant))
+        .collect::<Vec<_>>();
+
+    for (ent, implant_ent) in to_install {
+        let _ = state.world.remove_one::<WantsToInstallImplant>(ent);
+
+        let Ok(implant) = state
+            .world
+            .get::<&Implant>(implant_ent)
+            .map(|i| (*i).clone())
+        else {
+            continue;
+        };
+        let name = item_name(state, implant_ent);
+
+        if slot_taken(state, ent, implant.slot) {
+            state.game_log.add_log(format!(
+                "There's already an implant in your {}, {} won't fit.",
+                implant.slot, name
+            ));
+            continue;
+        }
+
+        let _ = state.world.remove_one::<InContainer>(implant_ent);
+        state
+            .world
+            .insert_one(
+                implant_ent,
+                Installed {
+                    owner: ent,
+                    slot: implant.slot,
+                },
+            )
+            .expect("Couldn't install implant!");
+
+        //no duration so the bonuses stay for good, same as an equipped item's status
+        for stat in implant.stats.iter() {
+            state.world.spawn((
+                StatusEffect {
+                    source: implant_ent,
+                    target: ent,
+                },
+                *stat,
+            ));
+        }
+
+        state.game_log.add_log(format!(
+            "The ripper-doc installs {} in your {}.",
+            name, implant.slot
+        ));
+        identify_item(state, implant_ent);
+
+        if implant
+            .stats
+            .iter()
+            .any(|stat| stat.stat == StatType::MentalFortitude && stat.amount < 0)
+        {
+            state
+                .game_log
+                .add_log("You feel a little less human.".to_string());
+        }
+    }
+}
+
+pub fn tick_implant_cooldowns(state: &mut State) {
+    for (_ent, ability) in state.world.query_mut::<&mut ImplantAbility>() {
+        if ability.remaining > 0 {
+            ability.remaining -= 1;
+        }
+    }
+}
+
+fn slot_taken(state: &mut State, owner: Entity, slot: ImplantSlot) -> bool {
+    state
+        .world
+        .query_mut::<&Installed>()
+        .into_iter()
+        .any(|(_ent, installed)| installed.owner == owner && installed.slot == slot)
+}
+
+///Ripper-doc the player is trying to use at the given tile, if there is one
+pub fn ripper_doc_at(state: &mut State, idx: usize) -> Option<Entity> {
+    let width = state.map.map_width;
+
+    state
+        .world
+        .query_mut::<(&Interactable, &RipperDoc, &Position)>()
+        .into_iter()
+        .find(|(_ent, (_, _, pos))| (pos.y * width + pos.x) as usize == idx)
+        .map(|(ent, _)| ent)
+}
+
+///Implants in the owner's backpack that are waiting to be installed
+pub fn carried_implants(state: &mut State, owner: Entity) -> Vec<Entity> {
+    state
+        .world
+        .query_mut::<(&Implant, &InContainer)>()
+        .into_iter()
+        .filter(|(_ent, (_, cont))| cont.owner == owner)
+        .map(|(ent, _)| ent)
+        .collect()
+}
+
+///Installed implants belonging to the owner that have an ability, along with whether it's ready to use
+pub fn implant_abilities(state: &mut State, owner: Entity) -> Vec<(Entity, bool)> {
+    state
+        .world
+        .query_mut::<(&ImplantAbility, &Installed)>()
+        .into_iter()
+        .filter(|(_ent, (_, installed))| installed.owner == owner)
+        .map(|(ent, (ability, _))| (ent, ability.is_ready()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        spawns::spawning_system::spawn_item_in_backpack,
+        statistics::{stat_calculation_system, BaseStatistics},
+        utils::testing::test_state,
+    };
+
+    #[test]
+    fn installed_implants_grant_their_stats_and_side_effects() {
+        let mut state = test_state(7);
+        let player = state.player_ent.unwrap();
+
+        spawn_item_in_backpack(&mut state, &"Reflex Booster".to_string(), player);
+        spawn_item_in_backpack(&mut state, &"Reflex Booster".to_string(), player);
+        let implants = carried_implants(&mut state, player);
+        let before = *state.world.get::<&BaseStatistics>(player).unwrap();
+
+        for implant in implants.iter() {
+            state
+                .world
+                .insert_one(player, WantsToInstallImplant { implant: *implant })
+                .unwrap();
+            implant_install_system(&mut state);
+        }
+        stat_calculation_system(&mut state);
+
+        let after = *state.world.get::<&BaseStatistics>(player).unwrap();
+        //the second booster has nowhere to go so it stays in the backpack
+        assert_eq!(carried_implants(&mut state, player).len(), 1);
+        assert_eq!(after.dexterity.total, before.dexterity.base + 2);
+        assert_eq!(
+            after.mental_fortitude.total,
+            before.mental_fortitude.base - 2
+        );
+    }
+}
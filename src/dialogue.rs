@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crafting::{carried_count, use_up},
+    missions::{accept_mission, MissionStatus},
+    raws::{DialogueChoice, DialogueNode, RAWS},
+    reputation::{change_standing, hostile_to_player},
+    spawns::spawning_system::spawn_item_in_backpack,
+    statistics::{BaseStatistics, Pools, Skill, Skills, StatType},
+    trading::{add_credits, credits},
+    Faction, Name, Position, State,
+};
+
+///Everything a dialogue choice can be made to depend on, e.g. "stat": "intelligence:12" or "mission": "Rat Problem:done"
+pub const CONDITION_KEYS: [&str; 6] = [
+    "stat", "skill", "standing", "has_item", "credits", "mission",
+];
+
+///Everything picking a dialogue choice can do, e.g. "reputation": "townsfolk:5" or "fight": "Mindless"
+pub const OUTCOME_KEYS: [&str; 7] = [
+    "give_item",
+    "take_item",
+    "credits",
+    "reputation",
+    "fight",
+    "trade",
+    "offer_mission",
+];
+
+///Mob the player can talk to, following the named dialogue tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dialogue {
+    pub tree: String,
+}
+
+///Where a conversation goes after the player picks a reply
+#[derive(Debug, PartialEq, Eq)]
+pub enum Reply {
+    Continue { node: String },
+    Trade,
+    End,
+}
+
+///Everyone next to the entity that's willing to have a conversation
+pub fn talkers_near(state: &State, ent: Entity) -> Vec<Entity> {
+    let Ok(ent_pos) = state.world.get::<&Position>(ent).map(|pos| *pos) else {
+        return Vec::new();
+    };
+
+    state
+        .world
+        .query::<(&Dialogue, &Position)>()
+        .iter()
+        .filter(|(other, (_, pos))| {
+            *other != ent && (pos.x - ent_pos.x).abs() <= 1 && (pos.y - ent_pos.y).abs() <= 1
+        })
+        .map(|(other, _)| other)
+        .filter(|other| !hostile_to_player(state, *other))
+        .collect()
+}
+
+///Prop with something to say at the given tile, like a job board, walking into it starts the conversation
+pub fn talking_prop_at(state: &State, idx: usize) -> Option<Entity> {
+    let width = state.map.map_width;
+
+    state
+        .world
+        .query::<(&Dialogue, &Position)>()
+        .without::<&Pools>()
+        .iter()
+        .find(|(_ent, (_, pos))| (pos.y * width + pos.x) as usize == idx)
+        .map(|(ent, _)| ent)
+}
+
+///The node of the speaker's dialogue tree, the start of the conversation if no node is given
+pub fn dialogue_node(state: &State, speaker: Entity, node: Option<&str>) -> Option<DialogueNode> {
+    let tree = state.world.get::<&Dialogue>(speaker).ok()?.tree.clone();
+    let tree = RAWS.lock().unwrap().get_dialogue(&tree)?;
+
+    tree.nodes.get(node.unwrap_or(&tree.start)).cloned()
+}
+
+///Replies the talker is able to give at this point in the conversation
+pub fn available_choices(
+    state: &State,
+    talker: Entity,
+    node: &DialogueNode,
+) -> Vec<DialogueChoice> {
+    node.choices
+        .iter()
+        .filter(|choice| {
+            choice
+                .conditions
+                .iter()
+                .flatten()
+                .all(|(key, value)| condition_met(state, talker, key, value))
+        })
+        .cloned()
+        .collect()
+}
+
+fn condition_met(state: &State, talker: Entity, key: &str, value: &str) -> bool {
+    let split_amount = |value: &str| {
+        value
+            .split_once(':')
+            .and_then(|(name, amount)| Some((name.to_string(), amount.parse::<i32>().ok()?)))
+    };
+
+    match key {
+        "stat" => split_amount(value).is_some_and(|(stat, amount)| {
+            let Some(stat) = StatType::parse(&stat) else {
+                return false;
+            };
+            state
+                .world
+                .get::<&BaseStatistics>(talker)
+                .is_ok_and(|stats| stats.get_stat(stat).total >= amount)
+        }),
+        "skill" => split_amount(value).is_some_and(|(skill, amount)| {
+            let Some(skill) = Skill::parse(&skill) else {
+                return false;
+            };
+            state
+                .world
+                .get::<&Skills>(talker)
+                .is_ok_and(|skills| skills.level(skill) >= amount)
+        }),
+        "standing" => split_amount(value)
+            .is_some_and(|(faction, amount)| state.reputation.standing(&faction) >= amount),
+        "has_item" => carried_count(state, talker, value) > 0,
+        "credits" => value
+            .parse::<i32>()
+            .is_ok_and(|amount| credits(state, talker) >= amount),
+        "mission" => value.split_once(':').is_some_and(|(mission, status)| {
+            MissionStatus::parse(status) == Some(state.missions.status(mission))
+        }),
+        _ => false,
+    }
+}
+
+///Makes the reply's outcomes happen and works out where the conversation goes next. Starting a fight always ends it
+pub fn choose(
+    state: &mut State,
+    talker: Entity,
+    speaker: Entity,
+    choice: &DialogueChoice,
+) -> Reply {
+    let outcomes = choice.outcomes.clone().unwrap_or_default();
+    apply_outcomes(state, talker, speaker, &outcomes);
+
+    if outcomes.contains_key("fight") {
+        Reply::End
+    } else if outcomes.contains_key("trade") {
+        Reply::Trade
+    } else {
+        match &choice.next {
+            Some(node) => Reply::Continue { node: node.clone() },
+            None => Reply::End,
+        }
+    }
+}
+
+fn apply_outcomes(
+    state: &mut State,
+    talker: Entity,
+    speaker: Entity,
+    outcomes: &BTreeMap<String, String>,
+) {
+    let speaker_name = state
+        .world
+        .get::<&Name>(speaker)
+        .map_or("They".to_string(), |name| name.name.clone());
+
+    for (key, value) in outcomes.iter() {
+        match key.as_str() {
+            "give_item" => {
+                spawn_item_in_backpack(state, value, talker);
+                let item = state.identification.display_name(value);
+                state
+                    .game_log
+                    .add_log(format!("{} hands you a {}.", speaker_name, item));
+            }
+            "take_item" => {
+                use_up(state, talker, value, 1);
+                let item = state.identification.display_name(value);
+                state.game_log.add_log(format!("You hand over a {}.", item));
+            }
+            "credits" => {
+                let amount = value.parse::<i32>().unwrap_or(0);
+                add_credits(state, talker, amount);
+                if amount < 0 {
+                    state
+                        .game_log
+                        .add_log(format!("You pay {} credits.", -amount));
+                } else {
+                    state
+                        .game_log
+                        .add_log(format!("You're given {} credits.", amount));
+                }
+            }
+            "reputation" => {
+                if let Some((faction, amount)) = value.split_once(':') {
+                    change_standing(state, faction, amount.parse().unwrap_or(0));
+                }
+            }
+            //the speaker sides with a faction that'll fight the player
+            "fight" => {
+                let _ = state.world.insert_one(
+                    speaker,
+                    Faction {
+                        name: value.clone(),
+                    },
+                );
+                state
+                    .game_log
+                    .add_log(format!("{} turns on you!", speaker_name));
+            }
+            "offer_mission" => accept_mission(state, value),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::test_state;
+
+    #[test]
+    fn choices_depend_on_what_the_player_has() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+
+        let choice = DialogueChoice {
+            text: "Here, have a ration.".to_string(),
+            next: Some("thanks".to_string()),
+            conditions: Some(BTreeMap::from([(
+                "has_item".to_string(),
+                "Ration".to_string(),
+            )])),
+            outcomes: Some(BTreeMap::from([(
+                "take_item".to_string(),
+                "Ration".to_string(),
+            )])),
+        };
+        let node = DialogueNode {
+            text: "Spare some food?".to_string(),
+            choices: vec![choice.clone()],
+        };
+
+        assert_eq!(
+            available_choices(&state, player, &node),
+            vec![choice.clone()]
+        );
+        assert_eq!(
+            choose(&mut state, player, player, &choice),
+            Reply::Continue {
+                node: "thanks".to_string()
+            }
+        );
+        assert!(available_choices(&state, player, &node).is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use bracket_lib::prelude::Point;
+use hecs::{Entity, World};
+
+use crate::{
+    ai::MyTurn,
+    components::{EffectSpawner, Equipped, InContainer, StatusEffect},
+    cybernetics::Installed,
+    maps::{map::Map, TileType},
+    networks::{connect_player_deck, NetworkMap, ParentNode},
+    State,
+};
+
+///A floor the player has left along with everything that was on it
+pub struct StoredLevel {
+    pub map: Map,
+    pub network_map: NetworkMap,
+    ///entities keep the handles they had in the main world so references to them stay valid
+    pub world: World,
+    ///where the player was standing when they left, used if the level has no stairs to arrive on
+    pub player_pos: Point,
+}
+
+///Keeps every visited level so that floors persist between visits
+#[derive(Default)]
+pub struct DungeonMaster {
+    levels: HashMap<i32, StoredLevel>,
+}
+
+impl DungeonMaster {
+    pub fn new() -> DungeonMaster {
+        DungeonMaster::default()
+    }
+
+    pub fn insert(&mut self, depth: i32, level: StoredLevel) {
+        self.levels.insert(depth, level);
+    }
+
+    pub fn take(&mut self, depth: i32) -> Option<StoredLevel> {
+        self.levels.remove(&depth)
+    }
+
+    pub fn levels(&self) -> impl Iterator<Item = (i32, &StoredLevel)> {
+        self.levels.iter().map(|(depth, level)| (*depth, level))
+    }
+}
+
+///Moves the current floor and all of its entities into the dungeon master, only the player and what they own stays
+/// in the world
+pub fn store_level(state: &mut State) {
+    let turns = state
+        .world
+        .query_mut::<&MyTurn>()
+        .into_iter()
+        .map(|(ent, _turn)| ent)
+        .collect::<Vec<_>>();
+    for turn in turns.iter() {
+        let _ = state.world.remove_one::<MyTurn>(*turn);
+    }
+
+    let mut stored = World::new();
+    for entity in level_entities(state) {
+        let components = state
+            .world
+            .take(entity)
+            .expect("Can't store entity that has been marked as part of the level!");
+        stored.spawn_at(entity, components);
+        //an empty placeholder keeps the handle reserved so nothing spawned later can take it
+        state.world.spawn_at(entity, ());
+    }
+
+    let level = StoredLevel {
+        map: state.map.clone(),
+        network_map: std::mem::replace(&mut state.network_map, NetworkMap::empty()),
+        world: stored,
+        player_pos: state.player_pos,
+    };
+
+    state.dungeon.insert(state.map.depth, level);
+}
+
+///Brings a previously visited floor back, placing the player on the first tile of the arrival type.
+/// Returns false if the depth hasn't been visited yet
+pub fn restore_level(state: &mut State, depth: i32, arrive_on: TileType) -> bool {
+    let Some(mut level) = state.dungeon.take(depth) else {
+        return false;
+    };
+
+    let entities = level
+        .world
+        .iter()
+        .map(|ent| ent.entity())
+        .collect::<Vec<_>>();
+    for entity in entities {
+        //replaces the placeholder that was holding the handle
+        state
+            .world
+            .spawn_at(entity, level.world.take(entity).unwrap());
+    }
+
+    state.map = level.map;
+    state.network_map = level.network_map;
+    state.player_pos = state
+        .map
+        .map
+        .iter()
+        .position(|tile| *tile == arrive_on)
+        .map(|idx| state.map.idx_to_pos(idx))
+        .unwrap_or(level.player_pos);
+
+    connect_player_deck(state);
+
+    true
+}
+
+///Everything on the current floor that doesn't belong to the player
+fn level_entities(state: &State) -> Vec<Entity> {
+    let player = state
+        .player_ent
+        .expect("Couldn't find player entity when storing the level!");
+
+    state
+        .world
+        .iter()
+        .filter(|entity| {
+            //placeholders for floors that are already stored
+            if entity.component_types().next().is_none() {
+                return false;
+            }
+
+            let owned_by_player = entity.entity() == player
+                || entity
+                    .get::<&InContainer>()
+                    .is_some_and(|cont| cont.owner == player)
+                || entity
+                    .get::<&EffectSpawner>()
+                    .is_some_and(|spawner| spawner.owner == player)
+                || entity
+                    .get::<&ParentNode>()
+                    .is_some_and(|node| node.ent == player)
+                || entity
+                    .get::<&Equipped>()
+                    .is_some_and(|eq| eq.owner == player)
+                || entity
+                    .get::<&Installed>()
+                    .is_some_and(|installed| installed.owner == player)
+                || entity
+                    .get::<&StatusEffect>()
+                    .is_some_and(|status| status.target == player);
+
+            !owned_by_player
+        })
+        .map(|entity| entity.entity())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{go_down_stairs, go_up_stairs, utils::testing::test_state, Position};
+
+    #[test]
+    fn floors_persist_between_visits() {
+        let mut state = test_state(42);
+        go_down_stairs(&mut state);
+
+        let player = state.player_ent.unwrap();
+        let level_positions = |state: &mut State| {
+            let mut positions = state
+                .world
+                .query::<&Position>()
+                .iter()
+                .filter(|(ent, _pos)| *ent != player)
+                .map(|(ent, pos)| (ent, pos.as_tuple()))
+                .collect::<Vec<_>>();
+            positions.sort();
+            positions
+        };
+
+        let depth_one = state.map.map.clone();
+        let positions = level_positions(&mut state);
+
+        go_down_stairs(&mut state);
+        assert_eq!(state.map.depth, 2);
+        assert!(state.world.get::<&Position>(positions[0].0).is_err());
+
+        go_up_stairs(&mut state);
+        assert_eq!(state.map.depth, 1);
+        assert_eq!(state.map.map, depth_one);
+        assert_eq!(level_positions(&mut state), positions);
+        assert_eq!(
+            state.map.map[state.map.xy_idx(state.player_pos.x, state.player_pos.y)],
+            TileType::DownStairs
+        );
+    }
+}
@@ -2,9 +2,9 @@ use bracket_lib::prelude::{BTerm, Point};
 use hecs::Entity;
 use macroquad::time::get_frame_time;
 
-use crate::{projectile::{projectile_system::*, ProjectileType}, Creator, Hidden, Map, ProgramState, Projectile, State};
+use crate::{projectile::{projectile_system::*, ProjectileType}, Creator, Hidden, Map, ProgramState, State};
 
-use super::{add_effect, Particle, ANIMATIONQUEUE};
+use super::{add_effect, run_effect_queue, Particle, ANIMATIONQUEUE};
 
 
 #[derive(Clone, PartialEq)]
@@ -19,11 +19,32 @@ pub struct Animation
 }
 
 pub fn run_animation_queue(state : &mut State)
+{
+    advance_animations(state, get_frame_time()*1000.);
+}
+
+///Plays every animation through to the end straight away, a headless run has no frames to spread them out over
+pub fn finish_animations(state : &mut State)
+{
+    loop
+    {
+        run_effect_queue(state);
+        advance_animations(state, f32::INFINITY);
+
+        if state.current_state != ProgramState::PlayAnimation { break; }
+    }
+}
+
+fn advance_animations(state : &mut State, elapsed_ms : f32)
 {
     //spawns animations added from effect queue
     for (anim, ranged) in ANIMATIONQUEUE.lock().unwrap().iter()
     {
-        state.world.spawn((anim.clone(), ProjectileType::Missile, ProjectileUpdated{}, Projectile{damage: ranged.damage}));
+        match ranged
+        {
+            Some(projectile) => { state.world.spawn((anim.clone(), ProjectileType::Missile, ProjectileUpdated{}, projectile.clone())); }
+            None => { state.world.spawn((anim.clone(),)); }
+        }
     }
     //clears animation queue list
     ANIMATIONQUEUE.lock().unwrap().clear();
@@ -33,7 +54,7 @@ pub fn run_animation_queue(state : &mut State)
     //updates animations in the animation queue
     for (id, anim) in state.world.query_mut::<&mut Animation>()
     {
-        anim.current_step_time -= get_frame_time()*1000.;
+        anim.current_step_time -= elapsed_ms;
 
         if anim.current_step_time < 0.
         {
@@ -4,11 +4,28 @@ use bracket_lib::{
 };
 use hecs::Entity;
 
-use crate::{statistics::Pools, Name};
+use crate::{
+    ai::alert,
+    components::{DamageType, HurtByPlayer, Resistances},
+    reputation,
+    statistics::Pools,
+    Equipped, Faction, Name,
+};
 
 use super::{add_effect, entity_position, EffectSpawner, EffectType, State, Targets};
 
+///No amount of armour makes something completely immune
+const MAX_RESISTANCE: i32 = 90;
+
 pub fn inflict_damage(state: &mut State, damage: &EffectSpawner, target: Entity) {
+    let resistance = match damage.effect_type {
+        EffectType::Damage {
+            damage_type: Some(damage_type),
+            ..
+        } => total_resistance(state, target, damage_type),
+        _ => 0,
+    };
+
     let query = state.world.query_one_mut::<(&mut Pools, &Name)>(target);
 
     let mut ent_name = String::from("");
@@ -16,7 +33,8 @@ pub fn inflict_damage(state: &mut State, damage: &EffectSpawner, target: Entity)
 
     match query {
         Ok((pools, name)) => {
-            if let EffectType::Damage { amount } = damage.effect_type {
+            if let EffectType::Damage { amount, .. } = damage.effect_type {
+                let amount = std::cmp::max(amount.min(1), amount - amount * resistance / 100);
                 pools.hitpoints.damage(amount);
 
                 ent_name = name.name.clone();
@@ -38,19 +56,62 @@ pub fn inflict_damage(state: &mut State, damage: &EffectSpawner, target: Entity)
         }
         Err(_) => {}
     }
+    if let Some(creator) = damage.creator.filter(|_| ent_name != "") {
+        if Some(creator) == state.player_ent && target != creator {
+            let _ = state.world.insert_one(target, HurtByPlayer {});
+            alert(state, target, creator);
+        }
+    }
+
     if ent_name != "" {
-        let msg = format!("{} took {} damage!", ent_name, dmg_num);
+        let msg = match damage.effect_type {
+            EffectType::Damage {
+                damage_type: Some(damage_type),
+                ..
+            } if resistance > 0 => format!(
+                "{} took {} {} damage ({}% resisted)!",
+                ent_name, dmg_num, damage_type, resistance
+            ),
+            EffectType::Damage {
+                damage_type: Some(damage_type),
+                ..
+            } if resistance < 0 => format!(
+                "{} took {} {} damage, it's weak to it!",
+                ent_name, dmg_num, damage_type
+            ),
+            _ => format!("{} took {} damage!", ent_name, dmg_num),
+        };
         state.game_log.add_log(msg.clone());
         console::log(msg);
     }
 }
 
+///Percentage of the damage type the target shrugs off, from its own resistances and everything it has equipped
+pub fn total_resistance(state: &State, target: Entity, damage_type: DamageType) -> i32 {
+    let own = state
+        .world
+        .get::<&Resistances>(target)
+        .map_or(0, |res| *res.values.get(&damage_type).unwrap_or(&0));
+
+    let worn: i32 = state
+        .world
+        .query::<(&Equipped, &Resistances)>()
+        .iter()
+        .filter(|(_ent, (equipped, _res))| equipped.owner == target)
+        .map(|(_ent, (_equipped, res))| *res.values.get(&damage_type).unwrap_or(&0))
+        .sum();
+
+    (own + worn).clamp(-100, MAX_RESISTANCE)
+}
+
 pub fn heal_damage(state: &mut State, heal: &EffectSpawner, target: Entity) {
     let query = state.world.query_one_mut::<&mut Pools>(target);
+    let mut patched_up = false;
 
     match query {
         Ok(pools) => {
             if let EffectType::Healing { amount } = heal.effect_type {
+                patched_up = pools.hitpoints.current_value < pools.hitpoints.max_value;
                 pools.hitpoints.restore(amount);
 
                 add_effect(
@@ -69,4 +130,44 @@ pub fn heal_damage(state: &mut State, heal: &EffectSpawner, target: Entity) {
         }
         Err(_) => {}
     }
+
+    //topping up someone who wasn't hurt doesn't earn any goodwill
+    if patched_up && heal.creator == state.player_ent && Some(target) != state.player_ent {
+        let faction = state.world.get::<&Faction>(target).map(|f| f.name.clone());
+        if let Ok(faction) = faction {
+            reputation::player_helped(state, &faction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{new_state, EquipmentSlot};
+
+    #[test]
+    fn worn_resistances_stack_with_the_wearers_own() {
+        let mut state = new_state(None, 1);
+        let target = state.world.spawn((Resistances {
+            values: HashMap::from([(DamageType::Emp, -100)]),
+        },));
+        state.world.spawn((
+            Equipped {
+                owner: target,
+                slot: EquipmentSlot::Body,
+            },
+            Resistances {
+                values: HashMap::from([(DamageType::Emp, 30), (DamageType::Kinetic, 95)]),
+            },
+        ));
+
+        assert_eq!(total_resistance(&state, target, DamageType::Emp), -70);
+        assert_eq!(
+            total_resistance(&state, target, DamageType::Kinetic),
+            MAX_RESISTANCE
+        );
+        assert_eq!(total_resistance(&state, target, DamageType::Thermal), 0);
+    }
 }
@@ -1,5 +1,10 @@
 use crate::{
-    effects::{door::toggle_door, status::spawn_status_effect},
+    components::DamageType,
+    effects::{
+        door::toggle_door,
+        status::{drain_energy, spawn_status_effect},
+    },
+    gamelog::DEBUGLOG,
     map_indexing::SPATIAL_INDEX,
     particles::ParticleBuilder,
     Projectile, State,
@@ -13,6 +18,7 @@ use std::{
     collections::{HashMap, VecDeque},
     sync::Mutex,
 };
+pub use triggers::combat_trigger;
 use triggers::{
     command_trigger, entry_trigger_fire, interact_trigger, item_trigger, ranged_trigger,
 };
@@ -28,17 +34,24 @@ mod targetting;
 mod triggers;
 pub use animation::*;
 pub use particles::*;
+pub use status::{
+    affected_by, blindness, confused_direction, has_status, named_statuses, status_stacks,
+    tick_status_effects,
+};
 pub use targetting::*;
 
 lazy_static! {
     pub static ref EFFECTQUEUE: Mutex<VecDeque<EffectSpawner>> = Mutex::new(VecDeque::new());
-    pub static ref ANIMATIONQUEUE: Mutex<Vec<(Animation, Projectile)>> = Mutex::new(Vec::new());
+    ///Animations with a projectile hurt whatever they pass through, the rest are just for show
+    pub static ref ANIMATIONQUEUE: Mutex<Vec<(Animation, Option<Projectile>)>> = Mutex::new(Vec::new());
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum EffectType {
     Damage {
         amount: i32,
+        ///None for damage that ignores resistances, like starving
+        damage_type: Option<DamageType>,
     },
     Particle {
         glyph: String,
@@ -88,6 +101,13 @@ pub enum EffectType {
         effects: HashMap<String, String>,
         duration: Option<i32>,
     },
+    DrainEnergy {
+        amount: i32,
+    },
+    ///Shows the run's seed, or restarts the run with a new one
+    Seed {
+        seed: Option<u64>,
+    },
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -138,6 +158,11 @@ fn target_applicator(state: &mut State, effect: &EffectSpawner) {
         ranged_trigger(effect.creator, item, &effect.targets, state);
     } else if let EffectType::ConsoleCommand { command } = &effect.effect_type {
         command_trigger(command.clone(), state);
+    } else if let EffectType::Seed { seed } = effect.effect_type {
+        match seed {
+            Some(seed) => crate::new_run(state, seed),
+            None => DEBUGLOG.add_log(format!("Seed: {}", state.seed)),
+        }
     } else {
         match &effect.targets {
             Targets::Tile { tile_idx } => affect_tile(state, effect, *tile_idx),
@@ -195,6 +220,7 @@ fn affect_entity(state: &mut State, effect: &EffectSpawner, target: Entity) {
         EffectType::PlayerDecendFloor { .. } => player_decend_floor(state, effect),
         EffectType::ToggleDoor => toggle_door(state, effect, target),
         EffectType::StatusEffect { .. } => spawn_status_effect(state, effect, target),
+        EffectType::DrainEnergy { .. } => drain_energy(state, effect, target),
         _ => {}
     }
 }
@@ -1,8 +1,9 @@
 use bracket_lib::{color::RGB, prelude::FontCharType};
+use serde::{Deserialize, Serialize};
 
 use crate::State;
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Particle {
     pub glyph: String,
     pub fg: RGB,
@@ -10,17 +11,17 @@ pub struct Particle {
     pub lifetime: f32,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParticleBurst {
     pub particle: Particle,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParticleLine {
     pub particle: Particle,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParticleAnimation {
     pub particle: Particle,
 }
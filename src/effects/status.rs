@@ -1,14 +1,423 @@
-use hecs::Entity;
+use std::collections::HashSet;
+
+use bracket_lib::prelude::Point;
+use hecs::{Component, Entity};
 
 use crate::{
-    effects::{EffectSpawner, EffectType},
+    ai::Energy,
+    components::{
+        Blinded, Confused, DamageOverTime, EffectDuration, EnergyDrain, NamedStatus, OnExpire,
+        StackingPolicy, StatusEffect,
+    },
+    effects::{add_effect, combat_trigger, EffectSpawner, EffectType, Targets},
     spawns::spawning_system::spawn_effect_entity,
-    State,
+    FoV, Name, State,
 };
 
+///Most stacks an intensifying status can build up to
+pub const MAX_STATUS_STACKS: i32 = 5;
+
 pub fn spawn_status_effect(state: &mut State, effect: &EffectSpawner, target: Entity) {
-    //
     if let EffectType::StatusEffect { effects, duration } = effect.effect_type.clone() {
+        if let Some(name) = effects.get("status") {
+            if let Some(existing) = find_named_status(state, target, name) {
+                stack_status(state, existing, duration);
+                return;
+            }
+
+            let target_name = state
+                .world
+                .get::<&Name>(target)
+                .map_or("Something".to_string(), |name| name.name.clone());
+            state
+                .game_log
+                .add_log(format!("{} is {}!", target_name, name));
+        }
+
         spawn_effect_entity(state, effects, duration, effect.creator.unwrap(), target);
+        refresh_vision(state, target);
+    }
+}
+
+///Status with the given name the target already has, if any
+fn find_named_status(state: &State, target: Entity, name: &str) -> Option<Entity> {
+    state
+        .world
+        .query::<(&StatusEffect, &NamedStatus)>()
+        .iter()
+        .find(|(_ent, (status, named))| status.target == target && named.name == name)
+        .map(|(ent, _)| ent)
+}
+
+///Applies the existing status's stacking policy instead of giving the target the same status twice
+fn stack_status(state: &mut State, existing: Entity, duration: Option<i32>) {
+    let Ok(mut named) = state.world.get::<&mut NamedStatus>(existing) else {
+        return;
+    };
+
+    match named.stacking {
+        StackingPolicy::Ignore => return,
+        StackingPolicy::Refresh => {}
+        StackingPolicy::Intensify => named.stacks = (named.stacks + 1).min(MAX_STATUS_STACKS),
+    }
+    std::mem::drop(named);
+
+    if let (Some(rounds), Ok(mut current)) =
+        (duration, state.world.get::<&mut EffectDuration>(existing))
+    {
+        current.rounds = current.rounds.max(rounds);
+    }
+}
+
+///How many times over the status applies, statuses without a name only ever have the one stack
+pub fn status_stacks(state: &State, status: Entity) -> i32 {
+    state
+        .world
+        .get::<&NamedStatus>(status)
+        .map_or(1, |named| named.stacks)
+}
+
+///Name, stacks and rounds left of every named status on the target
+pub fn named_statuses(state: &State, target: Entity) -> Vec<(String, i32, Option<i32>)> {
+    state
+        .world
+        .query::<(&StatusEffect, &NamedStatus, Option<&EffectDuration>)>()
+        .iter()
+        .filter(|(_ent, (status, _, _))| status.target == target)
+        .map(|(_ent, (_, named, duration))| {
+            (
+                named.name.clone(),
+                named.stacks,
+                duration.map(|duration| duration.rounds),
+            )
+        })
+        .collect()
+}
+
+///Everyone that has a status with the given component on it
+pub fn affected_by<T: Component>(state: &State) -> HashSet<Entity> {
+    state
+        .world
+        .query::<(&StatusEffect, &T)>()
+        .iter()
+        .map(|(_ent, (status, _))| status.target)
+        .collect()
+}
+
+pub fn has_status<T: Component>(state: &State, target: Entity) -> bool {
+    state
+        .world
+        .query::<(&StatusEffect, &T)>()
+        .iter()
+        .any(|(_ent, (status, _))| status.target == target)
+}
+
+///How much the target's vision range is cut by blinding statuses
+pub fn blindness(state: &State, target: Entity) -> i32 {
+    state
+        .world
+        .query::<(&StatusEffect, &Blinded)>()
+        .iter()
+        .filter(|(_ent, (status, _))| status.target == target)
+        .map(|(ent, (_, blinded))| blinded.range * status_stacks(state, ent))
+        .sum()
+}
+
+///Makes the target work out what they can see again, for when blindness comes or goes
+fn refresh_vision(state: &mut State, target: Entity) {
+    if let Ok(mut fov) = state.world.get::<&mut FoV>(target) {
+        fov.dirty = true;
+    }
+}
+
+///The direction a confused entity actually moves in when it tries to go somewhere, half of the time it's random
+pub fn confused_direction(state: &mut State, ent: Entity, intended: Point) -> Point {
+    if !has_status::<Confused>(state, ent) || state.rng.roll_dice(1, 2) == 1 {
+        return intended;
+    }
+
+    let mut direction = Point::zero();
+    while direction == Point::zero() {
+        direction = Point::new(state.rng.range(-1, 2), state.rng.range(-1, 2));
+    }
+
+    if Some(ent) == state.player_ent && direction != intended {
+        state
+            .game_log
+            .add_log("You stumble around in confusion.".to_string());
+    }
+
+    direction
+}
+
+pub fn drain_energy(state: &mut State, effect: &EffectSpawner, target: Entity) {
+    if let EffectType::DrainEnergy { amount } = effect.effect_type {
+        if let Ok(mut energy) = state.world.get::<&mut Energy>(target) {
+            energy.value -= amount;
+        }
+    }
+}
+
+///Fires every periodic status through the effect queue, then counts down the durations and sets off anything that
+/// happens when a status runs out. Called once a round
+pub fn tick_status_effects(state: &mut State) {
+    let periodic = state
+        .world
+        .query::<&StatusEffect>()
+        .iter()
+        .map(|(ent, status)| (ent, *status))
+        .collect::<Vec<_>>();
+
+    for (ent, status) in periodic.iter() {
+        let stacks = status_stacks(state, *ent);
+        let target = Targets::Single {
+            target: status.target,
+        };
+
+        if let Ok(dot) = state.world.get::<&DamageOverTime>(*ent).map(|dot| *dot) {
+            add_effect(
+                Some(status.source),
+                EffectType::Damage {
+                    amount: dot.amount * stacks,
+                    damage_type: dot.damage_type,
+                },
+                target.clone(),
+            );
+        }
+        if let Ok(drain) = state.world.get::<&EnergyDrain>(*ent).map(|drain| *drain) {
+            add_effect(
+                Some(status.source),
+                EffectType::DrainEnergy {
+                    amount: drain.amount * stacks,
+                },
+                target,
+            );
+        }
+    }
+
+    let mut expired = Vec::new();
+    for (ent, (status, duration)) in state
+        .world
+        .query_mut::<(&StatusEffect, &mut EffectDuration)>()
+    {
+        duration.rounds -= 1;
+
+        if duration.rounds <= 0 {
+            expired.push((ent, *status));
+        }
+    }
+
+    for (ent, status) in expired.into_iter() {
+        let on_expire = state
+            .world
+            .get::<&OnExpire>(ent)
+            .map(|on_expire| on_expire.effects.clone());
+        let name = state
+            .world
+            .get::<&NamedStatus>(ent)
+            .map(|named| named.name.clone());
+
+        let _ = state.world.despawn(ent);
+        refresh_vision(state, status.target);
+
+        if let Ok(effects) = on_expire {
+            combat_trigger(status.source, &effects, status.target, state);
+        }
+        if let (Ok(name), true) = (name, Some(status.target) == state.player_ent) {
+            state
+                .game_log
+                .add_log(format!("You are no longer {}.", name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bracket_lib::prelude::DistanceAlg;
+
+    use super::*;
+    use crate::{
+        ai::{run_initiative, MyTurn},
+        effects::run_effect_queue,
+        statistics::Pools,
+        utils::testing::{spawn_test_mob, test_state},
+        visibility_system::VisibilitySystem,
+        TileType,
+    };
+
+    fn status(
+        state: &State,
+        target: Entity,
+        effects: &[(&str, &str)],
+        duration: Option<i32>,
+    ) -> EffectSpawner {
+        EffectSpawner {
+            creator: state.player_ent,
+            effect_type: EffectType::StatusEffect {
+                effects: effects
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect::<HashMap<_, _>>(),
+                duration,
+            },
+            targets: Targets::Single { target },
+        }
+    }
+
+    #[test]
+    fn stacking_statuses_intensify_up_to_the_cap() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+        {
+            let mut pools = state.world.get::<&mut Pools>(player).unwrap();
+            pools.hitpoints.max_value = 100;
+            pools.hitpoints.current_value = 100;
+        }
+
+        let poison = status(
+            &state,
+            player,
+            &[("status", "poisoned"), ("stacking", "stack"), ("dot", "1")],
+            Some(3),
+        );
+        for _ in 0..MAX_STATUS_STACKS + 2 {
+            spawn_status_effect(&mut state, &poison, player);
+        }
+
+        let statuses = named_statuses(&state, player);
+        assert_eq!(
+            statuses,
+            vec![("poisoned".to_string(), MAX_STATUS_STACKS, Some(3))]
+        );
+
+        //every stack hurts once a round until it wears off
+        for _ in 0..3 {
+            let before = state
+                .world
+                .get::<&Pools>(player)
+                .unwrap()
+                .hitpoints
+                .current_value;
+            tick_status_effects(&mut state);
+            run_effect_queue(&mut state);
+            let after = state
+                .world
+                .get::<&Pools>(player)
+                .unwrap()
+                .hitpoints
+                .current_value;
+            assert_eq!(before - after, MAX_STATUS_STACKS);
+        }
+        assert!(named_statuses(&state, player).is_empty());
+    }
+
+    #[test]
+    fn ignored_statuses_keep_their_duration_and_refreshed_ones_reset_it() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+
+        let dazed = |duration| {
+            status(
+                &state,
+                player,
+                &[("status", "dazed"), ("stacking", "ignore")],
+                Some(duration),
+            )
+        };
+        let winded = |duration| {
+            status(
+                &state,
+                player,
+                &[("status", "winded"), ("stacking", "refresh")],
+                Some(duration),
+            )
+        };
+        let (first_dazed, first_winded) = (dazed(2), winded(2));
+        let (second_dazed, second_winded) = (dazed(5), winded(5));
+
+        spawn_status_effect(&mut state, &first_dazed, player);
+        spawn_status_effect(&mut state, &first_winded, player);
+        tick_status_effects(&mut state);
+        spawn_status_effect(&mut state, &second_dazed, player);
+        spawn_status_effect(&mut state, &second_winded, player);
+
+        let mut statuses = named_statuses(&state, player);
+        statuses.sort();
+        assert_eq!(
+            statuses,
+            vec![
+                ("dazed".to_string(), 1, Some(1)),
+                ("winded".to_string(), 1, Some(5))
+            ]
+        );
+    }
+
+    #[test]
+    fn stunned_mobs_build_up_no_energy() {
+        let mut state = test_state(5);
+        let pos = state.player_pos;
+        let stunned = spawn_test_mob(&mut state, "Street Thug", pos.x + 1, pos.y);
+        let alert = spawn_test_mob(&mut state, "Street Thug", pos.x - 1, pos.y);
+
+        let stun = status(
+            &state,
+            stunned,
+            &[("status", "stunned"), ("stun", "1")],
+            Some(2),
+        );
+        spawn_status_effect(&mut state, &stun, stunned);
+
+        let waiting = state
+            .world
+            .query::<&MyTurn>()
+            .iter()
+            .map(|(ent, _)| ent)
+            .collect::<Vec<_>>();
+        for ent in waiting {
+            let _ = state.world.remove_one::<MyTurn>(ent);
+        }
+        let energy = |state: &State, ent| state.world.get::<&Energy>(ent).unwrap().value;
+        let (stunned_before, alert_before) = (energy(&state, stunned), energy(&state, alert));
+
+        run_initiative(&mut state);
+        run_effect_queue(&mut state);
+
+        assert_eq!(energy(&state, stunned), stunned_before);
+        assert!(energy(&state, alert) > alert_before);
+    }
+
+    #[test]
+    fn blindness_cuts_vision_until_it_wears_off() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+        //nothing in the way, so only the vision range limits what can be seen
+        for tile in state.map.map.iter_mut() {
+            *tile = TileType::Floor;
+        }
+
+        let furthest_seen = |state: &mut State| {
+            state.world.get::<&mut FoV>(player).unwrap().dirty = true;
+            VisibilitySystem::run(state);
+            let fov = state.world.get::<&FoV>(player).unwrap();
+            fov.visible_tiles
+                .iter()
+                .map(|tile| DistanceAlg::Pythagoras.distance2d(state.player_pos, *tile))
+                .fold(0., f32::max)
+        };
+        let clear = furthest_seen(&mut state);
+
+        let blind = status(
+            &state,
+            player,
+            &[("status", "blinded"), ("blind", "3")],
+            Some(1),
+        );
+        spawn_status_effect(&mut state, &blind, player);
+        assert!(furthest_seen(&mut state) < clear - 2.);
+
+        tick_status_effects(&mut state);
+        assert_eq!(furthest_seen(&mut state), clear);
     }
 }
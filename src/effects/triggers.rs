@@ -1,12 +1,18 @@
+use std::collections::HashMap;
+
 use bracket_lib::prelude::{console, Point};
 use hecs::Entity;
 
 use crate::{
+    ai::{make_noise, GUNFIRE_NOISE},
     components::{DescendFloors, Door, GrantsStatus},
+    crafting::{learn_recipe, TeachesRecipe},
     gamelog,
+    identification::identify_item,
+    ranged_combat::ammo::chamber_round,
     raws::RawMaster,
-    Consumable, DamageEffect, GivesFood, HealingEffect, Hidden, Map, Position, Projectile,
-    RangedWeapon, State,
+    statistics::{gain_skill_exp, Skill},
+    Consumable, DamageEffect, GivesFood, HealingEffect, Hidden, Map, Position, State,
 };
 
 use super::{
@@ -16,12 +22,21 @@ use super::{
 
 pub fn ranged_trigger(creator: Option<Entity>, item: Entity, targets: &Targets, state: &mut State) {
     event_trigger(creator, item, targets, state);
+
+    if let Some(creator) = creator {
+        gain_skill_exp(Skill::Ranged, 1, creator, state);
+    }
 }
 
 pub fn item_trigger(creator: Option<Entity>, item: Entity, targets: &Targets, state: &mut State) {
     //fires off effect
     event_trigger(creator, item, targets, state);
 
+    //using something is a sure way of finding out what it was
+    if creator.is_some() && creator == state.player_ent {
+        identify_item(state, item);
+    }
+
     //despawns entity if it was consumable
     if state.world.get::<&Consumable>(item).is_ok() {
         if state.world.despawn(item).is_err() {
@@ -62,6 +77,23 @@ pub fn command_trigger(command: crate::raws::scripting::Command, state: &mut Sta
     }
 }
 
+///Fires a weapon, armour or mob's on_hit or on_damaged effects from the creator at the target, the effects only
+/// need to exist long enough to be queued
+pub fn combat_trigger(
+    creator: Entity,
+    effects: &HashMap<String, String>,
+    target: Entity,
+    state: &mut State,
+) {
+    let mut builder = hecs::EntityBuilder::new();
+    builder = RawMaster::add_effects_comps(builder, effects.clone());
+    let ent = state.world.spawn(builder.build());
+
+    event_trigger(Some(creator), ent, &Targets::Single { target }, state);
+
+    let _ = state.world.despawn(ent);
+}
+
 #[allow(dead_code)]
 pub fn entry_trigger_fire(
     creator: Option<Entity>,
@@ -83,15 +115,23 @@ fn event_trigger(creator: Option<Entity>, item: Entity, targets: &Targets, state
     }
 
     if let Ok(status) = state.world.get::<&GrantsStatus>(item) {
+        //a single target gets the status and anything aimed at tiles gives it to whoever used it. Items, machines
+        // and props only ever single out whoever used them so for them this is the same as always going to the user,
+        // on_hit and on_damaged effects single out the other side of the fight
+        let target = match targets {
+            Targets::Single { target } => *target,
+            _ => creator.unwrap(),
+        };
+
+        //the status remembers who caused it so damage it deals later is credited to them, the item itself might be
+        // used up or only exist for as long as it takes to queue its effects
         add_effect(
-            Some(item),
+            creator.or(Some(item)),
             EffectType::StatusEffect {
                 effects: status.effects.clone(),
                 duration: status.duration,
             },
-            Targets::Single {
-                target: creator.unwrap(),
-            },
+            Targets::Single { target },
         );
     }
 
@@ -100,6 +140,7 @@ fn event_trigger(creator: Option<Entity>, item: Entity, targets: &Targets, state
             creator,
             EffectType::Damage {
                 amount: damage.damage_amount,
+                damage_type: Some(damage.damage_type),
             },
             targets.clone(),
         );
@@ -125,6 +166,18 @@ fn event_trigger(creator: Option<Entity>, item: Entity, targets: &Targets, state
         );
     }
 
+    let taught = state
+        .world
+        .get::<&TeachesRecipe>(item)
+        .map(|teaches| teaches.recipe.clone());
+    if let (Ok(recipe), Some(learner)) = (taught, creator) {
+        if learn_recipe(state, learner, &recipe) && Some(learner) == state.player_ent {
+            state
+                .game_log
+                .add_log(format!("You learn how to make {}.", recipe));
+        }
+    }
+
     if let Ok(p) = state.world.get::<&ParticleBurst>(item) {
         add_effect(
             creator,
@@ -199,6 +252,7 @@ fn event_trigger(creator: Option<Entity>, item: Entity, targets: &Targets, state
         }
     }
 
+    let mut shot = None;
     if let Ok(p) = state.world.get::<&ParticleAnimation>(item) {
         if let Some(ent) = creator {
             if let Ok(pos) = state.world.get::<&Position>(ent) {
@@ -228,21 +282,80 @@ fn event_trigger(creator: Option<Entity>, item: Entity, targets: &Targets, state
                         creator: creator.expect("No projectile creator"),
                     };
 
-                    //std::mem::drop(p);
-                    //std::mem::drop(pos);
-                    ANIMATIONQUEUE.lock().unwrap().push((
-                        anim,
-                        Projectile {
-                            damage: state
-                                .world
-                                .get::<&RangedWeapon>(item)
-                                .expect("Couldn't get RangedWeapon component!")
-                                .damage,
-                        },
-                    ));
+                    shot = Some(anim);
                     //return;
                 }
             }
         }
     }
+
+    //a round is only used up once there's somewhere to fire it
+    if let Some(anim) = shot {
+        match chamber_round(state, item) {
+            Some(projectile) => {
+                let shooter = anim.creator;
+                ANIMATIONQUEUE
+                    .lock()
+                    .unwrap()
+                    .push((anim, Some(projectile)));
+                make_noise(state, shooter, GUNFIRE_NOISE);
+            }
+            None => state
+                .game_log
+                .add_log("*click* The magazine is empty.".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack_system::AttackSystem,
+        components::{HurtByPlayer, NamedStatus, StatusEffect},
+        effects::{run_effect_queue, tick_status_effects},
+        spawns::spawning_system::spawn_item_equipped,
+        statistics::Pools,
+        utils::testing::{spawn_test_mob, test_state},
+    };
+
+    #[test]
+    fn on_hit_statuses_land_on_the_target_and_are_credited_to_the_attacker() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+        let pos = state.player_pos;
+
+        spawn_item_equipped(&mut state, &"Katana".to_string(), player);
+        let thug = spawn_test_mob(&mut state, "Street Thug", pos.x + 1, pos.y);
+        //tough enough to survive however long it takes to land a hit
+        if let Ok(pools) = state.world.query_one_mut::<&mut Pools>(thug) {
+            pools.hitpoints.max_value = 10000;
+            pools.hitpoints.current_value = 10000;
+        }
+
+        let bleeding = |state: &State| {
+            state
+                .world
+                .query::<(&StatusEffect, &NamedStatus)>()
+                .iter()
+                .find(|(_ent, (status, named))| status.target == thug && named.name == "bleeding")
+                .map(|(_ent, (status, _named))| status.source)
+        };
+
+        for _ in 0..100 {
+            if bleeding(&state).is_some() {
+                break;
+            }
+            AttackSystem::add_attack(player, thug, &mut state);
+            AttackSystem::run(&mut state);
+            run_effect_queue(&mut state);
+        }
+        assert_eq!(bleeding(&state), Some(player));
+
+        //the bleeding on its own is enough for the thug to know who hurt it
+        let _ = state.world.remove_one::<HurtByPlayer>(thug);
+        tick_status_effects(&mut state);
+        run_effect_queue(&mut state);
+        assert!(state.world.get::<&HurtByPlayer>(thug).is_ok());
+    }
 }
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::sync::{LazyLock, Mutex, MutexGuard};
 
 pub static DEBUGLOG: DebugLog = DebugLog::new();
@@ -27,7 +28,7 @@ impl DebugLog {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameLog {
     pub entries: Vec<String>,
     pub index: usize,
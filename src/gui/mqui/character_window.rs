@@ -0,0 +1,98 @@
+use new_egui_macroquad::egui::{self, Color32, RichText};
+
+use crate::{
+    reputation::standing_label,
+    statistics::{skill_cap, skill_exp_to_next_level, BaseStatistics, Pools, Skill, Skills},
+    State,
+};
+
+///Shows the player's level, statistics, how far along each skill is towards its next level and where they stand
+/// with the factions they've dealt with
+pub fn show_character_window(ctx: &egui::Context, state: &State, is_open: &mut bool) {
+    let player = state.player_ent.unwrap();
+    let Ok(mut query) = state
+        .world
+        .query_one::<(&Pools, &BaseStatistics, Option<&Skills>)>(player)
+    else {
+        return;
+    };
+    let Some((pools, stats, skills)) = query.get() else {
+        return;
+    };
+
+    egui::Window::new("Character")
+        .resizable(false)
+        .collapsible(false)
+        .open(is_open)
+        .show(ctx, |ui| {
+            ui.label(RichText::new(format!("Level {}", pools.level)).heading());
+
+            egui::Grid::new("character_stats").show(ui, |ui| {
+                for stat in [
+                    stats.strength,
+                    stats.dexterity,
+                    stats.toughness,
+                    stats.intelligence,
+                    stats.mental_fortitude,
+                ]
+                .iter()
+                .zip([
+                    "Strength",
+                    "Dexterity",
+                    "Toughness",
+                    "Intelligence",
+                    "Mental Fortitude",
+                ]) {
+                    ui.label(stat.1);
+                    ui.label(format!(
+                        "{} {}",
+                        stat.0.total,
+                        stat.0.get_modifier_display()
+                    ));
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+            ui.label(RichText::new("Skills").heading());
+
+            egui::Grid::new("character_skills").show(ui, |ui| {
+                for skill in Skill::ALL {
+                    let level = skills.map_or(0, |s| s.level(skill));
+                    let exp = skills.map_or(0, |s| s.exp(skill));
+                    let cap = skill_cap(skill, stats);
+
+                    ui.label(skill.to_string());
+                    ui.label(format!("{} / {}", level, cap));
+
+                    if level >= cap {
+                        ui.label(format!("capped by {}", skill.governing_stat()));
+                    } else {
+                        let needed = skill_exp_to_next_level(level);
+                        ui.add(
+                            egui::ProgressBar::new(exp as f32 / needed as f32)
+                                .text(format!("{} / {} XP", exp, needed))
+                                .fill(Color32::DARK_GREEN)
+                                .desired_width(150.),
+                        );
+                    }
+                    ui.end_row();
+                }
+            });
+
+            let standings = state.reputation.standings();
+            if !standings.is_empty() {
+                ui.separator();
+                ui.label(RichText::new("Reputation").heading());
+
+                egui::Grid::new("character_reputation").show(ui, |ui| {
+                    for (faction, standing) in standings.iter() {
+                        ui.label(faction);
+                        ui.label(format!("{:+}", standing));
+                        ui.label(standing_label(*standing));
+                        ui.end_row();
+                    }
+                });
+            }
+        });
+}
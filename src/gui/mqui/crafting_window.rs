@@ -0,0 +1,95 @@
+use macroquad::input::{get_last_key_pressed, is_key_down, KeyCode};
+use new_egui_macroquad::egui::{self, Button, Color32, RichText, ScrollArea};
+
+use crate::{
+    crafting::{can_craft, carried_count, near_workbench},
+    menus::key_to_option,
+    raws::Recipe,
+    State,
+};
+
+///Lists the recipes the player knows, what each one needs and which ingredients they're still missing
+pub struct CraftingWindow {
+    pub recipes: Vec<Recipe>,
+}
+
+impl CraftingWindow {
+    pub fn new(recipes: Vec<Recipe>) -> CraftingWindow {
+        CraftingWindow { recipes }
+    }
+
+    ///Returns the name of the recipe picked and whether the window should close
+    pub fn show(&mut self, ctx: &egui::Context, state: &State) -> (Option<String>, bool) {
+        let mut chosen = None;
+        let should_close = is_key_down(KeyCode::Escape);
+        let player = state.player_ent.unwrap();
+        let at_workbench = near_workbench(state, player);
+
+        egui::Window::new("Crafting")
+            .resizable(false)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, (0., 0.))
+            .show(ctx, |ui| {
+                ScrollArea::vertical().max_height(400.).show(ui, |ui| {
+                    for (i, recipe) in self.recipes.iter().enumerate() {
+                        let craftable = can_craft(state, player, recipe);
+                        let count = recipe.count.unwrap_or(1);
+                        let mut label = format!("{}.) {}", (65u8 + i as u8) as char, recipe.result);
+                        if count > 1 {
+                            label.push_str(&format!(" x{}", count));
+                        }
+
+                        let fill = if craftable {
+                            Color32::DARK_GREEN
+                        } else {
+                            Color32::DARK_GRAY
+                        };
+                        if ui
+                            .add_enabled(craftable, Button::new(label).fill(fill))
+                            .clicked()
+                        {
+                            chosen = Some(recipe.name.clone());
+                        }
+
+                        ui.indent(&recipe.name, |ui| {
+                            for (ingredient, needed) in recipe.ingredients.iter() {
+                                let carried = carried_count(state, player, ingredient);
+                                let colour = if carried >= *needed {
+                                    Color32::LIGHT_GRAY
+                                } else {
+                                    Color32::LIGHT_RED
+                                };
+                                ui.label(
+                                    RichText::new(format!(
+                                        "{} {} / {}",
+                                        ingredient,
+                                        carried.min(*needed),
+                                        needed
+                                    ))
+                                    .color(colour),
+                                );
+                            }
+                            if recipe.workbench.unwrap_or(false) {
+                                let colour = if at_workbench {
+                                    Color32::LIGHT_GRAY
+                                } else {
+                                    Color32::LIGHT_RED
+                                };
+                                ui.label(RichText::new("Needs a workbench").color(colour));
+                            }
+                        });
+                    }
+                });
+            });
+
+        if let Some(key) = get_last_key_pressed() {
+            if let Some(recipe) = self.recipes.get(key_to_option(key) as usize) {
+                if can_craft(state, player, recipe) {
+                    chosen = Some(recipe.name.clone());
+                }
+            }
+        }
+
+        (chosen, should_close)
+    }
+}
@@ -1,7 +1,11 @@
 use macroquad::input::KeyCode;
 use new_egui_macroquad::egui::{self, RichText};
 
-use crate::{dev_console::Terminal, gamelog::DEBUGLOG, input::INPUT};
+use crate::{
+    dev_console::Terminal,
+    gamelog::DEBUGLOG,
+    input::{PlayerInput, INPUT},
+};
 
 pub struct DevConsole<'a> {
     current_cmd: String,
@@ -25,6 +29,14 @@ impl DevConsole<'_> {
         } else {
             INPUT.lock().enable_input();
         }
+
+        //commands from a replay run whether the console is open or not
+        let replayed = INPUT.lock().next_replayed_console(state.turn_number);
+        if let Some(command) = replayed {
+            self.terminal.set_cmd(command);
+            self.terminal.run_cmd();
+        }
+
         egui::Window::new("dev console")
             .fixed_size([400f32, 700f32])
             .open(is_open)
@@ -62,6 +74,10 @@ impl DevConsole<'_> {
                 );
 
                 if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    INPUT.lock().record(
+                        state.turn_number,
+                        PlayerInput::Console(self.current_cmd.clone()),
+                    );
                     self.terminal.set_cmd(self.current_cmd.clone());
                     self.terminal.run_cmd();
                     self.current_cmd.clear();
@@ -0,0 +1,62 @@
+use hecs::Entity;
+use macroquad::input::{get_last_key_pressed, is_key_down, KeyCode};
+use new_egui_macroquad::egui::{self, Button, RichText};
+
+use crate::{menus::key_to_option, raws::DialogueChoice};
+
+///What the speaker just said along with the replies the player is able to give
+pub struct DialogueWindow {
+    pub speaker: Entity,
+    pub speaker_name: String,
+    pub text: String,
+    pub choices: Vec<DialogueChoice>,
+}
+
+impl DialogueWindow {
+    pub fn new(
+        speaker: Entity,
+        speaker_name: String,
+        text: String,
+        choices: Vec<DialogueChoice>,
+    ) -> DialogueWindow {
+        DialogueWindow {
+            speaker,
+            speaker_name,
+            text,
+            choices,
+        }
+    }
+
+    ///Returns the reply picked and whether the player walked away from the conversation
+    pub fn show(&mut self, ctx: &egui::Context) -> (Option<DialogueChoice>, bool) {
+        let mut chosen = None;
+        let mut should_close = is_key_down(KeyCode::Escape);
+
+        egui::Window::new(self.speaker_name.clone())
+            .resizable(false)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, (0., 0.))
+            .show(ctx, |ui| {
+                ui.label(RichText::new(format!("\"{}\"", self.text)).italics());
+                ui.separator();
+
+                for (i, choice) in self.choices.iter().enumerate() {
+                    let label = format!("{}.) {}", (65u8 + i as u8) as char, choice.text);
+                    if ui.add(Button::new(label)).clicked() {
+                        chosen = Some(choice.clone());
+                    }
+                }
+                if ui.button("Leave").clicked() {
+                    should_close = true;
+                }
+            });
+
+        if let Some(key) = get_last_key_pressed() {
+            if let Some(choice) = self.choices.get(key_to_option(key) as usize) {
+                chosen = Some(choice.clone());
+            }
+        }
+
+        (chosen, should_close)
+    }
+}
@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use hecs::Entity;
 use macroquad::{input::{get_last_key_pressed, is_key_down, KeyCode}, window::{screen_height, screen_width}};
 use new_egui_macroquad::egui::{self as egui, Button, Color32, Layout, RichText, ScrollArea};
 
-use crate::{components::Name, menus::key_to_option, State};
+use crate::{components::Weight, identification::item_name, InContainer, menus::key_to_option, statistics::{carried_weight, carrying_capacity, BaseStatistics}, State};
 
 #[derive(Debug,PartialEq, Eq)]
 pub enum ItemWindowType{Pickup, Drop, Unequip}
@@ -17,23 +19,25 @@ pub struct ItemWindow
     pub title : String,
     pub dimensions : (f32, f32),
     pub mode : ItemWindowMode,
+    ///Set when trading, the player's own items are listed as for sale and everything else as for purchase
+    pub prices : HashMap<Entity, i32>,
 }
 
 impl ItemWindow
 {
     pub fn default(contents : Vec<(Entity, bool)>) -> ItemWindow
     {
-        ItemWindow {contents, title : "Inventory".to_string(), dimensions: (200.,200.), mode : ItemWindowMode ::Single}
+        ItemWindow {contents, title : "Inventory".to_string(), dimensions: (200.,200.), mode : ItemWindowMode ::Single, prices : HashMap::new()}
     }
 
     pub fn default_with_type(contents : Vec<(Entity, bool)>, title : String, mode: ItemWindowMode) -> ItemWindow
     {
-        ItemWindow{contents, title, dimensions : (200.,200.), mode}
+        ItemWindow{contents, title, dimensions : (200.,200.), mode, prices : HashMap::new()}
     }
 
     pub fn new <T : Into<(f32,f32)>>(contents : Vec<(Entity, bool)>, title : String, dimensions : T, mode: ItemWindowMode) -> ItemWindow
     {
-        ItemWindow {contents,title,dimensions : dimensions.into(),mode}
+        ItemWindow {contents,title,dimensions : dimensions.into(),mode, prices : HashMap::new()}
     }
 
     pub fn show(&mut self, ctx : &egui::Context, state : &State) -> (Option<Vec<Entity>>, bool)
@@ -73,9 +77,18 @@ impl ItemWindow
 
                                 let c = 65u8 + i as u8;
 
-                                if ui.add(Button::new(format!("{}.) {}",c as char
-                                    , state.world.get::<&Name>(self.contents[i].0).unwrap().name.clone())
-                                ).fill(col)).clicked()
+                                let mut label = format!("{}.) {}",c as char, item_name(state, self.contents[i].0));
+                                if let Ok(weight) = state.world.get::<&Weight>(self.contents[i].0)
+                                {
+                                    label.push_str(&format!(" ({:.1} kg)", weight.value));
+                                }
+                                if let Some(price) = self.prices.get(&self.contents[i].0)
+                                {
+                                    let selling = state.world.get::<&InContainer>(self.contents[i].0).is_ok_and(|cont| Some(cont.owner) == state.player_ent);
+                                    label.push_str(&format!(" - {} {} cr", if selling {"sell"} else {"buy"}, price));
+                                }
+
+                                if ui.add(Button::new(label).fill(col)).clicked()
                                 {
                                     if self.mode == ItemWindowMode::Single
                                     {
@@ -90,6 +103,16 @@ impl ItemWindow
                             }
                         });
 
+                        //only menus of items have anything to weigh
+                        if self.contents.iter().any(|(ent, _)| state.world.get::<&Weight>(*ent).is_ok())
+                        {
+                            let player = state.player_ent.unwrap();
+                            if let Ok(stats) = state.world.get::<&BaseStatistics>(player)
+                            {
+                                ui.label(format!("Carrying {:.1} / {:.1} kg", carried_weight(state, player), carrying_capacity(&stats)));
+                            }
+                        }
+
                         if self.mode == ItemWindowMode::Multiple
                         {
                             if ui.button("Execute!").clicked()
@@ -0,0 +1,35 @@
+use new_egui_macroquad::egui::{self, Color32, RichText};
+
+use crate::{
+    missions::{mission_info, objective_text},
+    State,
+};
+
+///Lists the missions the player is working on with what's left to do for each, followed by the ones they've finished
+pub fn show_journal_window(ctx: &egui::Context, state: &State, is_open: &mut bool) {
+    egui::Window::new("Missions")
+        .resizable(false)
+        .collapsible(false)
+        .open(is_open)
+        .show(ctx, |ui| {
+            if state.missions.active.is_empty() {
+                ui.label("No active missions. Look for someone with work going.");
+            }
+
+            for mission in state.missions.active.iter() {
+                ui.label(RichText::new(&mission.name).heading());
+                if let Some(info) = mission_info(&mission.name) {
+                    ui.label(info.description);
+                }
+                ui.label(RichText::new(objective_text(state, mission)).color(Color32::YELLOW));
+                ui.separator();
+            }
+
+            if !state.missions.completed.is_empty() {
+                ui.label(RichText::new("Completed").heading());
+                for name in state.missions.completed.iter() {
+                    ui.label(RichText::new(name).color(Color32::GRAY));
+                }
+            }
+        });
+}
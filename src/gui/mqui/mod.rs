@@ -1,11 +1,19 @@
+mod character_window;
+mod crafting_window;
 mod dev_console;
+mod dialogue_window;
 mod gamelog;
 mod item_window;
+mod journal_window;
 mod status_panel;
 mod tooltip_window;
 
+pub use character_window::*;
+pub use crafting_window::*;
 pub use dev_console::*;
+pub use dialogue_window::*;
 pub use item_window::*;
+pub use journal_window::*;
 pub use tooltip_window::*;
 
 use crate::State;
@@ -1,7 +1,7 @@
 
 use macroquad::{color::RED, time::get_fps};
 use new_egui_macroquad::egui::{self as egui, Color32, Frame, Layout, Widget};
-use crate::{hunger::HungerLevel, statistics::{calculate_xp_from_level, get_xp_from_current_level, BaseStatistics, Pools}, State};
+use crate::{effects::named_statuses, hunger::HungerLevel, networks::MAX_ALERT, ranged_combat::ammo::{magazine_status, quiver_rounds}, statistics::{calculate_xp_from_level, carried_weight, carrying_capacity, get_xp_from_current_level, BaseStatistics, Encumbrance, Pools}, trading::credits, State};
 
 
 
@@ -27,6 +27,7 @@ pub fn right_panel(ctx : &egui::Context, state : &State )
                 cols[1].label(format!("Turn {}",state.turn_number));
                 cols[2].label(format!("FPS: {}", get_fps()));
             });
+            ui.label(format!("Seed {}", state.seed));
 
 
             ui.add(egui::ProgressBar::new(((pools.hitpoints.current_value as f32/pools.hitpoints.max_value as f32)))
@@ -51,9 +52,32 @@ pub fn right_panel(ctx : &egui::Context, state : &State )
                 .text(format!("Hunger"))
                 .fill(Color32::from_hex("#ee913a").unwrap()));
 
+            ui.add(egui::ProgressBar::new(state.network_map.alert as f32/MAX_ALERT as f32)
+                .text(format!("Network Alert {} / {}", state.network_map.alert, MAX_ALERT))
+                .fill(Color32::DARK_RED));
+
 
             std::mem::drop(hng);
 
+            let stats = state.world.get::<&BaseStatistics>(state.player_ent.unwrap()).unwrap();
+            let carried = carried_weight(state, state.player_ent.unwrap());
+            let capacity = carrying_capacity(&stats);
+            std::mem::drop(stats);
+
+            ui.add(egui::ProgressBar::new((carried/capacity).min(1.))
+                .text(format!("{:.1} / {:.1} kg {}", carried, capacity, Encumbrance::from_load(carried, capacity)))
+                .fill(Color32::from_hex("#8a6d3b").unwrap()));
+
+            if let Some((rounds, magazine, loaded)) = magazine_status(state, state.player_ent.unwrap())
+            {
+                let loaded = loaded.unwrap_or(String::from("Empty"));
+                ui.add(egui::ProgressBar::new(rounds as f32/magazine as f32)
+                    .text(format!("{} / {} {} ({} spare)", rounds, magazine, loaded, quiver_rounds(state, state.player_ent.unwrap())))
+                    .fill(Color32::DARK_GRAY));
+            }
+
+            ui.label(format!("Credits: {}", credits(state, state.player_ent.unwrap())));
+
             let stats = state.world.get::<&BaseStatistics>(state.player_ent.unwrap()).unwrap();
 
             ui.columns(2, |cols| 
@@ -72,6 +96,22 @@ pub fn right_panel(ctx : &egui::Context, state : &State )
                 {
                     cols[0].label(format!("Mental Fortitude: {} {}",stats.mental_fortitude.total, stats.mental_fortitude.get_modifier_display()));
                 });
+
+            std::mem::drop(stats);
+
+            for (name, stacks, rounds) in named_statuses(state, state.player_ent.unwrap())
+            {
+                let mut label = name;
+                if stacks > 1
+                {
+                    label.push_str(&format!(" x{}", stacks));
+                }
+                if let Some(rounds) = rounds
+                {
+                    label.push_str(&format!(" ({})", rounds));
+                }
+                ui.colored_label(Color32::LIGHT_RED, label);
+            }
         });
         });
 }
\ No newline at end of file
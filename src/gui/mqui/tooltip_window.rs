@@ -14,12 +14,12 @@ pub fn show_tooltip_window(ctx: &egui::Context, state: &mut State, x: i32, y: i3
 
     for ent in entities.iter() {
         if let Ok(n) = state.world.query_one_mut::<&Name>(*ent) {
-            name.push(n.name.clone());
+            name.push(state.identification.display_name(&n.name));
         }
     }
     if name.len() > 0 {
         let (screen_x, screen_y) = state
-            .renderer
+            .renderer()
             .canvas
             .get_tile_screen_pos(x - min_x, y - min_y);
         egui::Window::new("Tile Contents")
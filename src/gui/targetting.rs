@@ -46,7 +46,7 @@ pub fn mouse_cursor(state : &mut State) -> Point
 {
     let p = macroquad::input::mouse_position();
 
-    Point::from_tuple(state.renderer.canvas.get_tile_coords(p.0 as i32, p.1 as i32))
+    Point::from_tuple(state.renderer().canvas.get_tile_coords(p.0 as i32, p.1 as i32))
 }
 
 
@@ -74,7 +74,7 @@ pub fn ranged_target(state : &mut State, range : i32, aoe : Option<i32>)
         return (inventory_state::Cancel, None);
     }
 
-    state.renderer.draw_char(5, 0, "SELECT TARGET", macroquad::prelude::YELLOW);
+    state.renderer().draw_char(5, 0, "SELECT TARGET", macroquad::prelude::YELLOW);
     
     let mut available_cells = Vec::new();
     let visible = state.world.get::<&FoV>(state.player_ent
@@ -95,7 +95,7 @@ pub fn ranged_target(state : &mut State, range : i32, aoe : Option<i32>)
                     {
                         let mut bg = macroquad::prelude::BLUE;
                         bg.a = 0.4;
-                        state.renderer.draw_square(screen_x, screen_y, bg);
+                        state.renderer().draw_square(screen_x, screen_y, bg);
                         available_cells.push(idx.clone());
                     }
                 }
@@ -124,7 +124,7 @@ pub fn ranged_target(state : &mut State, range : i32, aoe : Option<i32>)
     }
     if is_valid_target
     {
-        state.renderer.draw_square(mouse_pos.0, mouse_pos.1, color_with_alpha(macroquad::color::SKYBLUE, 0.4));
+        state.renderer().draw_square(mouse_pos.0, mouse_pos.1, color_with_alpha(macroquad::color::SKYBLUE, 0.4));
         if is_mouse_button_down(MouseButton::Left)
         {
             return (inventory_state::Selected,Some(Point::new(mouse_map_pos.0, mouse_map_pos.1)) );
@@ -139,7 +139,7 @@ pub fn ranged_target(state : &mut State, range : i32, aoe : Option<i32>)
     {
         let mut bg = macroquad::prelude::RED;
         bg.a = 0.4;
-        state.renderer.draw_square(mouse_pos.0, mouse_pos.1, bg);
+        state.renderer().draw_square(mouse_pos.0, mouse_pos.1, bg);
         if is_mouse_button_down(MouseButton::Left)
         {
             return (inventory_state::Cancel, None);
@@ -165,7 +165,7 @@ pub fn ranged_target(state : &mut State, range : i32, aoe : Option<i32>)
 
                 let mut bg = YELLOW;
                 bg.a = 0.4;
-                state.renderer.draw_square(screen_x, screen_y, bg);
+                state.renderer().draw_square(screen_x, screen_y, bg);
             }
             
         }
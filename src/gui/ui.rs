@@ -108,7 +108,7 @@ pub fn draw_cursor(pos : Point, state : &mut State, bg :  (u8, u8, u8)) -> Point
     let cursor_pos = keyboard_cursor(state,  pos);
 
     let col = RGB::from_u8(bg.0, bg.1, bg.2);
-    state.renderer.draw_square(cursor_pos.x, cursor_pos.y, color_with_alpha(rgb_to_color(col), 0.4));
+    state.renderer().draw_square(cursor_pos.x, cursor_pos.y, color_with_alpha(rgb_to_color(col), 0.4));
     
     cursor_pos
 }
@@ -0,0 +1,539 @@
+use std::{collections::HashMap, fmt, path::Path, str::FromStr};
+
+use bracket_lib::random::RandomNumberGenerator;
+use hecs::Entity;
+
+use crate::{
+    ai::MyTurn,
+    components::{Consumable, InContainer, RangedTargetting, WantsToUseItem},
+    dev_console::Terminal,
+    effects, game_init,
+    gamelog::DEBUGLOG,
+    go_down_stairs,
+    input::{handle_command, Command, PlayerInput, Replay},
+    maps::TileType,
+    new_state, raws, run_input_systems,
+    screen_manager::MANAGER,
+    statistics::Pools,
+    ProgramState, State,
+};
+
+pub enum PlayerPolicy {
+    ///Player never acts, handy for watching the AI on its own
+    Wait,
+    ///Wanders about randomly, takes any stairs it finds and uses items from its backpack when hurt
+    Random,
+    ///Plays the commands in order then waits for the rest of the run
+    Scripted(Vec<Command>),
+    ///Plays back a recorded game along with the player's answers to menus, targetting and the dev console. Inputs
+    /// that didn't take a turn when they were recorded don't take one here either and the run stops once they're used
+    /// up
+    Replayed(Vec<PlayerInput>),
+}
+
+pub struct SimulationConfig {
+    pub seed: u64,
+    pub depth: i32,
+    pub turns: u32,
+    pub policy: PlayerPolicy,
+}
+
+pub const USAGE: &str =
+    "Usage: --headless [--seed N] [--turns N] [--depth N] [--policy wait|random] \
+[--script n,e,wait,...] [--replay FILE] [--raws DIR]";
+
+impl SimulationConfig {
+    ///Parses the arguments described in `USAGE`, None if the game should open a window as normal and an error
+    /// naming the bad argument if they don't make sense
+    pub fn from_args<I: Iterator<Item = String>>(
+        args: I,
+    ) -> Result<Option<SimulationConfig>, String> {
+        let args = args.collect::<Vec<String>>();
+
+        if !args.iter().any(|arg| arg == "--headless") {
+            return Ok(None);
+        }
+
+        let value_of = |flag: &str| {
+            args.iter()
+                .position(|arg| arg == flag)
+                .and_then(|i| args.get(i + 1))
+        };
+
+        let mut config = SimulationConfig {
+            seed: RandomNumberGenerator::new().next_u64(),
+            depth: 0,
+            turns: 1000,
+            policy: PlayerPolicy::Random,
+        };
+
+        if let Some(seed) = value_of("--seed") {
+            config.seed = parse_number("--seed", seed)?;
+        }
+        if let Some(turns) = value_of("--turns") {
+            config.turns = parse_number("--turns", turns)?;
+        }
+        if let Some(depth) = value_of("--depth") {
+            config.depth = parse_number("--depth", depth)?;
+        }
+        if let Some(policy) = value_of("--policy") {
+            config.policy = match policy.as_str() {
+                "wait" => PlayerPolicy::Wait,
+                "random" => PlayerPolicy::Random,
+                _ => {
+                    return Err(format!(
+                        "Unknown policy {}, expected wait or random",
+                        policy
+                    ))
+                }
+            };
+        }
+        if let Some(script) = value_of("--script") {
+            config.policy = PlayerPolicy::Scripted(
+                script
+                    .split(',')
+                    .map(parse_script_command)
+                    .collect::<Result<_, _>>()?,
+            );
+        }
+        if let Some(path) = value_of("--replay") {
+            let replay = Replay::load(Path::new(path))
+                .map_err(|err| format!("Couldn't load replay: {}", err))?;
+
+            config.seed = replay.seed;
+            config.depth = 0;
+            if value_of("--turns").is_none() {
+                config.turns = u32::MAX;
+            }
+            config.policy = PlayerPolicy::Replayed(
+                replay
+                    .commands
+                    .into_iter()
+                    .map(|recorded| recorded.input)
+                    .collect(),
+            );
+        }
+
+        Ok(Some(config))
+    }
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} needs to be a number, got {}", flag, value))
+}
+
+fn parse_script_command(command: &str) -> Result<Command, String> {
+    let direction = |x: i32, y: i32| Command::Move { pos: (x, y).into() };
+
+    let command = match command.trim() {
+        "n" => direction(0, -1),
+        "s" => direction(0, 1),
+        "e" => direction(1, 0),
+        "w" => direction(-1, 0),
+        "ne" => direction(1, -1),
+        "nw" => direction(-1, -1),
+        "se" => direction(1, 1),
+        "sw" => direction(-1, 1),
+        "wait" => Command::Wait,
+        "down" => Command::GoDownStairs,
+        "up" => Command::GoUpStairs,
+        "pickup" => Command::Pickup,
+        "reload" => Command::Reload,
+        other => {
+            return Err(format!(
+                "Unknown script command {}, expected a direction, wait, down, up, pickup or reload",
+                other
+            ))
+        }
+    };
+
+    Ok(command)
+}
+
+#[derive(Default, Debug)]
+pub struct SimulationSummary {
+    pub seed: u64,
+    pub turns_run: u32,
+    pub final_depth: i32,
+    pub player_died: bool,
+    pub deaths: u32,
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+    pub items_used: u32,
+}
+
+impl fmt::Display for SimulationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Simulation with seed {} ran for {} turns",
+            self.seed, self.turns_run
+        )?;
+        writeln!(f, "  Depth reached: {}", self.final_depth)?;
+        writeln!(
+            f,
+            "  Deaths: {} (player died: {})",
+            self.deaths, self.player_died
+        )?;
+        writeln!(f, "  Damage dealt: {}", self.damage_dealt)?;
+        writeln!(f, "  Damage taken by player: {}", self.damage_taken)?;
+        write!(f, "  Items used: {}", self.items_used)
+    }
+}
+
+///Runs the game loop without a window for the configured number of player turns
+pub fn run_simulation(config: SimulationConfig) -> SimulationSummary {
+    simulate(config).1
+}
+
+///Runs the simulation and hands back the game as it was left at the end
+fn simulate(config: SimulationConfig) -> (State, SimulationSummary) {
+    let mut state = new_state(None, config.seed);
+    //kept separate so the player's choices don't shift the rolls the game itself makes
+    let mut policy_rng = RandomNumberGenerator::seeded(config.seed.wrapping_add(1));
+
+    if let Err(err) = raws::run() {
+        panic!("Couldn't load raws: {}", err);
+    }
+    game_init(&mut state);
+    for _ in 0..config.depth {
+        go_down_stairs(&mut state);
+    }
+
+    let mut summary = SimulationSummary {
+        seed: config.seed,
+        ..Default::default()
+    };
+    let mut script = match config.policy {
+        PlayerPolicy::Scripted(ref commands) => commands.clone().into_iter(),
+        _ => Vec::new().into_iter(),
+    };
+    let mut replay = match config.policy {
+        PlayerPolicy::Replayed(ref inputs) => inputs.clone().into_iter(),
+        _ => Vec::new().into_iter(),
+    };
+    //console commands in a replay need a scripting engine to run on
+    let mut terminal = matches!(config.policy, PlayerPolicy::Replayed(_)).then(|| {
+        let mut terminal = Terminal::new();
+        terminal.load_commands();
+        terminal
+    });
+
+    //gets everything going until the player is first able to act
+    state.current_state = state.run_turns();
+
+    while summary.turns_run < config.turns {
+        let hitpoints = snapshot_hitpoints(&mut state);
+        let depth = state.map.depth;
+
+        let command = match config.policy {
+            PlayerPolicy::Wait => Command::Wait,
+            PlayerPolicy::Scripted(_) => script.next().unwrap_or(Command::Wait),
+            PlayerPolicy::Random => random_command(&mut state, &mut policy_rng),
+            PlayerPolicy::Replayed(_) => match replay.next() {
+                Some(input) => {
+                    play_back(&mut state, input, terminal.as_mut().unwrap());
+                    Command::None
+                }
+                None => break,
+            },
+        };
+
+        if command != Command::None {
+            state.current_state = handle_command(&mut state, command);
+        }
+
+        //anything that didn't end the player's turn is followed by whatever the player did next
+        if matches!(config.policy, PlayerPolicy::Replayed(_))
+            && state.current_state != ProgramState::Ticking
+        {
+            run_input_systems(&mut state);
+            continue;
+        }
+
+        //menus and targetting can't be answered without a window so the player waits instead
+        if state.current_state != ProgramState::Ticking {
+            MANAGER.lock().unwrap().close_menus();
+            state.current_state = handle_command(&mut state, Command::Wait);
+        }
+
+        summary.items_used += state.world.query_mut::<&WantsToUseItem>().into_iter().len() as u32;
+        run_input_systems(&mut state);
+        state.current_state = state.run_turns();
+
+        summary.turns_run += 1;
+
+        //everything from the last level was cleaned up so there is nothing to compare against
+        if state.map.depth == depth {
+            tally_damage(&mut state, hitpoints, &mut summary);
+        }
+
+        let player_hp = state
+            .world
+            .get::<&Pools>(state.player_ent.unwrap())
+            .unwrap()
+            .hitpoints
+            .current_value;
+        if player_hp <= 0 {
+            summary.player_died = true;
+            break;
+        }
+    }
+
+    summary.final_depth = state.map.depth;
+    (state, summary)
+}
+
+///Carries out one recorded input, guns and throws play out in full straight away as there are no frames to animate
+/// them over
+fn play_back(state: &mut State, input: PlayerInput, terminal: &mut Terminal) {
+    match input {
+        PlayerInput::Command(command) => state.current_state = handle_command(state, command),
+        PlayerInput::Console(command) => {
+            terminal.set_cmd(command);
+            terminal.run_cmd();
+            effects::run_effect_queue(state);
+        }
+        answer if state.awaiting_answer() => state.answer(answer),
+        answer => DEBUGLOG.add_log(format!(
+            "Replay out of sync, nothing was waiting on {:?}",
+            answer
+        )),
+    }
+
+    if state.current_state == ProgramState::PlayAnimation {
+        effects::finish_animations(state);
+    }
+}
+
+fn snapshot_hitpoints(state: &mut State) -> HashMap<Entity, i32> {
+    state
+        .world
+        .query_mut::<&Pools>()
+        .into_iter()
+        .map(|(ent, pools)| (ent, pools.hitpoints.current_value))
+        .collect()
+}
+
+fn tally_damage(state: &mut State, before: HashMap<Entity, i32>, summary: &mut SimulationSummary) {
+    let player = state.player_ent.unwrap();
+
+    for (ent, old_hp) in before {
+        let damage = match state.world.get::<&Pools>(ent) {
+            Ok(pools) => std::cmp::max(0, old_hp - pools.hitpoints.current_value),
+            Err(_) => {
+                summary.deaths += 1;
+                old_hp
+            }
+        };
+
+        if ent == player {
+            summary.damage_taken += damage;
+        } else {
+            summary.damage_dealt += damage;
+        }
+    }
+}
+
+fn random_command(state: &mut State, rng: &mut RandomNumberGenerator) -> Command {
+    let player = state.player_ent.unwrap();
+
+    let idx = state.map.xy_idx(state.player_pos.x, state.player_pos.y);
+    if state.map.map[idx] == TileType::DownStairs {
+        return Command::GoDownStairs;
+    }
+
+    let hurt = state
+        .world
+        .get::<&Pools>(player)
+        .is_ok_and(|pools| pools.hitpoints.current_value < pools.hitpoints.max_value / 2);
+
+    if hurt {
+        let item = state
+            .world
+            .query::<(&Consumable, &InContainer)>()
+            .without::<&RangedTargetting>()
+            .iter()
+            .find(|(_ent, (_consumable, cont))| cont.owner == player)
+            .map(|(ent, _)| ent);
+
+        if let Some(item) = item {
+            let _ = state
+                .world
+                .insert_one(player, WantsToUseItem { item, target: None });
+            let _ = state.world.remove_one::<MyTurn>(player);
+            state.current_state = ProgramState::Ticking;
+            return Command::None;
+        }
+    }
+
+    match rng.roll_dice(1, 10) {
+        1 => Command::Wait,
+        _ => Command::Move {
+            pos: (rng.range(-1, 2), rng.range(-1, 2)).into(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bracket_lib::prelude::Point;
+
+    use super::*;
+    use crate::{
+        components::{Name, RangedWeapon},
+        hunger::HungerLevel,
+        input::Recorder,
+        utils::testing::test_state,
+        Position,
+    };
+
+    #[test]
+    fn simulation_runs_without_a_window() {
+        let summary = run_simulation(SimulationConfig {
+            seed: 1234,
+            depth: 1,
+            turns: 200,
+            policy: PlayerPolicy::Random,
+        });
+
+        //the run only stops early if the player dies, which can't happen without them getting hurt
+        if summary.player_died {
+            assert!(summary.damage_taken > 0);
+        } else {
+            assert_eq!(summary.turns_run, 200);
+        }
+        assert!(summary.final_depth >= 1);
+    }
+
+    #[test]
+    fn bad_arguments_are_reported_instead_of_panicking() {
+        let parse =
+            |args: &[&str]| SimulationConfig::from_args(args.iter().map(|arg| arg.to_string()));
+
+        assert!(matches!(parse(&["--seed", "3"]), Ok(None)));
+        assert!(parse(&["--headless", "--seed", "three"]).is_err());
+        assert!(parse(&["--headless", "--policy", "smart"]).is_err());
+        assert!(parse(&["--headless", "--script", "n,jump"]).is_err());
+
+        let config = parse(&["--headless", "--seed", "3", "--script", "n,wait"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.seed, 3);
+        assert!(matches!(
+            config.policy,
+            PlayerPolicy::Scripted(commands) if commands.len() == 2
+        ));
+    }
+
+    #[test]
+    fn same_seed_builds_the_same_floors() {
+        let build = |seed: u64| {
+            let mut state = test_state(seed);
+            go_down_stairs(&mut state);
+
+            let mut spawns = state
+                .world
+                .query::<(&Name, &Position)>()
+                .iter()
+                .map(|(_ent, (name, pos))| (name.name.clone(), pos.as_tuple()))
+                .collect::<Vec<_>>();
+            spawns.sort();
+
+            (state.map.map, spawns)
+        };
+
+        let first = build(77);
+        assert_eq!(first, build(77));
+        assert_ne!(first.0, build(78).0);
+    }
+
+    #[test]
+    fn replays_answer_menus_and_targetting_the_same_way() {
+        let seed = 11;
+
+        //a replay rebuilds the world from its seed, so the ration ends up with the same id in every run
+        let (ration, target) = {
+            let state = test_state(seed);
+            let ration = state
+                .world
+                .query::<&Name>()
+                .iter()
+                .find(|(_ent, name)| name.name == "Ration")
+                .map(|(ent, _)| ent)
+                .unwrap();
+            (ration, state.player_pos + Point::new(3, 0))
+        };
+
+        let path = std::env::temp_dir().join("replays_answer_menus_and_targetting.jsonl");
+        let mut recorder = Recorder::create(&path, seed).unwrap();
+        for input in [
+            PlayerInput::Command(Command::Inventory),
+            PlayerInput::menu(Some(&vec![ration])),
+            PlayerInput::Command(Command::Fire),
+            PlayerInput::Target(Some(target)),
+            PlayerInput::Command(Command::Wait),
+        ] {
+            recorder.record(0, input).unwrap();
+        }
+
+        let replay = |path: &Path| {
+            simulate(
+                SimulationConfig::from_args(
+                    ["--headless", "--replay", path.to_str().unwrap()]
+                        .iter()
+                        .map(|arg| arg.to_string()),
+                )
+                .unwrap()
+                .unwrap(),
+            )
+            .0
+        };
+        let first = replay(&path);
+        let second = replay(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let snapshot = |state: &State| {
+            let player = state.player_ent.unwrap();
+            let rounds = state
+                .world
+                .query::<&RangedWeapon>()
+                .iter()
+                .map(|(_ent, weapon)| weapon.rounds)
+                .sum::<i32>();
+
+            (
+                state.world.get::<&Position>(player).unwrap().as_tuple(),
+                state
+                    .world
+                    .get::<&Pools>(player)
+                    .unwrap()
+                    .hitpoints
+                    .current_value,
+                state
+                    .world
+                    .get::<&HungerLevel>(player)
+                    .unwrap()
+                    .nutrition
+                    .current_value,
+                rounds,
+                state.turn_number,
+                state.game_log.entries.clone(),
+            )
+        };
+
+        //the ration was eaten and the pistol fired rather than either being swapped for a wait
+        assert!(!first.world.contains(ration));
+        let magazine = first
+            .world
+            .query::<&RangedWeapon>()
+            .iter()
+            .map(|(_ent, weapon)| weapon.magazine)
+            .sum::<i32>();
+        assert_eq!(snapshot(&first).3, magazine - 1);
+
+        assert_eq!(snapshot(&first), snapshot(&second));
+    }
+}
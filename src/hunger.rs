@@ -1,4 +1,5 @@
 use bracket_lib::prelude::console;
+use serde::{Deserialize, Serialize};
 
 use crate::{damage_system::DamageSystem, effects::{add_effect, EffectType, Targets}, statistics::{BaseStatistics, Pools, StatPool}, Name, State, WantsToRest};
 
@@ -11,7 +12,7 @@ pub enum HungerState
     Hungry,
     Starving
 }
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct HungerLevel
 {
     pub nutrition: StatPool,
@@ -78,7 +79,7 @@ pub fn hunger_system(state: &mut State)
                     console::log(msg);
 
                     
-                    add_effect(None, EffectType::Damage { amount: 3 }, Targets::Single { target: _id })
+                    add_effect(None, EffectType::Damage { amount: 3, damage_type: None }, Targets::Single { target: _id })
                 }else
                 {
                     let msg = format!("{} died of starvation!", name.name.clone());
@@ -0,0 +1,196 @@
+use std::collections::{HashMap, HashSet};
+
+use bracket_lib::random::RandomNumberGenerator;
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::Interactable,
+    raws::{self, RAWS},
+    statistics::{skill_check, Skill, StatType},
+    InContainer, Name, Position, State,
+};
+
+///Difficulty of working out what an item is at a diagnostic terminal
+pub const IDENTIFY_DIFFICULTY: i32 = 12;
+
+const DESCRIPTORS: [&str; 8] = [
+    "unlabelled",
+    "scratched",
+    "bootleg",
+    "humming",
+    "sticky",
+    "grey-market",
+    "dented",
+    "hand-soldered",
+];
+const COLOURS: [&str; 8] = [
+    "blue", "red", "green", "amber", "violet", "white", "black", "chrome",
+];
+
+///What the unidentified kinds of item are called this run and which of them the player has worked out
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Identification {
+    ///real name to the name it goes by until it's identified
+    obfuscated: HashMap<String, String>,
+    identified: HashSet<String>,
+}
+
+impl Identification {
+    pub fn new() -> Identification {
+        Identification::default()
+    }
+
+    ///Makes up a different name for every unidentified item in the raws, e.g. "unlabelled blue injector". The same
+    /// seed always hands out the same names
+    pub fn generate(unidentified: &[(String, String)], seed: u64) -> Identification {
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        let mut obfuscated = HashMap::new();
+        let mut used = HashSet::new();
+
+        for (name, kind) in unidentified.iter() {
+            let mut fake = String::new();
+            for _ in 0..DESCRIPTORS.len() * COLOURS.len() {
+                fake = format!(
+                    "{} {} {}",
+                    rng.random_slice_entry(&DESCRIPTORS).unwrap(),
+                    rng.random_slice_entry(&COLOURS).unwrap(),
+                    kind
+                );
+                if !used.contains(&fake) {
+                    break;
+                }
+            }
+            used.insert(fake.clone());
+            obfuscated.insert(name.clone(), fake);
+        }
+
+        Identification {
+            obfuscated,
+            identified: HashSet::new(),
+        }
+    }
+
+    pub fn is_identified(&self, name: &str) -> bool {
+        !self.obfuscated.contains_key(name) || self.identified.contains(name)
+    }
+
+    ///The name the player knows the item by
+    pub fn display_name(&self, name: &str) -> String {
+        match self.obfuscated.get(name) {
+            Some(fake) if !self.identified.contains(name) => fake.clone(),
+            _ => name.to_string(),
+        }
+    }
+
+    ///Returns whether the item was still unidentified
+    pub fn identify(&mut self, name: &str) -> bool {
+        !self.is_identified(name) && self.identified.insert(name.to_string())
+    }
+}
+
+///Prop that can work out what the player's unidentified items are
+#[derive(Serialize, Deserialize)]
+pub struct DiagnosticTerminal;
+
+///Hands out this run's names, called once the raws are loaded
+pub fn generate_names(state: &mut State) {
+    let unidentified = raws::unidentified_items(&RAWS.lock().unwrap());
+    //kept off the run's rng so the floors don't change depending on the raws
+    state.identification = Identification::generate(&unidentified, !state.seed);
+}
+
+///What the item is called as far as the player knows
+pub fn item_name(state: &State, item: Entity) -> String {
+    state
+        .world
+        .get::<&Name>(item)
+        .map_or("item".to_string(), |name| {
+            state.identification.display_name(&name.name)
+        })
+}
+
+///Identifies every item of the same kind, letting the player know what it was
+pub fn identify_item(state: &mut State, item: Entity) {
+    let Ok(name) = state.world.get::<&Name>(item).map(|name| name.name.clone()) else {
+        return;
+    };
+    let fake = state.identification.display_name(&name);
+
+    if state.identification.identify(&name) {
+        state
+            .game_log
+            .add_log(format!("The {} was a {}!", fake, name));
+    }
+}
+
+///Diagnostic terminal the player is trying to use at the given tile, if there is one
+pub fn terminal_at(state: &mut State, idx: usize) -> Option<Entity> {
+    let width = state.map.map_width;
+
+    state
+        .world
+        .query_mut::<(&Interactable, &DiagnosticTerminal, &Position)>()
+        .into_iter()
+        .find(|(_ent, (_, _, pos))| (pos.y * width + pos.x) as usize == idx)
+        .map(|(ent, _)| ent)
+}
+
+///Items in the owner's backpack the player doesn't know the names of yet
+pub fn unidentified_items(state: &State, owner: Entity) -> Vec<Entity> {
+    state
+        .world
+        .query::<(&Name, &InContainer)>()
+        .iter()
+        .filter(|(_ent, (name, cont))| {
+            cont.owner == owner && !state.identification.is_identified(&name.name)
+        })
+        .map(|(ent, _)| ent)
+        .collect()
+}
+
+///Runs the item through a diagnostic terminal, a Hack check using Intelligence decides if it makes sense of it
+pub fn terminal_identify(state: &mut State, user: Entity, item: Entity) {
+    let fake = item_name(state, item);
+
+    if skill_check(
+        Skill::Hack,
+        StatType::Intelligence,
+        user,
+        state,
+        IDENTIFY_DIFFICULTY,
+    ) {
+        identify_item(state, item);
+    } else {
+        state
+            .game_log
+            .add_log(format!("The terminal can't make sense of the {}.", fake));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_hidden_until_identified() {
+        let unidentified = vec![
+            ("Health Injector".to_string(), "injector".to_string()),
+            ("Focus Chip".to_string(), "chip".to_string()),
+        ];
+        let mut ident = Identification::generate(&unidentified, 3);
+        assert_eq!(
+            Identification::generate(&unidentified, 3).display_name("Focus Chip"),
+            ident.display_name("Focus Chip")
+        );
+
+        let fake = ident.display_name("Health Injector");
+        assert!(fake.ends_with(" injector") && fake != "Health Injector");
+        assert_ne!(fake, ident.display_name("Focus Chip"));
+        assert_eq!(ident.display_name("Katana"), "Katana");
+
+        assert!(ident.identify("Health Injector"));
+        assert!(!ident.identify("Health Injector"));
+        assert_eq!(ident.display_name("Health Injector"), "Health Injector");
+    }
+}
@@ -2,24 +2,40 @@ use std::collections::{HashMap, HashSet, VecDeque};
 
 use bracket_lib::prelude::Point;
 use macroquad::input::KeyCode;
+use serde::{Deserialize, Serialize};
 
-use crate::{gui::mqui::DevConsole, utils};
+use crate::{gamelog::DEBUGLOG, gui::mqui::DevConsole, utils};
 
-use super::keymap::default_keymap;
+use super::{
+    keymap::default_keymap,
+    recording::{PlayerInput, RecordedCommand, Recorder, Replay},
+};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+///Advances a replay by one command when it's being stepped through
+const REPLAY_STEP_KEY: KeyCode = KeyCode::Space;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 //internal representation of actions to be taken by player, to decouple them from having fixed keys
 pub enum Command {
     Move { pos: Point },
     Wait,
     GoDownStairs,
+    GoUpStairs,
     Inventory,
     Pickup,
     Drop,
     Equip,
     Unequip,
     Fire,
+    Reload,
+    Throw,
     Look,
+    Hack,
+    Implants,
+    Craft,
+    Talk,
+    Character,
+    Journal,
     Save,
 
     Quit,
@@ -34,6 +50,11 @@ pub struct CommandManager {
     cooldown: utils::timer::Timer,
     command_locked: bool,
     keymap: HashMap<KeyCode, Command>,
+    recorder: Option<Recorder>,
+    ///while this has commands in it they are played back instead of reading the keyboard
+    replay: VecDeque<RecordedCommand>,
+    replay_step: bool,
+    step_requested: bool,
 }
 
 impl CommandManager {
@@ -43,6 +64,10 @@ impl CommandManager {
             cooldown: utils::timer::Timer::new_stopped(0.15),
             command_locked: false,
             keymap: default_keymap(), //todo: actually add keybindings and keybinding loading
+            recorder: None,
+            replay: VecDeque::new(),
+            replay_step: false,
+            step_requested: false,
         }
     }
 
@@ -50,6 +75,9 @@ impl CommandManager {
         self.command_queue.clear();
         self.cooldown.tick(delta_t);
         //if !self.command_locked {
+        if self.replay_step && inputs.contains(&REPLAY_STEP_KEY) {
+            self.step_requested = true;
+        }
         self.translate_inputs(inputs);
         //}
     }
@@ -65,20 +93,145 @@ impl CommandManager {
         }
     }
 
-    pub fn get_command(&mut self) -> Option<Command> {
+    ///Takes the next command to carry out on the given turn, from the replay if one is running otherwise from the keyboard
+    pub fn get_command(&mut self, turn: i32) -> Option<Command> {
         if self.command_locked || !self.cooldown.timer_elapsed() {
             None
+        } else if !self.replay.is_empty() {
+            self.next_replayed(turn)
         } else {
             let cmd = self.command_queue.pop_front();
 
-            if cmd.is_some() {
+            if let Some(command) = cmd {
                 self.cooldown.reset();
+                self.record(turn, PlayerInput::Command(command));
             }
 
             cmd
         }
     }
 
+    ///Commands that come through here are recorded as they're taken, menus, targetting and the dev console record
+    /// the player's answers themselves
+    pub fn start_recording(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
+    }
+
+    pub fn start_replay(&mut self, replay: Replay, step: bool) {
+        self.replay = replay.commands.into();
+        self.replay_step = step;
+        self.step_requested = false;
+
+        if step {
+            DEBUGLOG.add_log(format!(
+                "Replaying {} commands, press {:?} to step",
+                self.replay.len(),
+                REPLAY_STEP_KEY
+            ));
+        }
+    }
+
+    fn next_replayed(&mut self, turn: i32) -> Option<Command> {
+        //the game only asks for a command once it's done with whatever the last one opened, so an answer still
+        // waiting here was never asked for and the replay has gone out of sync
+        while self
+            .replay
+            .front()
+            .is_some_and(|recorded| recorded.input.is_answer())
+        {
+            let skipped = self.replay.pop_front().unwrap();
+            DEBUGLOG.add_log(format!(
+                "Replay out of sync, skipping {:?} recorded on turn {}",
+                skipped.input, skipped.turn
+            ));
+        }
+
+        //console commands are left for the console to pick up
+        let PlayerInput::Command(command) = self.replay.front()?.input else {
+            return None;
+        };
+        if self.replay_step && !std::mem::take(&mut self.step_requested) {
+            return None;
+        }
+
+        self.pop_replayed(turn);
+        self.cooldown.reset();
+
+        Some(command)
+    }
+
+    ///The answer to the menu or targetting the game is waiting on, if it's what the replay has next
+    pub fn next_replayed_answer(&mut self, turn: i32) -> Option<PlayerInput> {
+        if !self
+            .replay
+            .front()
+            .is_some_and(|recorded| recorded.input.is_answer())
+        {
+            return None;
+        }
+
+        self.pop_replayed(turn)
+    }
+
+    ///The dev console command to run, if it's what the replay has next
+    pub fn next_replayed_console(&mut self, turn: i32) -> Option<String> {
+        if !matches!(
+            self.replay.front(),
+            Some(RecordedCommand {
+                input: PlayerInput::Console(_),
+                ..
+            })
+        ) {
+            return None;
+        }
+
+        match self.pop_replayed(turn) {
+            Some(PlayerInput::Console(command)) => Some(command),
+            _ => None,
+        }
+    }
+
+    fn pop_replayed(&mut self, turn: i32) -> Option<PlayerInput> {
+        let recorded = self.replay.pop_front()?;
+
+        if recorded.turn != turn {
+            DEBUGLOG.add_log(format!(
+                "Replay out of sync, {:?} was recorded on turn {} but it's turn {}",
+                recorded.input, recorded.turn, turn
+            ));
+        }
+        if self.replay.is_empty() {
+            DEBUGLOG.add_log("Replay finished, the keyboard is back in control".to_string());
+        }
+
+        Some(recorded.input)
+    }
+
+    pub fn record(&mut self, turn: i32, input: PlayerInput) {
+        //these don't change the game so replaying them would only get in the way
+        if matches!(
+            input,
+            PlayerInput::Command(
+                Command::Save
+                    | Command::DevConsole
+                    | Command::Character
+                    | Command::Journal
+                    | Command::Look
+                    | Command::Quit
+                    | Command::None
+            )
+        ) {
+            return;
+        }
+
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(err) = recorder.record(turn, input) {
+                DEBUGLOG.add_log(format!("Error: stopped recording, {}", err));
+                self.recorder = None;
+            }
+        }
+    }
+
     pub fn disable(&mut self) {
         self.command_locked = true;
     }
@@ -95,6 +248,10 @@ impl Default for CommandManager {
             cooldown: utils::timer::Timer::new(0.17),
             command_locked: false,
             keymap: HashMap::new(),
+            recorder: None,
+            replay: VecDeque::new(),
+            replay_step: false,
+            step_requested: false,
         }
     }
 }
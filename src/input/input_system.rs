@@ -2,23 +2,32 @@ use bracket_lib::prelude::Point;
 use hecs::Entity;
 
 use crate::{
-    ai::{apply_energy_cost, MyTurn},
+    ai::{apply_energy_cost, footstep_noise, make_noise, MyTurn},
     attack_system::AttackSystem,
     camera,
     components::{
         BlocksTiles, BlocksVisibility, Door, EquipmentSlot, Equipped, FoV, HasMoved, InContainer,
         Item, Name, RangedWeapon, Renderable, WantsToPickupItem, WantsToRest,
     },
-    effects::{add_effect, EffectType, Targets},
+    crafting, cybernetics, dialogue,
+    effects::{add_effect, confused_direction, EffectType, Targets},
     gamelog::DEBUGLOG,
-    go_down_stairs,
-    gui::{mqui::ItemWindowMode, TargettingMode},
+    go_down_stairs, go_up_stairs,
+    gui::{
+        mqui::{DialogueWindow, ItemWindowMode},
+        TargettingMode,
+    },
+    identification,
     map_indexing::SPATIAL_INDEX,
     maps::TileType,
+    networks,
     player::Player,
-    ranged_combat::ranged_aim::select_nearest_target_pos,
+    ranged_combat::{ammo, ranged_aim::select_nearest_target_pos},
+    saveload,
     screen_manager::{self, MANAGER},
     statistics::Pools,
+    trading,
+    utils::get_mob_entities_at_position,
     Position, ProgramState, State,
 };
 
@@ -26,55 +35,330 @@ use super::{Command, INPUT};
 
 ///Rework of player_input_system using the new command enum instead of direct reading of inputs
 pub fn input_system(state: &mut State) -> ProgramState {
-    if let Some(command) = INPUT.lock().get_command() {
-        match command {
-            Command::Move { pos } => attempt_move(state, pos.x, pos.y),
-            Command::Drop => drop_item(state),
-            Command::GoDownStairs => {
-                let idx = state.map.xy_idx(state.player_pos.x, state.player_pos.y);
-                if state.map.map[idx] == TileType::DownStairs {
-                    go_down_stairs(state);
-                    return ProgramState::Ticking;
-                } else {
-                    return ProgramState::AwaitingInput;
-                }
+    if let Some(command) = INPUT.lock().get_command(state.turn_number) {
+        handle_command(state, command)
+    } else {
+        ProgramState::AwaitingInput
+    }
+}
+
+///Carries out a single player command, returning the state the game should move to
+pub fn handle_command(state: &mut State, command: Command) -> ProgramState {
+    match command {
+        Command::Move { pos } => {
+            let pos = confused_direction(state, state.player_ent.unwrap(), pos);
+            attempt_move(state, pos.x, pos.y)
+        }
+        Command::Drop => drop_item(state),
+        Command::GoDownStairs => {
+            let idx = state.map.xy_idx(state.player_pos.x, state.player_pos.y);
+            if state.map.map[idx] == TileType::DownStairs {
+                go_down_stairs(state);
+                return ProgramState::Ticking;
+            } else {
+                return ProgramState::AwaitingInput;
             }
-            Command::Unequip => unequip_item(state),
-            Command::Inventory => open_inventory(state),
-            Command::Wait => {
-                {
-                    state
-                        .world
-                        .insert_one(state.player_ent.unwrap(), WantsToRest {})
-                        .expect("Couldn't insert WantsToRest componenent onto player!");
-                    let _ = state.world.remove_one::<MyTurn>(state.player_ent.unwrap());
-                    //apply_energy_cost(state, crate::ai::ActionType::Move, state.player_ent.unwrap());
-                    return ProgramState::Ticking;
-                }
+        }
+        Command::GoUpStairs => {
+            let idx = state.map.xy_idx(state.player_pos.x, state.player_pos.y);
+            if state.map.map[idx] == TileType::UpStairs {
+                go_up_stairs(state);
+                return ProgramState::Ticking;
+            } else {
+                return ProgramState::AwaitingInput;
             }
-            Command::Fire => fire_ranged_weapon(state),
-            Command::Pickup => pickup_items(state),
-            Command::Look => {
-                let (min_x, _, min_y, _) = camera::get_screen_bounds(state);
-
-                let player_pos = state.player_pos;
-                let px = player_pos.x;
-                let py = player_pos.y;
-
-                return ProgramState::KeyboardTargetting {
-                    cursor_pos: Point::new(px - min_x, py - min_y),
-                };
+        }
+        Command::Unequip => unequip_item(state),
+        Command::Inventory => open_inventory(state),
+        Command::Wait => {
+            {
+                state
+                    .world
+                    .insert_one(state.player_ent.unwrap(), WantsToRest {})
+                    .expect("Couldn't insert WantsToRest componenent onto player!");
+                let _ = state.world.remove_one::<MyTurn>(state.player_ent.unwrap());
+                //apply_energy_cost(state, crate::ai::ActionType::Move, state.player_ent.unwrap());
+                return ProgramState::Ticking;
             }
+        }
+        Command::Fire => fire_ranged_weapon(state),
+        Command::Reload => reload_ranged_weapon(state),
+        Command::Throw => open_throw_menu(state),
+        Command::Pickup => pickup_items(state),
+        Command::Look => {
+            let (min_x, _, min_y, _) = camera::get_screen_bounds(state);
+
+            let player_pos = state.player_pos;
+            let px = player_pos.x;
+            let py = player_pos.y;
+
+            return ProgramState::KeyboardTargetting {
+                cursor_pos: Point::new(px - min_x, py - min_y),
+            };
+        }
+
+        Command::DevConsole => {
+            MANAGER.lock().unwrap().toggle_view();
+            ProgramState::AwaitingInput
+        }
+
+        Command::Character => {
+            MANAGER.lock().unwrap().toggle_character_screen();
+            ProgramState::AwaitingInput
+        }
 
-            Command::DevConsole => {
-                MANAGER.lock().unwrap().toggle_view();
-                ProgramState::AwaitingInput
+        Command::Journal => {
+            MANAGER.lock().unwrap().toggle_journal();
+            ProgramState::AwaitingInput
+        }
+
+        Command::Hack => open_hacking_menu(state),
+
+        Command::Implants => open_implant_menu(state),
+
+        Command::Craft => open_crafting_window(state),
+        Command::Talk => talk(state),
+
+        Command::Save => {
+            match saveload::save_game(state) {
+                Ok(()) => state.game_log.add_log("Game saved.".to_string()),
+                Err(err) => state
+                    .game_log
+                    .add_log(format!("Failed to save the game: {}", err)),
             }
+            ProgramState::AwaitingInput
+        }
+
+        _ => ProgramState::AwaitingInput,
+    }
+}
+
+fn open_hacking_menu(state: &mut State) -> ProgramState {
+    let nodes = networks::reachable_nodes(state)
+        .into_iter()
+        .map(|node| (node, false))
+        .collect::<Vec<_>>();
+
+    if nodes.is_empty() {
+        state
+            .game_log
+            .add_log("Your deck can't find any networks to connect to.".to_string());
+        return ProgramState::AwaitingInput;
+    }
+
+    networks::run_hack_command(
+        state,
+        networks::Command::ListNodes,
+        state.player_ent.unwrap(),
+    );
+
+    let menu_type = screen_manager::MenuType::Hack;
+    MANAGER.lock().unwrap().create_menu(
+        nodes,
+        "Network:".to_string(),
+        crate::gui::mqui::ItemWindowMode::Single,
+        menu_type,
+        state,
+    );
+
+    ProgramState::AwaitingMenu {
+        response: None,
+        menu_type,
+    }
+}
+
+fn open_implant_menu(state: &mut State) -> ProgramState {
+    let abilities = cybernetics::implant_abilities(state, state.player_ent.unwrap());
+
+    if abilities.is_empty() {
+        state
+            .game_log
+            .add_log("None of your implants can be activated.".to_string());
+        return ProgramState::AwaitingInput;
+    }
+
+    let ready = abilities
+        .into_iter()
+        .filter(|(_ent, ready)| *ready)
+        .map(|(ent, _)| (ent, false))
+        .collect::<Vec<_>>();
+
+    if ready.is_empty() {
+        state
+            .game_log
+            .add_log("Your implants are still recharging.".to_string());
+        return ProgramState::AwaitingInput;
+    }
+
+    let menu_type = screen_manager::MenuType::Implants;
+    MANAGER.lock().unwrap().create_menu(
+        ready,
+        "Implants:".to_string(),
+        ItemWindowMode::Single,
+        menu_type,
+        state,
+    );
+
+    ProgramState::AwaitingMenu {
+        response: None,
+        menu_type,
+    }
+}
+
+///Walking into a diagnostic terminal offers to work out what any unidentified items the player is carrying are
+fn open_identify_menu(state: &mut State) -> ProgramState {
+    let items = identification::unidentified_items(state, state.player_ent.unwrap())
+        .into_iter()
+        .map(|ent| (ent, false))
+        .collect::<Vec<_>>();
+
+    if items.is_empty() {
+        state
+            .game_log
+            .add_log("You aren't carrying anything the terminal could identify.".to_string());
+        return ProgramState::AwaitingInput;
+    }
+
+    let menu_type = screen_manager::MenuType::Identify;
+    MANAGER.lock().unwrap().create_menu(
+        items,
+        "Identify:".to_string(),
+        ItemWindowMode::Single,
+        menu_type,
+        state,
+    );
+
+    ProgramState::AwaitingMenu {
+        response: None,
+        menu_type,
+    }
+}
+
+///Lists every recipe the player knows, whether they have what it takes to make them or not
+fn open_crafting_window(state: &mut State) -> ProgramState {
+    let recipes = crafting::known_recipes(state, state.player_ent.unwrap());
+
+    if recipes.is_empty() {
+        state
+            .game_log
+            .add_log("You don't know how to make anything.".to_string());
+        return ProgramState::AwaitingInput;
+    }
+
+    MANAGER.lock().unwrap().open_crafting(recipes, state);
+
+    ProgramState::Crafting { recipe: None }
+}
+
+///Strikes up a conversation with whoever is next to the player, asking who to talk to if there's more than one
+fn talk(state: &mut State) -> ProgramState {
+    let talkers = dialogue::talkers_near(state, state.player_ent.unwrap());
 
-            _ => ProgramState::AwaitingInput,
+    match talkers.as_slice() {
+        [] => {
+            state
+                .game_log
+                .add_log("There's nobody nearby to talk to.".to_string());
+            ProgramState::AwaitingInput
         }
-    } else {
-        ProgramState::AwaitingInput
+        [speaker] => open_dialogue(state, *speaker, None),
+        _ => {
+            let menu_type = screen_manager::MenuType::Talk;
+            MANAGER.lock().unwrap().create_menu(
+                talkers.iter().map(|ent| (*ent, false)).collect(),
+                "Talk to:".to_string(),
+                ItemWindowMode::Single,
+                menu_type,
+                state,
+            );
+
+            ProgramState::AwaitingMenu {
+                response: None,
+                menu_type,
+            }
+        }
+    }
+}
+
+///Shows what the speaker says at the given node of their dialogue tree, or how they open the conversation if there's
+/// no node
+pub fn open_dialogue(state: &mut State, speaker: Entity, node: Option<&str>) -> ProgramState {
+    let Some(node) = dialogue::dialogue_node(state, speaker, node) else {
+        return ProgramState::AwaitingInput;
+    };
+    let choices = dialogue::available_choices(state, state.player_ent.unwrap(), &node);
+    let speaker_name = state
+        .world
+        .get::<&Name>(speaker)
+        .map_or("Stranger".to_string(), |name| name.name.clone());
+
+    MANAGER.lock().unwrap().open_dialogue(
+        DialogueWindow::new(speaker, speaker_name, node.text, choices),
+        state,
+    );
+
+    ProgramState::Talking {
+        speaker,
+        choice: None,
+    }
+}
+
+///Walking into a vendor lists what they have for sale and what they'd buy off the player, also called again after
+/// every trade so the player can keep haggling
+pub fn open_trade_menu(state: &mut State, vendor: Entity) -> ProgramState {
+    let player = state.player_ent.unwrap();
+    let goods = trading::trade_goods(state, player, vendor)
+        .into_iter()
+        .filter_map(|item| {
+            trading::trade_price(state, player, vendor, item).map(|price| (item, price))
+        })
+        .collect::<Vec<_>>();
+
+    if goods.is_empty() {
+        state
+            .game_log
+            .add_log("There's nothing to trade here.".to_string());
+        return ProgramState::AwaitingInput;
+    }
+
+    let title = trading::trade_title(state, player, vendor);
+    MANAGER
+        .lock()
+        .unwrap()
+        .open_trade(vendor, goods, title, state);
+
+    ProgramState::AwaitingMenu {
+        response: None,
+        menu_type: screen_manager::MenuType::Trade { vendor },
+    }
+}
+
+///Walking into a ripper-doc's chair offers to install any implants the player is carrying
+fn open_install_menu(state: &mut State) -> ProgramState {
+    let implants = cybernetics::carried_implants(state, state.player_ent.unwrap())
+        .into_iter()
+        .map(|ent| (ent, false))
+        .collect::<Vec<_>>();
+
+    if implants.is_empty() {
+        state
+            .game_log
+            .add_log("You aren't carrying any implants to install.".to_string());
+        return ProgramState::AwaitingInput;
+    }
+
+    let menu_type = screen_manager::MenuType::Install;
+    MANAGER.lock().unwrap().create_menu(
+        implants,
+        "Install:".to_string(),
+        ItemWindowMode::Single,
+        menu_type,
+        state,
+    );
+
+    ProgramState::AwaitingMenu {
+        response: None,
+        menu_type,
     }
 }
 
@@ -161,11 +445,18 @@ fn fire_ranged_weapon(state: &mut State) -> ProgramState {
             // .expect("Couldn't get player ent for damage for ranged combat"))
             // .expect("Couldn't get the Combat stats for player for ranged combat").power.total;
 
-            let range = state
+            let (range, rounds) = state
                 .world
                 .query_one_mut::<&RangedWeapon>(query[0])
-                .expect("Couldn't get range of players ranged weapon for range combat")
-                .range;
+                .map(|ranged| (ranged.range, ranged.rounds))
+                .expect("Couldn't get range of players ranged weapon for range combat");
+
+            if rounds < 1 {
+                state
+                    .game_log
+                    .add_log("Your weapon is empty, reload it first.".to_string());
+                return ProgramState::AwaitingInput;
+            }
 
             if let TargettingMode::Keyboard { cursor_pos } = state.target_mode {
                 state.target_mode = TargettingMode::Keyboard {
@@ -181,6 +472,18 @@ fn fire_ranged_weapon(state: &mut State) -> ProgramState {
     }
 }
 
+fn reload_ranged_weapon(state: &mut State) -> ProgramState {
+    let player = state.player_ent.unwrap();
+
+    if ammo::reload(state, player) {
+        let _ = state.world.remove_one::<MyTurn>(player);
+        apply_energy_cost(state, crate::ai::ActionType::Reload, player);
+        ProgramState::Ticking
+    } else {
+        ProgramState::AwaitingInput
+    }
+}
+
 fn open_inventory(state: &mut State) -> ProgramState {
     {
         let items = state
@@ -261,7 +564,69 @@ fn drop_item(state: &mut State) -> ProgramState {
     }
 }
 
+fn open_throw_menu(state: &mut State) -> ProgramState {
+    let items = state
+        .world
+        .query::<&InContainer>()
+        .iter()
+        .filter(|(_ent, cont)| cont.owner == state.player_ent.unwrap())
+        .map(|(ent, _cont)| (ent, false))
+        .collect::<Vec<_>>();
+
+    let menu_type = screen_manager::MenuType::Throw;
+    MANAGER.lock().unwrap().create_menu(
+        items,
+        "Throw Item:".to_string(),
+        crate::gui::mqui::ItemWindowMode::Single,
+        menu_type,
+        state,
+    );
+
+    ProgramState::AwaitingMenu {
+        response: None,
+        menu_type,
+    }
+}
+
 fn attempt_move(state: &mut State, delta_x: i32, delta_y: i32) -> ProgramState {
+    let destination = state
+        .map
+        .xy_idx(state.player_pos.x + delta_x, state.player_pos.y + delta_y);
+    if destination < state.map.map.len() && cybernetics::ripper_doc_at(state, destination).is_some()
+    {
+        return open_install_menu(state);
+    }
+    if destination < state.map.map.len()
+        && identification::terminal_at(state, destination).is_some()
+    {
+        return open_identify_menu(state);
+    }
+    if destination < state.map.map.len() && crafting::workbench_at(state, destination).is_some() {
+        return open_crafting_window(state);
+    }
+    if destination < state.map.map.len() {
+        if let Some(vendor) = trading::vendor_at(state, destination) {
+            return open_trade_menu(state, vendor);
+        }
+    }
+    if destination < state.map.map.len() {
+        if let Some(prop) = dialogue::talking_prop_at(state, destination) {
+            return open_dialogue(state, prop, None);
+        }
+    }
+    //anyone standing in the junk gets attacked instead
+    let target = Point::new(state.player_pos.x + delta_x, state.player_pos.y + delta_y);
+    if destination < state.map.map.len() && get_mob_entities_at_position(state, target).is_empty() {
+        if let Some(prop) = crafting::salvage_at(state, destination) {
+            let player = state.player_ent.unwrap();
+            crafting::salvage(state, player, prop);
+
+            let _ = state.world.remove_one::<MyTurn>(player);
+            apply_energy_cost(state, crate::ai::ActionType::Salvage, player);
+            return ProgramState::Ticking;
+        }
+    }
+
     match try_move(state, delta_x, delta_y) {
         true => {
             return ProgramState::Ticking;
@@ -347,6 +712,9 @@ pub fn try_move(state: &mut State, delta_x: i32, delta_y: i32) -> bool {
             state.player_ent.unwrap(),
         );
         let _ = state.world.remove_one::<MyTurn>(state.player_ent.unwrap());
+
+        let noise = footstep_noise(state, state.player_ent.unwrap());
+        make_noise(state, state.player_ent.unwrap(), noise);
     }
 
     let contents = spatial_map.get_tile_contents(destination_id);
@@ -46,9 +46,19 @@ pub fn default_keymap() -> HashMap<KeyCode, Command> {
     keys.insert(KeyCode::Semicolon, Command::Look);
     keys.insert(KeyCode::Kp5, Command::Wait);
     keys.insert(KeyCode::F, Command::Fire);
+    keys.insert(KeyCode::L, Command::Reload);
+    keys.insert(KeyCode::T, Command::Throw);
     keys.insert(KeyCode::Period, Command::GoDownStairs);
+    keys.insert(KeyCode::Comma, Command::GoUpStairs);
     keys.insert(KeyCode::G, Command::Pickup);
     keys.insert(KeyCode::Apostrophe, Command::DevConsole);
+    keys.insert(KeyCode::H, Command::Hack);
+    keys.insert(KeyCode::C, Command::Implants);
+    keys.insert(KeyCode::B, Command::Craft);
+    keys.insert(KeyCode::E, Command::Talk);
+    keys.insert(KeyCode::K, Command::Character);
+    keys.insert(KeyCode::J, Command::Journal);
+    keys.insert(KeyCode::F5, Command::Save);
 
     keys
 }
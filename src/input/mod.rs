@@ -1,8 +1,10 @@
 mod commands;
 mod input_system;
 mod keymap;
+mod recording;
 pub use commands::*;
 pub use input_system::*;
+pub use recording::*;
 
 use macroquad::input::KeyCode;
 use std::{
@@ -64,8 +66,29 @@ impl InputManager {
         );
     }
 
-    pub fn get_command(&mut self) -> Option<Command> {
-        self.commands.get_command()
+    pub fn get_command(&mut self, turn: i32) -> Option<Command> {
+        self.commands.get_command(turn)
+    }
+
+    pub fn start_recording(&mut self, recorder: Recorder) {
+        self.commands.start_recording(recorder);
+    }
+
+    pub fn start_replay(&mut self, replay: Replay, step: bool) {
+        self.commands.start_replay(replay, step);
+    }
+
+    pub fn next_replayed_answer(&mut self, turn: i32) -> Option<PlayerInput> {
+        self.commands.next_replayed_answer(turn)
+    }
+
+    pub fn next_replayed_console(&mut self, turn: i32) -> Option<String> {
+        self.commands.next_replayed_console(turn)
+    }
+
+    ///Records the player's answer to a menu or targetting, or a dev console command, if a recording is running
+    pub fn record(&mut self, turn: i32, input: PlayerInput) {
+        self.commands.record(turn, input);
     }
 
     pub fn disable_input(&mut self) {
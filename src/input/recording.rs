@@ -0,0 +1,245 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use bracket_lib::prelude::Point;
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::raws::DialogueChoice;
+
+use super::Command;
+
+///Bump this whenever a change to Command or PlayerInput makes older replays unreadable
+pub const REPLAY_VERSION: u32 = 2;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io {
+        path: PathBuf,
+        err: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        line: usize,
+        err: serde_json::Error,
+    },
+    WrongVersion {
+        found: u32,
+        expected: u32,
+    },
+    Empty {
+        path: PathBuf,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io { path, err } => {
+                write!(f, "couldn't access replay {}: {}", path.display(), err)
+            }
+            ReplayError::Parse { path, line, err } => write!(
+                f,
+                "replay {} is malformed on line {}: {}",
+                path.display(),
+                line,
+                err
+            ),
+            ReplayError::WrongVersion { found, expected } => write!(
+                f,
+                "replay is version {} but version {} is required",
+                found, expected
+            ),
+            ReplayError::Empty { path } => write!(f, "replay {} is empty", path.display()),
+        }
+    }
+}
+
+///First line of a replay file, every line after it is a RecordedCommand
+#[derive(Serialize, Deserialize)]
+struct ReplayHeader {
+    version: u32,
+    seed: u64,
+}
+
+///Everything the player decides, the commands themselves and their answers to whatever menu or targetting the
+/// commands opened up. The answers that hold None are the player backing out without picking anything
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PlayerInput {
+    Command(Command),
+    ///Entities picked from a menu, stored by their id since a replay rebuilds the world from the same seed and so
+    /// ends up with the same ids
+    Menu(Option<Vec<u64>>),
+    Recipe(Option<String>),
+    Reply(Option<DialogueChoice>),
+    ///Tile picked when aiming an item, a gun or a throw
+    Target(Option<Point>),
+    ///Dev console command, they can spawn things and change stats so they have to be played back too
+    Console(String),
+}
+
+impl PlayerInput {
+    pub fn menu(response: Option<&Vec<Entity>>) -> PlayerInput {
+        PlayerInput::Menu(response.map(|ents| ents.iter().map(|ent| ent.to_bits().get()).collect()))
+    }
+
+    ///Whether this is an answer to something the game asked the player rather than something they did off their
+    /// own bat
+    pub fn is_answer(&self) -> bool {
+        !matches!(self, PlayerInput::Command(_) | PlayerInput::Console(_))
+    }
+}
+
+///Turns the ids a menu answer was recorded with back into entities, None if any of them is no longer alive
+pub fn menu_entities(ids: &[u64]) -> Option<Vec<Entity>> {
+    ids.iter().map(|id| Entity::from_bits(*id)).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCommand {
+    pub turn: i32,
+    pub input: PlayerInput,
+}
+
+pub struct Replay {
+    pub seed: u64,
+    pub commands: Vec<RecordedCommand>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Replay, ReplayError> {
+        let data = fs::read_to_string(path).map_err(|err| ReplayError::Io {
+            path: path.to_path_buf(),
+            err,
+        })?;
+        let parse_err = |line: usize| {
+            move |err| ReplayError::Parse {
+                path: path.to_path_buf(),
+                line: line + 1,
+                err,
+            }
+        };
+
+        let mut lines = data
+            .lines()
+            .enumerate()
+            .filter(|(_n, line)| !line.trim().is_empty());
+
+        let (n, header) = lines.next().ok_or_else(|| ReplayError::Empty {
+            path: path.to_path_buf(),
+        })?;
+        let header: ReplayHeader = serde_json::from_str(header).map_err(parse_err(n))?;
+
+        if header.version != REPLAY_VERSION {
+            return Err(ReplayError::WrongVersion {
+                found: header.version,
+                expected: REPLAY_VERSION,
+            });
+        }
+
+        let commands = lines
+            .map(|(n, line)| serde_json::from_str(line).map_err(parse_err(n)))
+            .collect::<Result<Vec<RecordedCommand>, ReplayError>>()?;
+
+        Ok(Replay {
+            seed: header.seed,
+            commands,
+        })
+    }
+}
+
+///Writes commands out as they happen, one json object per line and flushed straight away so the file is still
+/// usable if the game crashes part way through
+pub struct Recorder {
+    path: PathBuf,
+    file: File,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, seed: u64) -> Result<Recorder, ReplayError> {
+        let file = File::create(path).map_err(|err| ReplayError::Io {
+            path: path.to_path_buf(),
+            err,
+        })?;
+        let mut recorder = Recorder {
+            path: path.to_path_buf(),
+            file,
+        };
+
+        recorder.write_line(&ReplayHeader {
+            version: REPLAY_VERSION,
+            seed,
+        })?;
+
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, turn: i32, input: PlayerInput) -> Result<(), ReplayError> {
+        self.write_line(&RecordedCommand { turn, input })
+    }
+
+    fn write_line<T: Serialize>(&mut self, value: &T) -> Result<(), ReplayError> {
+        let line = serde_json::to_string(value).expect("Replay lines always serialize");
+
+        writeln!(self.file, "{}", line)
+            .and_then(|_| self.file.flush())
+            .map_err(|err| ReplayError::Io {
+                path: self.path.clone(),
+                err,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_commands_load_back() {
+        let path = std::env::temp_dir().join("recorded_commands_load_back.jsonl");
+
+        let mut recorder = Recorder::create(&path, 99).unwrap();
+        recorder
+            .record(
+                3,
+                PlayerInput::Command(Command::Move {
+                    pos: Point::new(1, -1),
+                }),
+            )
+            .unwrap();
+        recorder
+            .record(4, PlayerInput::Command(Command::Fire))
+            .unwrap();
+        recorder
+            .record(4, PlayerInput::Target(Some(Point::new(7, 2))))
+            .unwrap();
+
+        let replay = Replay::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(replay.seed, 99);
+        assert_eq!(
+            replay.commands,
+            vec![
+                RecordedCommand {
+                    turn: 3,
+                    input: PlayerInput::Command(Command::Move {
+                        pos: Point::new(1, -1)
+                    })
+                },
+                RecordedCommand {
+                    turn: 4,
+                    input: PlayerInput::Command(Command::Fire)
+                },
+                RecordedCommand {
+                    turn: 4,
+                    input: PlayerInput::Target(Some(Point::new(7, 2)))
+                },
+            ]
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use hecs::Entity;
 
 use crate::{
-    components::{BlocksTiles, BlocksVisibility, FoV, Renderable},
+    ai::{make_noise, DOOR_NOISE},
+    components::{BlocksTiles, BlocksVisibility, Door, FoV, Renderable},
     visibility_system::VisibilitySystem,
     State,
 };
@@ -9,11 +10,13 @@ use crate::{
 pub fn open_door(state: &mut State, interactor: Entity, door: Entity) {
     let _ = state.world.remove_one::<BlocksTiles>(door);
     let _ = state.world.remove_one::<BlocksVisibility>(door);
+    state.world.query_one_mut::<&mut Door>(door).unwrap().open = true;
     state
         .world
         .query_one_mut::<&mut Renderable>(door)
         .unwrap()
         .glyph = "/".to_string();
+    make_noise(state, interactor, DOOR_NOISE);
     state
         .world
         .query_one_mut::<&mut FoV>(state.player_ent.unwrap())
@@ -26,11 +29,13 @@ pub fn open_door(state: &mut State, interactor: Entity, door: Entity) {
 pub fn close_door(state: &mut State, interactor: Entity, door: Entity) {
     let _ = state.world.insert_one(door, BlocksTiles {});
     let _ = state.world.insert_one(door, BlocksVisibility {});
+    state.world.query_one_mut::<&mut Door>(door).unwrap().open = false;
     state
         .world
         .query_one_mut::<&mut Renderable>(door)
         .unwrap()
         .glyph = "+".to_string();
+    make_noise(state, interactor, DOOR_NOISE);
     state
         .world
         .query_one_mut::<&mut FoV>(state.player_ent.unwrap())
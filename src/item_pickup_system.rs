@@ -1,6 +1,6 @@
 use bracket_lib::prelude::console;
 use hecs::{Entity, World};
-use crate::{InContainer, Item,  Position, WantsToPickupItem};
+use crate::{missions::{self, MissionEvent}, statistics::get_encumbrance, InContainer, Item, Name, Position, WantsToPickupItem};
 
 use super::{State};
 
@@ -38,9 +38,25 @@ pub fn run(state : &mut State)
             continue;
         }
 
+        let before = get_encumbrance(state, ent);
+
         state.world.insert_one(pickup, InContainer{owner : ent})
         .expect("Couldn't insert InContainer componenent onto item to pickup");
         state.world.remove_one::<Position>(pickup).expect("Couldn't remove Position component from item entity.");
+
+        let after = get_encumbrance(state, ent);
+        if after > before && Some(ent) == state.player_ent
+        {
+            state.game_log.add_log(format!("You are now {}.", after.to_string().to_lowercase()));
+        }
+
+        if Some(ent) == state.player_ent
+        {
+            if let Ok(name) = state.world.get::<&Name>(pickup).map(|name| name.name.clone())
+            {
+                missions::record(state, MissionEvent::PickedUp(name));
+            }
+        }
         //console::log("Item picked up!");
     }
     
@@ -1,6 +1,7 @@
 
 use crate::effects::{add_effect, get_aoe_tiles, EffectType, Targets};
 use crate::{ AoE, Map,  State, WantsToUseItem};
+use crate::cybernetics::ImplantAbility;
 
 
 
@@ -48,6 +49,12 @@ pub fn run(state : &mut State)
             
         }
 
+        //implant abilities start recharging as soon as they fire
+        if let Ok(ability) = state.world.query_one_mut::<&mut ImplantAbility>(*item)
+        {
+            ability.remaining = ability.cooldown;
+        }
+
         state.world.remove_one::<WantsToUseItem>(*entity).unwrap();
 
         
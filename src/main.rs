@@ -9,9 +9,11 @@ use bracket_lib::prelude::*;
 use clear_dead_system::ClearDeadSystem;
 use damage_system::DamageSystem;
 use dev_console::Terminal;
+use dungeon_master::DungeonMaster;
 use effects::add_effect;
 use effects::run_effect_queue;
 use gamelog::GameLog;
+use gamelog::DEBUGLOG;
 use gui::draw_cursor;
 use gui::keyboard_cursor;
 use gui::menu_theme;
@@ -20,6 +22,8 @@ use gui::TargettingMode;
 use hecs::*;
 use hunger::hunger_system;
 use hunger::HungerLevel;
+use identification::Identification;
+use input::{PlayerInput, Recorder, Replay, INPUT};
 use interaction::interaction_system;
 use macroquad::color::GRAY;
 use macroquad::color::RED;
@@ -33,9 +37,11 @@ use menus::menu_input;
 use menus::select_menu_functions;
 use menus::MenuSelections;
 use menus::MenuType;
+use missions::MissionLog;
 use networks::ControlNode;
 use networks::NetworkMap;
 use networks::NodeOwned;
+use networks::ParentNode;
 use new_egui_macroquad::egui::Align2;
 use new_egui_macroquad::egui::Context;
 use new_egui_macroquad::egui::FontData;
@@ -51,15 +57,20 @@ use renderer::draw_tiles;
 use renderer::CharSize;
 use renderer::GraphicGrid;
 use renderer::Renderer;
+use reputation::Reputation;
 use screen_manager::MANAGER;
+use serde::{Deserialize, Serialize};
 use spawns::spawning_system::EntityType;
 use statistics::BaseStatistics;
 use statistics::Pools;
+use statistics::Skill;
+use statistics::Skills;
 use statistics::StatPool;
 use std::cmp::*;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::hash::Hash;
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
 use time_system::time_system;
@@ -71,6 +82,7 @@ use visibility_system::*;
 mod attack_system;
 mod clear_dead_system;
 mod damage_system;
+mod dungeon_master;
 mod item_pickup_system;
 mod map_indexing_system;
 mod player;
@@ -91,17 +103,26 @@ use spawns::spawning_system;
 use spawns::*;
 mod ai;
 pub mod camera;
+mod crafting;
+mod cybernetics;
+mod dialogue;
 pub mod effects;
 mod entry_trigger_system;
 mod gui;
+mod headless;
 mod hunger;
+mod identification;
+mod missions;
 mod networks;
 mod projectile;
 mod prop_trigger_system;
 mod ranged_combat;
 pub mod renderer;
+mod reputation;
+mod saveload;
 mod statistics;
 mod time_system;
+mod trading;
 use macroquad::prelude::*;
 use new_egui_macroquad as em;
 use new_egui_macroquad::egui;
@@ -124,6 +145,8 @@ pub struct State {
     world: World,
     map: Map,
     rng: bracket_lib::random::RandomNumberGenerator,
+    ///everything random in a run comes from this, the same seed always builds the same floors
+    seed: u64,
     current_state: ProgramState,
     player_pos: Point,
     player_ent: Option<Entity>,
@@ -132,7 +155,12 @@ pub struct State {
     target_mode: TargettingMode,
     turn_number: i32,
     network_map: NetworkMap,
-    renderer: Renderer,
+    dungeon: DungeonMaster,
+    reputation: Reputation,
+    identification: Identification,
+    missions: MissionLog,
+    ///None when running headless
+    renderer: Option<Renderer>,
 }
 
 #[derive(PartialEq, Clone)]
@@ -153,6 +181,19 @@ pub enum ProgramState {
         range: i32,
         dmg: i32,
     },
+    Throwing {
+        range: i32,
+        item: Entity,
+    },
+    ///The crafting window is open, recipe is set once the player picks something to make
+    Crafting {
+        recipe: Option<String>,
+    },
+    ///In conversation with the speaker, choice is set once the player picks a reply
+    Talking {
+        speaker: Entity,
+        choice: Option<raws::DialogueChoice>,
+    },
     KeyboardTargetting {
         cursor_pos: Point,
     },
@@ -171,7 +212,7 @@ pub enum ProgramState {
     },
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Position {
     x: i32,
     y: i32,
@@ -206,9 +247,39 @@ impl Position {
 }
 
 pub fn go_down_stairs(state: &mut State) {
-    cleanup_ECS(state);
-    //Map::generate_map_checked(state);
-    state.generate_world_map(state.map.depth + 1);
+    change_level(state, state.map.depth + 1, TileType::UpStairs);
+
+    let msg = format!("You traversed the stairs downwards to the next layer of the dungeon");
+
+    console::log(msg.clone());
+
+    state.game_log.add_log(msg);
+}
+
+pub fn go_up_stairs(state: &mut State) {
+    if state.map.depth == 0 {
+        return;
+    }
+
+    change_level(state, state.map.depth - 1, TileType::DownStairs);
+
+    let msg = format!("You climbed the stairs back up to the previous layer of the dungeon");
+
+    console::log(msg.clone());
+
+    state.game_log.add_log(msg);
+}
+
+///Stores the current floor and moves the player to the new depth, which is restored if it has been visited before
+/// otherwise a fresh floor is generated
+fn change_level(state: &mut State, new_depth: i32, arrive_on: TileType) {
+    dungeon_master::store_level(state);
+
+    if !dungeon_master::restore_level(state, new_depth, arrive_on) {
+        state.generate_world_map(new_depth);
+    }
+    trading::restock_vendors(state);
+    missions::record(state, missions::MissionEvent::ReachedDepth(new_depth));
 
     SPATIAL_INDEX
         .lock()
@@ -226,11 +297,6 @@ pub fn go_down_stairs(state: &mut State) {
         fov.dirty = true;
     }
 
-    let msg = format!("You traversed the stairs downwards to the next layer of the dungeon");
-
-    console::log(msg.clone());
-
-    state.game_log.add_log(msg);
     state
         .world
         .query_one_mut::<&mut Energy>(state.player_ent.unwrap())
@@ -240,76 +306,23 @@ pub fn go_down_stairs(state: &mut State) {
     state.current_state = ProgramState::Ticking;
 }
 
-#[allow(non_snake_case)]
-fn cleanup_ECS(state: &mut State) {
-    let mut turns = Vec::new();
-    for (ent, _turn) in state.world.query_mut::<&MyTurn>() {
-        turns.push(ent);
-    }
-    for turn in turns.iter() {
-        let _ = state.world.remove_one::<MyTurn>(*turn);
-    }
-
-    let mut entities_to_delete = Vec::new();
-    for entity in state.world.iter() {
-        let mut should_delete = true;
-
-        if entity.entity()
-            == state
-                .player_ent
-                .expect("Couldn't find player entity in cleanup ECS!")
-        {
-            should_delete = false;
-        }
-
-        if entity.get::<&InContainer>().is_some_and(|cont| {
-            cont.owner
-                == state
-                    .player_ent
-                    .expect("Couldn't find player entity in cleanup ECS!")
-        }) {
-            should_delete = false;
-        }
-        if entity.get::<&EffectSpawner>().is_some_and(|cont| {
-            cont.owner
-                == state
-                    .player_ent
-                    .expect("Couldn't find player entity in cleanup ECS!")
-        }) {
-            should_delete = false;
-        }
-
-        if entity.get::<&Equipped>().is_some_and(|eq| {
-            eq.owner
-                == state
-                    .player_ent
-                    .expect("Couldn't find player entity in cleanup ECS!")
-        }) {
-            should_delete = false;
-        }
-
-        if should_delete {
-            entities_to_delete.push(entity.entity());
-        }
-    }
-
-    for entity in entities_to_delete {
-        state
-            .world
-            .despawn(entity)
-            .expect("Can't delete entity that has been marked for removal when cleaning up ECS!");
-    }
-}
-
 impl State {
     fn generate_world_map(&mut self, new_depth: i32) {
-        let mut builder = maps::level_generator(new_depth);
+        //floors get their own rng so they come out the same no matter what the player did before reaching them
+        let mut level_rng = RandomNumberGenerator::seeded(level_seed(self.seed, new_depth));
+        let mut builder = maps::level_generator(new_depth, &mut level_rng);
 
-        builder.build_map(&mut self.rng);
+        builder.build_map(&mut level_rng);
 
         self.map = builder.build_data.map.clone();
         self.player_pos = builder.build_data.starting_position.unwrap().clone();
 
+        //the town is the top of the dungeon, every floor below it leads back up from where the player arrives
+        if new_depth != 0 {
+            let idx = self.map.xy_idx(self.player_pos.x, self.player_pos.y);
+            self.map.map[idx] = TileType::UpStairs;
+        }
+
         if new_depth != 0 {
             let _ = self.world.insert_one(
                 self.player_ent.unwrap(),
@@ -321,21 +334,109 @@ impl State {
         }
 
         builder.spawn_entities(self);
+
+        networks::generate_network(self, &builder.build_data.networks);
     }
 }
 
 impl State {
+    fn renderer(&self) -> &Renderer {
+        self.renderer
+            .as_ref()
+            .expect("Tried to draw without a renderer, the game is running headless!")
+    }
+
+    fn is_headless(&self) -> bool {
+        self.renderer.is_none()
+    }
+
+    ///Puts the player into targetting mode if the item has to be aimed, false if it can just be used
+    fn start_targetting(&mut self, item: Entity) -> bool {
+        let Ok(range) = self.world.get::<&RangedTargetting>(item).map(|r| r.range) else {
+            return false;
+        };
+        let aoe = self.world.get::<&AoE>(item).ok().map(|aoe| aoe.radius);
+
+        self.center_cursor_on_player();
+        self.current_state = ProgramState::Targeting { range, item, aoe };
+
+        true
+    }
+
+    ///Puts the targetting cursor over the player, there's no screen to put it on when running headless
+    fn center_cursor_on_player(&mut self) {
+        if self.is_headless() {
+            return;
+        }
+
+        let (min_x, _max_x, min_y, _max_y) = camera::get_screen_bounds(self);
+        self.target_mode = TargettingMode::Keyboard {
+            cursor_pos: Point::new(self.player_pos.x - min_x, self.player_pos.y - min_y),
+        };
+    }
+
+    ///Keeps running initiative, the AI and all of the game systems until it's the player's turn again
+    fn run_turns(&mut self) -> ProgramState {
+        let mut newrunstate = ProgramState::Ticking;
+        while newrunstate == ProgramState::Ticking {
+            VisibilitySystem::run(self);
+            newrunstate = run_initiative(self);
+
+            {
+                ai::quip_system(self);
+
+                //confused mobs might stumble about instead of doing what they meant to
+                ai::confused_ai_system(self);
+
+                //watchers get one roll to spot the player each pass, however many systems ask
+                ai::forget_perception_rolls(self);
+
+                //todo: in all of the systems that can end a turn apply the energy costs to the entities!
+                //check adjacent reactions
+                ai::adjacent_ai_system(self);
+
+                //check further away reactions
+                ai::visible_ai_system(self);
+
+                //run current goal behaviour
+                ai::approach_ai_system(self);
+
+                ai::flee_ai_system(self);
+
+                //go looking for whoever was last seen or heard
+                ai::chase_ai_system(self);
+
+                //idle movement
+                ai::idle_movement_ai(self);
+
+                //default behaviour
+                ai::default_move_ai_system(self);
+                //run systems!
+                run_systems(self);
+            }
+        }
+
+        newrunstate
+    }
+
     fn tick(&mut self) {
+        //a replay answers menus and targetting itself rather than waiting on the player
+        if self.awaiting_answer() {
+            let answer = INPUT.lock().next_replayed_answer(self.turn_number);
+
+            if let Some(answer) = answer {
+                self.answer(answer);
+                return;
+            }
+        }
+
         match self.current_state.clone() {
             ProgramState::AwaitingInput => {
                 //runs effect queue so console commands can take effect even if player doesn't take an action
                 effects::run_effect_queue(self);
 
                 self.current_state = input::input_system(self);
-                item_pickup_system::run(self);
-                item_use_system::run(self);
-                MapIndexingSystem::run(self);
-                ClearDeadSystem::run(self);
+                run_input_systems(self);
                 camera::render_camera(self);
                 ////render_system(self, ctx);
                 gui::draw_ui(self);
@@ -354,36 +455,7 @@ impl State {
                 gui::draw_gamelog(self);
             }
             ProgramState::Ticking => {
-                let mut newrunstate = ProgramState::Ticking;
-                while newrunstate == ProgramState::Ticking {
-                    VisibilitySystem::run(self);
-                    newrunstate = run_initiative(self);
-
-                    {
-                        ai::quip_system(self);
-
-                        //todo: in all of the systems that can end a turn apply the energy costs to the entities!
-                        //check adjacent reactions
-                        ai::adjacent_ai_system(self);
-
-                        //check further away reactions
-                        ai::visible_ai_system(self);
-
-                        //run current goal behaviour
-                        ai::approach_ai_system(self);
-
-                        ai::flee_ai_system(self);
-
-                        //idle movement
-                        ai::idle_movement_ai(self);
-
-                        //default behaviour
-                        ai::default_move_ai_system(self);
-                        //run systems!
-                        run_systems(self);
-                    }
-                }
-                self.current_state = newrunstate;
+                self.current_state = self.run_turns();
             }
 
             ProgramState::TextInput { mut text } => {
@@ -392,7 +464,7 @@ impl State {
                 self.current_state = ProgramState::TextInput { text }
             }
 
-            ProgramState::Targeting { range, item, aoe } => {
+            ProgramState::Targeting { range, aoe, .. } => {
                 camera::render_camera(self);
                 //render_system(self, ctx);
                 gui::draw_ui(self);
@@ -405,22 +477,17 @@ impl State {
                 let (inv_state, point) = gui::ranged_target(self, range, aoe);
                 match inv_state {
                     inventory_state::Cancel => {
+                        INPUT
+                            .lock()
+                            .record(self.turn_number, PlayerInput::Target(None));
                         self.current_state = ProgramState::AwaitingInput;
                     }
                     inventory_state::Selected => {
-                        if point.is_some() {
-                            let point =
-                                point.expect("Couldn't find point even tho it is Some??? like wtf");
-                            self.world.insert_one(self.player_ent.expect("Couldn't find player"),
-                            WantsToUseItem{item:item, target: Some(point)})
-                            .expect("Couldn't insert WantsToUseItem onto player for ranged targeting!");
-                            apply_energy_cost(
-                                self,
-                                ai::ActionType::UseItem,
-                                self.player_ent.unwrap(),
-                            );
-                            let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
-                            self.current_state = ProgramState::Ticking;
+                        if let Some(point) = point {
+                            INPUT
+                                .lock()
+                                .record(self.turn_number, PlayerInput::Target(Some(point)));
+                            self.resolve_target(point);
                         }
                     }
                     inventory_state::None => {}
@@ -445,38 +512,44 @@ impl State {
                         }
                     }
                     TargettingState::Cancel => {
+                        INPUT
+                            .lock()
+                            .record(self.turn_number, PlayerInput::Target(None));
                         self.current_state = ProgramState::AwaitingInput;
                     }
 
-                    TargettingState::Selected { mut path, end } => {
-                        path.push(end);
-                        let dmg = 4;
-                        //TODO:!!!!!!!!!!!!!!
-
-                        let query = self.world.query::<&Equipped>()
-                            .iter()
-                            .filter(|(_ent,equip) |
-                            equip.slot == EquipmentSlot::Ranged && equip.owner == self.player_ent
-                            .expect("Couldn't find player entity to fetch ranged stats for combat"))
-                            .map(|(ent, _eq)| ent)
-                            .collect::<Vec<_>>();
-
-                        //effects::ra
-                        add_effect(
-                            Some(self.player_ent.unwrap()),
-                            effects::EffectType::RangedFire { item: query[0] },
-                            effects::Targets::Tile {
-                                tile_idx: self.map.xy_idx(end.x, end.y) as i32,
-                            },
-                        );
+                    TargettingState::Selected { end } => {
+                        INPUT
+                            .lock()
+                            .record(self.turn_number, PlayerInput::Target(Some(end)));
+                        self.resolve_target(end);
+                    }
+                }
 
-                        effects::run_effect_queue(self);
-                        effects::run_animation_queue(self);
+                if let TargettingMode::Keyboard { cursor_pos } = self.target_mode {
+                    gui::draw_tooltip(self, cursor_pos);
+                }
+            }
+
+            ProgramState::Throwing { range, .. } => {
+                camera::render_camera(self);
+                gui::draw_ui(self);
+                gui::draw_status_box(self);
+                gui::draw_gamelog(self);
 
-                        //self.projectile_builder.add_request(10., path.into_iter().skip(1).collect::<Vec<_>>(), projectile::ProjectileType::Missile,
-                        //    '/', RGB::named(WHITE), RGB::named(BLACK), 5,dmg );
-                        let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
-                        self.current_state = ProgramState::PlayAnimation;
+                match ranged_aim::aim_projectile(self, self.player_pos, range) {
+                    TargettingState::None => {}
+                    TargettingState::Cancel => {
+                        INPUT
+                            .lock()
+                            .record(self.turn_number, PlayerInput::Target(None));
+                        self.current_state = ProgramState::AwaitingInput;
+                    }
+                    TargettingState::Selected { end } => {
+                        INPUT
+                            .lock()
+                            .record(self.turn_number, PlayerInput::Target(Some(end)));
+                        self.resolve_target(end);
                     }
                 }
 
@@ -485,6 +558,22 @@ impl State {
                 }
             }
 
+            ProgramState::Crafting { recipe } => {
+                camera::render_camera(self);
+
+                if let Some(recipe) = recipe {
+                    self.resolve_crafting(&recipe);
+                }
+            }
+
+            ProgramState::Talking { speaker, choice } => {
+                camera::render_camera(self);
+
+                if let Some(choice) = choice {
+                    self.resolve_talking(speaker, &choice);
+                }
+            }
+
             ProgramState::KeyboardTargetting { cursor_pos } => {
                 MANAGER.lock().unwrap().tooltip_active = true;
                 camera::render_camera(self);
@@ -511,147 +600,325 @@ impl State {
             } => {
                 camera::render_camera(self);
 
-                if response.is_some() {
-                    match menu_type {
-                        screen_manager::MenuType::Pickup => {
-                            for item in response.unwrap().iter() {
-                                self.world
-                                    .insert_one(
-                                        *item,
-                                        InContainer {
-                                            owner: self.player_ent.unwrap(),
-                                        },
-                                    )
-                                    .unwrap();
-
-                                self.world.remove_one::<Position>(*item).unwrap();
-                            }
-
-                            self.current_state = ProgramState::AwaitingInput;
-                        }
+                if let Some(response) = response {
+                    self.resolve_menu(response, menu_type);
+                }
+            }
+
+            ProgramState::GameOver => {
+                self.renderer().draw_char(250, 100, "You have died!", RED);
+                if is_key_down(KeyCode::Escape) {
+                    quit();
+                }
+            }
+            _ => {
+                self.current_state = ProgramState::AwaitingInput;
+            }
+        }
+    }
+
+    ///Whether the game is waiting on the player to answer a menu or pick a target
+    fn awaiting_answer(&self) -> bool {
+        matches!(
+            self.current_state,
+            ProgramState::AwaitingMenu { response: None, .. }
+                | ProgramState::Crafting { recipe: None }
+                | ProgramState::Talking { choice: None, .. }
+                | ProgramState::Targeting { .. }
+                | ProgramState::RangedCombat { .. }
+                | ProgramState::Throwing { .. }
+        )
+    }
+
+    ///Answers whatever the game is waiting on the player for with an answer from a replay
+    fn answer(&mut self, answer: PlayerInput) {
+        MANAGER.lock().unwrap().close_menus();
+
+        match (self.current_state.clone(), answer) {
+            (ProgramState::AwaitingMenu { menu_type, .. }, PlayerInput::Menu(Some(ids))) => {
+                match input::menu_entities(&ids) {
+                    Some(response) => self.resolve_menu(response, menu_type),
+                    None => self.current_state = ProgramState::AwaitingInput,
+                }
+            }
+            (ProgramState::Crafting { .. }, PlayerInput::Recipe(Some(recipe))) => {
+                self.resolve_crafting(&recipe)
+            }
+            (ProgramState::Talking { speaker, .. }, PlayerInput::Reply(Some(choice))) => {
+                self.resolve_talking(speaker, &choice)
+            }
+            (
+                ProgramState::Targeting { .. }
+                | ProgramState::RangedCombat { .. }
+                | ProgramState::Throwing { .. },
+                PlayerInput::Target(Some(target)),
+            ) => self.resolve_target(target),
+            (_, PlayerInput::Menu(None))
+            | (_, PlayerInput::Recipe(None))
+            | (_, PlayerInput::Reply(None))
+            | (_, PlayerInput::Target(None)) => self.current_state = ProgramState::AwaitingInput,
+            (_, answer) => {
+                DEBUGLOG.add_log(format!(
+                    "Replay out of sync, {:?} doesn't answer what the game is waiting on",
+                    answer
+                ));
+                self.current_state = ProgramState::AwaitingInput;
+            }
+        }
+    }
 
-                        screen_manager::MenuType::Unequip => {
-                            let ent = self.player_ent.unwrap();
+    ///Carries out whatever the player picked from a menu
+    fn resolve_menu(&mut self, response: Vec<Entity>, menu_type: screen_manager::MenuType) {
+        match menu_type {
+            screen_manager::MenuType::Pickup => {
+                for item in response.iter() {
+                    self.world
+                        .insert_one(
+                            *item,
+                            InContainer {
+                                owner: self.player_ent.unwrap(),
+                            },
+                        )
+                        .unwrap();
 
-                            for item in response.unwrap().iter() {
-                                self.world
-                                    .insert_one(*item, InContainer { owner: ent })
-                                    .unwrap();
+                    self.world.remove_one::<Position>(*item).unwrap();
+                }
 
-                                self.world.remove_one::<Equipped>(*item).unwrap();
+                self.current_state = ProgramState::AwaitingInput;
+            }
 
-                                self.world.insert_one(ent, EquipmentDirty {}).unwrap();
-                            }
+            screen_manager::MenuType::Unequip => {
+                let ent = self.player_ent.unwrap();
 
-                            apply_energy_cost(
-                                self,
-                                ai::ActionType::Equip,
-                                self.player_ent.unwrap(),
-                            );
-                            let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
-                            self.current_state = ProgramState::Ticking;
-                        }
+                for item in response.iter() {
+                    self.world
+                        .insert_one(*item, InContainer { owner: ent })
+                        .unwrap();
 
-                        screen_manager::MenuType::Drop => {
-                            let posref = self
-                                .world
-                                .get::<&Position>(self.player_ent.unwrap())
-                                .unwrap();
-                            let pos = Point::new(posref.x, posref.y);
-
-                            std::mem::drop(posref);
-                            for item in response.unwrap().iter() {
-                                self.world
-                                    .insert_one(*item, Position { x: pos.x, y: pos.y })
-                                    .unwrap();
-
-                                self.world.remove_one::<InContainer>(*item).unwrap();
-                            }
-
-                            apply_energy_cost(
-                                self,
-                                ai::ActionType::Pickup,
-                                self.player_ent.unwrap(),
-                            );
-
-                            let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
-                            self.current_state = ProgramState::Ticking;
-                        }
-                        screen_manager::MenuType::Inventory => {
-                            let item = response.unwrap()[0];
-
-                            let (min_x, max_x, min_y, max_y) = camera::get_screen_bounds(self);
-
-                            if let Ok(ranged) = self.world.get::<&RangedTargetting>(item) {
-                                let range = ranged.range;
-                                std::mem::drop(ranged);
-
-                                let query = self.world.get::<&AoE>(item);
-                                let mut aoe = None;
-                                match query {
-                                    Ok(ref aoe_comp) => {
-                                        aoe = Some(aoe_comp.radius);
-                                    }
-                                    Err(_) => {}
-                                }
-                                std::mem::drop(query);
-                                let mut screen_pos = self.player_pos;
-
-                                screen_pos.x -= min_x;
-                                screen_pos.y -= min_y;
-                                self.target_mode = TargettingMode::Keyboard {
-                                    cursor_pos: screen_pos,
-                                };
-                                self.current_state = ProgramState::Targeting {
-                                    range: range,
-                                    item: item,
-                                    aoe: aoe,
-                                };
-
-                                return;
-                            }
-
-                            if self.world.get::<&Equippable>(item).is_ok() {
-                                let equip = self.world.get::<&Equippable>(item).unwrap();
-                                let slot = equip.slot;
-
-                                std::mem::drop(equip);
-
-                                let _ = self.world.insert_one(
-                                    self.player_ent.unwrap(),
-                                    WantsToEquipItem { item, slot },
-                                );
-
-                                let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
-
-                                self.current_state = ProgramState::Ticking;
-                                return;
-                            }
-
-                            let _ = self.world.insert_one(
-                                self.player_ent.unwrap(),
-                                WantsToUseItem { item, target: None },
-                            );
-                            let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
-                            self.current_state = ProgramState::Ticking;
-                            return;
-                        }
-                        _ => self.current_state = ProgramState::AwaitingInput,
-                    }
+                    self.world.remove_one::<Equipped>(*item).unwrap();
+
+                    self.world.insert_one(ent, EquipmentDirty {}).unwrap();
                 }
+
+                apply_energy_cost(self, ai::ActionType::Equip, self.player_ent.unwrap());
+                let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
+                self.current_state = ProgramState::Ticking;
             }
 
-            ProgramState::GameOver => {
-                self.renderer.draw_char(250, 100, "You have died!", RED);
-                if is_key_down(KeyCode::Escape) {
-                    quit();
+            screen_manager::MenuType::Drop => {
+                let posref = self
+                    .world
+                    .get::<&Position>(self.player_ent.unwrap())
+                    .unwrap();
+                let pos = Point::new(posref.x, posref.y);
+
+                std::mem::drop(posref);
+                for item in response.iter() {
+                    self.world
+                        .insert_one(*item, Position { x: pos.x, y: pos.y })
+                        .unwrap();
+
+                    self.world.remove_one::<InContainer>(*item).unwrap();
                 }
+
+                apply_energy_cost(self, ai::ActionType::Pickup, self.player_ent.unwrap());
+
+                let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
+                self.current_state = ProgramState::Ticking;
             }
-            _ => {
-                self.current_state = ProgramState::AwaitingInput;
+            screen_manager::MenuType::Throw => {
+                let item = response[0];
+                let range =
+                    ranged_combat::throwing::throw_range(self, self.player_ent.unwrap(), item);
+
+                self.center_cursor_on_player();
+                self.current_state = ProgramState::Throwing { range, item };
+            }
+            screen_manager::MenuType::Inventory => {
+                let item = response[0];
+
+                if self.world.get::<&cybernetics::Implant>(item).is_ok() {
+                    self.game_log
+                        .add_log("Implants need a ripper-doc to install them.".to_string());
+                    self.current_state = ProgramState::AwaitingInput;
+                    return;
+                }
+
+                if self.start_targetting(item) {
+                    return;
+                }
+
+                if self.world.get::<&Equippable>(item).is_ok() {
+                    let equip = self.world.get::<&Equippable>(item).unwrap();
+                    let slot = equip.slot;
+
+                    std::mem::drop(equip);
+
+                    let _ = self
+                        .world
+                        .insert_one(self.player_ent.unwrap(), WantsToEquipItem { item, slot });
+
+                    let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
+
+                    self.current_state = ProgramState::Ticking;
+                    return;
+                }
+
+                let _ = self.world.insert_one(
+                    self.player_ent.unwrap(),
+                    WantsToUseItem { item, target: None },
+                );
+                let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
+                self.current_state = ProgramState::Ticking;
+                return;
+            }
+            screen_manager::MenuType::Identify => {
+                let item = response[0];
+                let player = self.player_ent.unwrap();
+
+                identification::terminal_identify(self, player, item);
+
+                apply_energy_cost(self, ai::ActionType::Hack, player);
+                let _ = self.world.remove_one::<MyTurn>(player);
+                self.current_state = ProgramState::Ticking;
+            }
+            screen_manager::MenuType::Install => {
+                let implant = response[0];
+
+                let _ = self.world.insert_one(
+                    self.player_ent.unwrap(),
+                    cybernetics::WantsToInstallImplant { implant },
+                );
+                apply_energy_cost(self, ai::ActionType::Equip, self.player_ent.unwrap());
+                let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
+                self.current_state = ProgramState::Ticking;
+            }
+            screen_manager::MenuType::Implants => {
+                let implant = response[0];
+
+                if let Ok(ability) = self.world.get::<&cybernetics::ImplantAbility>(implant) {
+                    self.game_log
+                        .add_log(format!("You activate {}.", ability.name));
+                }
+
+                if self.start_targetting(implant) {
+                    return;
+                }
+
+                let _ = self.world.insert_one(
+                    self.player_ent.unwrap(),
+                    WantsToUseItem {
+                        item: implant,
+                        target: None,
+                    },
+                );
+                apply_energy_cost(self, ai::ActionType::UseItem, self.player_ent.unwrap());
+                let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
+                self.current_state = ProgramState::Ticking;
+            }
+            screen_manager::MenuType::Trade { vendor } => {
+                let player = self.player_ent.unwrap();
+
+                //haggling is free, the player stays at the counter until they walk away
+                for item in response.iter() {
+                    trading::trade(self, player, vendor, *item);
+                }
+                self.current_state = input::open_trade_menu(self, vendor);
+            }
+            screen_manager::MenuType::Talk => {
+                for speaker in response.iter() {
+                    self.current_state = input::open_dialogue(self, *speaker, None);
+                }
             }
+            screen_manager::MenuType::Hack => {
+                let node = response[0];
+
+                networks::hack_node(self, node);
+
+                apply_energy_cost(self, ai::ActionType::Hack, self.player_ent.unwrap());
+                let _ = self.world.remove_one::<MyTurn>(self.player_ent.unwrap());
+                self.current_state = ProgramState::Ticking;
+            }
+            _ => self.current_state = ProgramState::AwaitingInput,
         }
     }
+
+    ///Uses, fires or throws at the target tile depending on what the player was aiming
+    fn resolve_target(&mut self, target: Point) {
+        let player = self.player_ent.unwrap();
+
+        match self.current_state {
+            ProgramState::Targeting { item, .. } => {
+                self.world
+                    .insert_one(
+                        player,
+                        WantsToUseItem {
+                            item,
+                            target: Some(target),
+                        },
+                    )
+                    .expect("Couldn't insert WantsToUseItem onto player for ranged targeting!");
+                apply_energy_cost(self, ai::ActionType::UseItem, player);
+                let _ = self.world.remove_one::<MyTurn>(player);
+                self.current_state = ProgramState::Ticking;
+            }
+            ProgramState::RangedCombat { .. } => {
+                let query = self
+                    .world
+                    .query::<&Equipped>()
+                    .iter()
+                    .filter(|(_ent, equip)| {
+                        equip.slot == EquipmentSlot::Ranged && equip.owner == player
+                    })
+                    .map(|(ent, _eq)| ent)
+                    .collect::<Vec<_>>();
+
+                add_effect(
+                    Some(player),
+                    effects::EffectType::RangedFire { item: query[0] },
+                    effects::Targets::Tile {
+                        tile_idx: self.map.xy_idx(target.x, target.y) as i32,
+                    },
+                );
+                effects::run_effect_queue(self);
+
+                let _ = self.world.remove_one::<MyTurn>(player);
+                self.current_state = ProgramState::PlayAnimation;
+            }
+            ProgramState::Throwing { item, .. } => {
+                ranged_combat::throwing::throw_item(self, player, item, target);
+                effects::run_effect_queue(self);
+
+                apply_energy_cost(self, ai::ActionType::Throw, player);
+                let _ = self.world.remove_one::<MyTurn>(player);
+                self.current_state = ProgramState::PlayAnimation;
+            }
+            _ => {}
+        }
+    }
+
+    fn resolve_crafting(&mut self, recipe: &str) {
+        let player = self.player_ent.unwrap();
+
+        if crafting::craft(self, player, recipe) {
+            apply_energy_cost(self, ai::ActionType::Craft, player);
+            let _ = self.world.remove_one::<MyTurn>(player);
+            self.current_state = ProgramState::Ticking;
+        } else {
+            self.current_state = ProgramState::AwaitingInput;
+        }
+    }
+
+    fn resolve_talking(&mut self, speaker: Entity, choice: &raws::DialogueChoice) {
+        let player = self.player_ent.unwrap();
+
+        //talking is free, only whatever the conversation leads to takes any time
+        self.current_state = match dialogue::choose(self, player, speaker, choice) {
+            dialogue::Reply::Continue { node } => input::open_dialogue(self, speaker, Some(&node)),
+            dialogue::Reply::Trade => input::open_trade_menu(self, speaker),
+            dialogue::Reply::End => ProgramState::AwaitingInput,
+        };
+    }
 }
 
 fn run_systems(state: &mut State) {
@@ -661,6 +928,7 @@ fn run_systems(state: &mut State) {
 
     item_equip_system::run(state);
     item_unequip_system(state);
+    cybernetics::implant_install_system(state);
     item_use_system::run(state);
 
     interaction_system(state);
@@ -681,6 +949,12 @@ fn run_systems(state: &mut State) {
         cursor_pos: state.player_pos,
     };
 
+    //nothing will ever draw the requested particles without a renderer so they're just thrown away
+    if state.is_headless() {
+        state.particle_builder.clear();
+        return;
+    }
+
     //camera::render_camera(state);
     effects::run_animation_queue(state);
     camera::render_camera(state);
@@ -689,11 +963,18 @@ fn run_systems(state: &mut State) {
     gui::draw_gamelog(state);
 }
 
-fn game_init(state: &mut State) {
-    raws::run();
+///Systems that have to respond straight away to whatever the player just did
+fn run_input_systems(state: &mut State) {
+    item_pickup_system::run(state);
+    item_use_system::run(state);
+    MapIndexingSystem::run(state);
+    ClearDeadSystem::run(state);
+}
 
+fn game_init(state: &mut State) {
     //let item = raws::RawMaster::spawn_named_item(raws::RAWS.lock().unwrap()., new_entity, key, pos)
     //Spawn player object
+    identification::generate_names(state);
     state.generate_world_map(0);
     let _asset = maps::RexAssests::new();
     let xy = state.player_pos;
@@ -718,7 +999,7 @@ fn game_init(state: &mut State) {
             armour_class: Attribute::new(10),
             hit_die: DiceType::new(1, 10, 1),
         },
-        BaseStatistics::roll_stats(3),
+        BaseStatistics::roll_stats(3, &mut state.rng),
         HungerLevel {
             nutrition: StatPool::new(300),
         },
@@ -729,6 +1010,20 @@ fn game_init(state: &mut State) {
         Player {},
     )));
 
+    let _ = state
+        .world
+        .insert_one(state.player_ent.unwrap(), Skills::new(&[(Skill::Hack, 2)]));
+    let _ = state.world.insert_one(
+        state.player_ent.unwrap(),
+        crafting::KnownRecipes::starting(),
+    );
+    let _ = state.world.insert_one(
+        state.player_ent.unwrap(),
+        trading::Credits {
+            amount: trading::STARTING_CREDITS,
+        },
+    );
+
     spawning_system::spawn_item_in_backpack(
         state,
         &"Ration".to_string(),
@@ -747,6 +1042,14 @@ fn game_init(state: &mut State) {
         state.player_ent.unwrap(),
     );
 
+    spawning_system::spawn_item_equipped(
+        state,
+        &"Pistol Ammo".to_string(),
+        state.player_ent.unwrap(),
+    );
+    ranged_combat::ammo::reload(state, state.player_ent.unwrap());
+    trading::restock_vendors(state);
+
     state.game_log.add_log(
         "You wake up in your rundown apartment with no memory of what happened last night"
             .to_string(),
@@ -758,17 +1061,80 @@ fn game_init(state: &mut State) {
         .game_log
         .add_log("The message simply states 'RUN, *THEY* are coming'".to_string());
     state.world.spawn((
-        FoV::new(10),
+        Name {
+            name: "Cyberdeck".to_string(),
+        },
         ControlNode { level: 3 },
         NodeOwned {
             owner: state.player_ent.unwrap(),
         },
-        Position { x: xy.x, y: xy.y },
+        ParentNode {
+            ent: state.player_ent.unwrap(),
+        },
     ));
+    networks::connect_player_deck(state);
+}
+
+///Throws away the current run and starts a fresh one from the given seed
+pub fn new_run(state: &mut State, seed: u64) {
+    state.world = World::new();
+    state.dungeon = DungeonMaster::new();
+    state.reputation = Reputation::new();
+    state.identification = Identification::new();
+    state.missions = MissionLog::new();
+    state.network_map = NetworkMap::empty();
+    state.rng = RandomNumberGenerator::seeded(seed);
+    state.seed = seed;
+    state.game_log = GameLog::new();
+    state.particle_builder.clear();
+    state.turn_number = 0;
+    state.player_ent = None;
+
+    game_init(state);
+
+    DEBUGLOG.add_log(format!("Started a new run with seed {}", seed));
+    state.current_state = ProgramState::Ticking;
+}
+
+///Mixes the run seed with the depth (splitmix64) so every floor gets its own repeatable seed
+fn level_seed(seed: u64, depth: i32) -> u64 {
+    let mut z = seed.wrapping_add((depth as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
 }
 
-fn create_state(renderer: Renderer) -> State {
-    let mut gs: State = State {
+///The value following the flag on the command line, None if the flag wasn't passed
+fn arg_value(flag: &str) -> Option<String> {
+    let args = std::env::args().collect::<Vec<String>>();
+
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+///The seed passed with `--seed N`, if there was one
+fn seed_from_args() -> Result<Option<u64>, String> {
+    arg_value("--seed")
+        .map(|seed| {
+            seed.parse()
+                .map_err(|_| format!("--seed needs to be a number, got {}", seed))
+        })
+        .transpose()
+}
+
+///A replay always starts its own run from the seed it was recorded with
+fn replay_from_args() -> Result<Option<Replay>, String> {
+    arg_value("--replay")
+        .map(|path| {
+            Replay::load(Path::new(&path)).map_err(|err| format!("Couldn't load replay: {}", err))
+        })
+        .transpose()
+}
+
+fn new_state(renderer: Option<Renderer>, seed: u64) -> State {
+    State {
         world: World::new(),
 
         map: Map {
@@ -781,7 +1147,8 @@ fn create_state(renderer: Renderer) -> State {
             map_height: 69,
         },
 
-        rng: bracket_lib::random::RandomNumberGenerator::new(),
+        rng: RandomNumberGenerator::seeded(seed),
+        seed,
         current_state: ProgramState::Ticking,
         player_pos: Point::zero(),
         player_ent: None,
@@ -792,13 +1159,52 @@ fn create_state(renderer: Renderer) -> State {
         },
         turn_number: 0,
         network_map: NetworkMap::empty(),
+        dungeon: DungeonMaster::new(),
+        reputation: Reputation::new(),
+        identification: Identification::new(),
+        missions: MissionLog::new(),
         renderer,
-    };
-    game_init(&mut gs);
+    }
+}
+
+fn create_state(renderer: Renderer, seed: Option<u64>, replay: Option<Replay>) -> State {
+    let record_path = arg_value("--record");
+    let seed = replay.as_ref().map(|replay| replay.seed).or(seed);
+    let mut gs = new_state(
+        Some(renderer),
+        seed.unwrap_or_else(|| RandomNumberGenerator::new().next_u64()),
+    );
+    if let Err(err) = raws::run() {
+        panic!("Couldn't load raws: {}", err);
+    }
+
+    //asking for a seed or a recording means starting that run rather than carrying on with the saved one
+    if seed.is_none() && record_path.is_none() && saveload::does_save_exist() {
+        if let Err(err) = saveload::load_game(&mut gs) {
+            DEBUGLOG.add_log(format!("Couldn't load save, starting a new game: {}", err));
+            game_init(&mut gs);
+        }
+    } else {
+        game_init(&mut gs);
+    }
+
+    if let Some(path) = record_path {
+        match Recorder::create(Path::new(&path), gs.seed) {
+            Ok(recorder) => INPUT.lock().start_recording(recorder),
+            Err(err) => DEBUGLOG.add_log(format!("Error: not recording, {}", err)),
+        }
+    }
+    if let Some(replay) = replay {
+        let step = std::env::args().any(|arg| arg == "--replay-step");
+        INPUT.lock().start_replay(replay, step);
+    }
 
     gs
 }
 
+const USAGE: &str = "Usage: [--seed N] [--record FILE] [--replay FILE [--replay-step]] [--raws DIR] \
+[--validate-raws]";
+
 fn window_conf() -> Conf {
     Conf {
         window_title: "CyberRL".to_owned(),
@@ -808,8 +1214,42 @@ fn window_conf() -> Conf {
     }
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
+fn main() {
+    if let Some(dir) = arg_value("--raws") {
+        raws::set_raws_dir(dir);
+    }
+
+    //headless runs never open a window so they have to be picked off before macroquad takes over
+    match headless::SimulationConfig::from_args(std::env::args().skip(1)) {
+        Ok(Some(config)) => {
+            println!("{}", headless::run_simulation(config));
+            return;
+        }
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("{}\n{}", err, headless::USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    if std::env::args().any(|arg| arg == "--validate-raws") {
+        let valid = raws::print_validation_report(&raws::raws_dir());
+        std::process::exit(if valid { 0 } else { 1 });
+    }
+
+    //checked before the window opens so a typo doesn't leave a blank window behind
+    let (seed, replay) = match (seed_from_args(), replay_from_args()) {
+        (Ok(seed), Ok(replay)) => (seed, replay),
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    macroquad::Window::from_config(window_conf(), game_main(seed, replay));
+}
+
+async fn game_main(seed: Option<u64>, replay: Option<Replay>) {
     let font = load_ttf_font("./assets/fonts/Mx437_ATI_8x8.ttf")
         .await
         .unwrap();
@@ -878,14 +1318,14 @@ async fn main() {
     );
     rend.char_size = CharSize(size.width as i32, size.height as i32, size.offset_y as i32);
     //let cam = Camera2D::from_display_rect(macroquad::prelude::Rect::new(0.0, 152.0, 320.0, -152.0));
-    let mut state = create_state(rend.clone());
+    rend.setup_grid();
+    let mut state = create_state(rend.clone(), seed, replay);
 
     //creates instance of scripting engine for dev console
     let mut term = Terminal::new();
     term.load_commands();
     let mut console = DevConsole::new(&mut term);
 
-    state.renderer.setup_grid();
     loop {
         clear_background(GRAY);
         //updates hashmaps of currently pressed and released keys, and what game commands they map to
@@ -102,7 +102,7 @@ impl SpatialIndexMap {
         self.map_width = dimensions.0;
         self.map_height = dimensions.1;
 
-        self.blocked = vec![TileBlocked::default(); self.map_width * self.map_width];
+        self.blocked = vec![TileBlocked::default(); self.map_width * self.map_height];
         self.tile_content = vec![Vec::new(); self.map_width * self.map_height];
     }
 
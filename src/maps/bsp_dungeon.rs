@@ -1,6 +1,6 @@
 use bracket_lib::{prelude::Rect, random::RandomNumberGenerator};
 
-use super::{common::apply_room_to_map, BuilderMap, InitialMapBuilder, Map, TileType};
+use super::{common::{apply_room_to_map, fill_with_rock}, BuilderMap, InitialMapBuilder, Map, TileType};
 
 
 
@@ -28,6 +28,7 @@ impl BspDungeon
     fn build(&mut self, rng : &mut RandomNumberGenerator, build_data : &mut BuilderMap)
     {
         let mut rooms: Vec<Rect> = Vec::new();
+        fill_with_rock(&mut build_data.map);
         self.rects.clear();
         self.rects.push(Rect::with_size(2, 2, build_data.map.map_width-5, build_data.map.map_height-5));
 
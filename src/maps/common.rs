@@ -2,6 +2,11 @@ use bracket_lib::prelude::Rect;
 
 use super::{Map, TileType};
 
+///Map::new starts every tile as floor, room builders call this first so they have solid rock to carve rooms out of
+pub fn fill_with_rock(map : &mut Map) {
+    map.map.fill(TileType::Wall);
+}
+
 pub fn apply_room_to_map(map : &mut super::Map, room : &Rect) {
     for y in room.y1 +1 ..= room.y2 {
         for x in room.x1 + 1 ..= room.x2 {
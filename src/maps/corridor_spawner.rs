@@ -33,7 +33,7 @@ impl CorridorSpawner
             {
                 let depth = build_data.map.depth;
 
-                spawn_region(&c, depth, &mut build_data.spawn_list);
+                spawn_region(&c, depth, &mut build_data.spawn_list, rng);
             }
         } else 
         {
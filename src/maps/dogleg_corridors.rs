@@ -19,7 +19,7 @@ impl MetaMapBuilder for DoglegCorridors
 
 impl DoglegCorridors
 {
-    fn new() -> Box<DoglegCorridors>
+    pub fn new() -> Box<DoglegCorridors>
     {
         Box::new(DoglegCorridors{})
     }
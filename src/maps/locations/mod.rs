@@ -1,7 +1,9 @@
 use bracket_lib::random::RandomNumberGenerator;
 use town_builder::starting_town;
 
-use super::{random_map_builder, BuilderChain};
+use crate::raws::RAWS;
+
+use super::{profile_map_builder, random_map_builder, BuilderChain};
 
 mod town_builder;
 mod utils;
@@ -11,18 +13,24 @@ const MAX_WIDTH : i32 = 120;
 const MAX_HEIGHT : i32 = 90;
 
 
-pub fn level_generator(new_depth: i32) -> BuilderChain
+pub fn level_generator(new_depth: i32, rng : &mut RandomNumberGenerator) -> BuilderChain
 {
     match new_depth
     {
         0 => { return starting_town()}
         _ => 
         {
-            let mut rng = RandomNumberGenerator::new();
-            let width = rng.range(50, MAX_WIDTH + 1);
-            let height = rng.range(40, MAX_HEIGHT + 1);
+            let profile = RAWS.lock().unwrap().get_level_profile(new_depth);
+
+            let width = profile.as_ref().and_then(|p| p.width).unwrap_or_else(|| rng.range(50, MAX_WIDTH + 1));
+            let height = profile.as_ref().and_then(|p| p.height).unwrap_or_else(|| rng.range(40, MAX_HEIGHT + 1));
 
-            return random_map_builder(new_depth, width, height)
+            //floors without a profile (or with a broken one) fall back to the caves
+            match profile.and_then(|p| profile_map_builder(&p, new_depth, width, height))
+            {
+                Some(builder) => return builder,
+                None => return random_map_builder(new_depth, width, height),
+            }
             
         }
 
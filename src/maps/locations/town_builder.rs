@@ -4,7 +4,7 @@ use bracket_lib::{prelude::{a_star_search, console, DijkstraMap, DistanceAlg, Po
 
 use crate::{maps::{border_wall::BorderWall, voronoi::{DistanceAlgorithm, VoronoiCellBuilder}, AreaStartingPosition, DistantExitBuilder, MetaMapBuilder}, raws::RAWS, BuilderChain, BuilderMap, InitialMapBuilder, TileType};
 
-use super::utils::{find_entity_spawn_locations, spawn_building_contents, vec_of_str};
+use super::utils::{find_entity_spawn_locations, spawn_building_contents, take_random_tile, vec_of_str};
 
 
 const MAX_W : i32 = 13;
@@ -347,7 +347,24 @@ impl TownBuilder
                     }
                 }
                 console::log(format!("Length of tiles set {}", tiles.len()));
-                spawn_building_contents(build_data, &building.contents, &mut tiles);
+
+                let mut contents = building.contents.clone();
+                if let Some(network) = building.network.as_ref().and_then(|name| RAWS.lock().unwrap().get_network_from_name(name))
+                {
+                    contents.extend(network.nodes.iter().cloned());
+
+                    //walls are included so the building's door ends up on the network too
+                    let mut footprint = HashSet::new();
+                    for y in build.1 .. build.1+build.3
+                    {
+                        for x in build.0 .. build.0+build.2
+                        {
+                            footprint.insert(build_data.map.xy_idx(x, y));
+                        }
+                    }
+                    build_data.networks.push((network.name, footprint));
+                }
+                spawn_building_contents(build_data, &contents, &mut tiles, rng);
             
         }
     }
@@ -418,11 +435,9 @@ impl TownBuilder
 
         for i in 0..roll
         {
-            if let Some(idx) = valid_locations.iter().next().cloned()
+            if let Some(idx) = take_random_tile(&mut valid_locations, rng)
             {
                 build_data.spawn_list.push((idx, "Citizen".to_string()));
-
-                valid_locations.remove(&idx);
             }
             //let idx = valid_locations.iter().take(1).
         }
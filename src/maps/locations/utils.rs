@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use bracket_lib::prelude::Point;
+use bracket_lib::{prelude::Point, random::RandomNumberGenerator};
 use hecs::Entity;
 
 use crate::{
@@ -43,21 +43,39 @@ pub fn spawn_building_contents(
     build_data: &mut BuilderMap,
     contents: &[String],
     building_tiles: &mut HashSet<usize>,
+    rng: &mut RandomNumberGenerator,
 ) {
     for obj in contents.iter() {
         let mut success = false;
         while !success {
-            let idx = *building_tiles.iter().next().unwrap();
+            let idx = take_random_tile(building_tiles, rng).unwrap();
             if tile_walkable(build_data.map.map[idx]) {
                 success = true;
                 build_data.spawn_list.push((idx, obj.clone()));
             }
-
-            building_tiles.remove(&idx);
         }
     }
 }
 
+///Removes and returns a random tile from the set, the tiles are sorted first as set order changes between runs
+/// which would stop a seed from always building the same map
+pub fn take_random_tile(
+    tiles: &mut HashSet<usize>,
+    rng: &mut RandomNumberGenerator,
+) -> Option<usize> {
+    if tiles.is_empty() {
+        return None;
+    }
+
+    let mut candidates = tiles.iter().copied().collect::<Vec<usize>>();
+    candidates.sort_unstable();
+
+    let idx = candidates[rng.range(0, candidates.len())];
+    tiles.remove(&idx);
+
+    Some(idx)
+}
+
 pub fn vec_of_str(v: &[&str]) -> Vec<String> {
     v.iter().map(|&x| x.into()).collect()
 }
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::collections::{hash_map, HashMap, HashSet};
 use std::f32::consts::PI;
 
@@ -31,7 +32,7 @@ impl Map {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Map {
     pub map: Vec<TileType>,
     pub revealed_tiles: Vec<bool>,
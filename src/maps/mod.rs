@@ -27,6 +27,7 @@ mod waveform_collapse;
 use crate::{
     map::*,
     map_indexing::SPATIAL_INDEX,
+    raws::LevelProfile,
     spawns::spawning_system::{self, get_entity_type, EntityType},
     State,
 };
@@ -53,6 +54,7 @@ use room_based_stairs::*;
 use room_based_starting_pos::*;
 use room_sorter::*;
 use simple_map::*;
+use std::collections::HashSet;
 pub use tile_type::*;
 use voronoi::*;
 use voronoi_spawning::*;
@@ -91,12 +93,73 @@ pub fn random_map_builder(new_depth: i32, width: i32, height: i32) -> BuilderCha
     builder
 }
 
+///Builds the chain described by a level profile from the raws, None if it names a builder that doesn't exist
+pub fn profile_map_builder(
+    profile: &LevelProfile,
+    new_depth: i32,
+    width: i32,
+    height: i32,
+) -> Option<BuilderChain> {
+    let mut builder = BuilderChain::new(new_depth, width, height);
+
+    builder.start_with(starter_from_name(&profile.starter, new_depth)?);
+    for name in profile.builders.iter() {
+        builder.with(meta_builder_from_name(name)?);
+    }
+
+    Some(builder)
+}
+
+pub fn starter_from_name(name: &str, new_depth: i32) -> Option<Box<dyn InitialMapBuilder>> {
+    let starter: Box<dyn InitialMapBuilder> = match name {
+        "cellular_automata" => CellularAutomataBuilder::new(),
+        "bsp_dungeon" => BspDungeon::new(),
+        "simple_map" => SimpleMapBuilder::new(new_depth),
+        "voronoi" => VoronoiCellBuilder::pythagoras(),
+        "voronoi_manhattan" => VoronoiCellBuilder::manhattan(),
+        "prefab_scrapyard" => PrefabBuilder::constant(SCRAPYARD),
+        "rex_wfctest" => PrefabBuilder::rex_level("wfctest.xp"),
+        _ => return None,
+    };
+
+    Some(starter)
+}
+
+pub fn meta_builder_from_name(name: &str) -> Option<Box<dyn MetaMapBuilder>> {
+    let builder: Box<dyn MetaMapBuilder> = match name {
+        "cellular_automata" => CellularAutomataBuilder::new(),
+        "waveform_collapse" => WaveformCollapseBuilder::new(),
+        "area_start_center" => AreaStartingPosition::new(XStart::CENTER, YStart::CENTER),
+        "area_start_left" => AreaStartingPosition::new(XStart::LEFT, YStart::CENTER),
+        "room_based_starting_position" => RoomBasedStartingPosition::new(),
+        "border_wall" => BorderWall::new(),
+        "cull_unreachable" => CullUnreachable::new(),
+        "room_sorter" => RoomSorter::new(),
+        "bsp_corridors" => BspCorridors::new(),
+        "corridors_nearest_neighbour" => CorridorsNearestNeighbour::new(),
+        "dogleg_corridors" => DoglegCorridors::new(),
+        "voronoi_spawning" => VoronoiSpawning::new(),
+        "room_based_spawns" => RoomBasedSpawns::new(),
+        "corridor_spawner" => CorridorSpawner::new(),
+        "distant_exit" => DistantExitBuilder::new(),
+        "room_based_stairs" => RoomBasedStairs::new(),
+        "door_placement" => DoorPlacement::new(),
+        "room_vaults" => PrefabBuilder::vaults(),
+        "security_checkpoint" => PrefabBuilder::sectional(SECURITY_CHECKPOINT),
+        _ => return None,
+    };
+
+    Some(builder)
+}
+
 pub struct BuilderMap {
     pub spawn_list: Vec<(usize, String)>,
     pub map: Map,
     pub starting_position: Option<Point>,
     pub rooms: Option<Vec<Rect>>,
     pub corridors: Option<Vec<Vec<usize>>>,
+    ///network name from the raws paired with every tile covered by the building it belongs to
+    pub networks: Vec<(String, HashSet<usize>)>,
 }
 
 pub struct BuilderChain {
@@ -116,6 +179,7 @@ impl BuilderChain {
                 starting_position: None,
                 rooms: None,
                 corridors: None,
+                networks: Vec::new(),
             },
         }
     }
@@ -1,9 +1,15 @@
-use bracket_lib::random::RandomNumberGenerator;
+use std::{collections::HashSet, fs::File};
 
-use super::{BuilderMap, InitialMapBuilder, MetaMapBuilder};
+use bracket_lib::{prelude::{to_char, Point, Rect, XpFile}, random::RandomNumberGenerator};
+
+use super::{BuilderMap, InitialMapBuilder, MetaMapBuilder, TileType};
 
 mod prefab_levels;
+mod prefab_rooms;
 mod prefab_section;
+pub use prefab_levels::*;
+pub use prefab_rooms::*;
+pub use prefab_section::*;
 
 
 #[derive(PartialEq, Copy, Clone)]
@@ -22,30 +28,368 @@ pub struct PrefabBuilder
     mode: PrefabMode
 }
 
-impl MetaMapBuilder for PrefabBuilder 
+impl MetaMapBuilder for PrefabBuilder
 {
-    fn build_map(&mut self, rng: &mut bracket_lib::prelude::RandomNumberGenerator, build_data: &mut super::BuilderMap) 
+    fn build_map(&mut self, rng: &mut bracket_lib::prelude::RandomNumberGenerator, build_data: &mut super::BuilderMap)
     {
-        
+        self.build(rng, build_data);
     }
 }
 
 impl InitialMapBuilder for PrefabBuilder
 {
-    fn build_map(&mut self, rng: &mut bracket_lib::prelude::RandomNumberGenerator, build_data: &mut super::BuilderMap) 
+    fn build_map(&mut self, rng: &mut bracket_lib::prelude::RandomNumberGenerator, build_data: &mut super::BuilderMap)
     {
-        
+        self.build(rng, build_data);
     }
 }
+#[allow(dead_code)]
 impl PrefabBuilder
 {
-    pub fn new() -> Box<PrefabBuilder>
+    ///Loads a whole level from a REX Paint file, unpainted cells are left as wall
+    pub fn rex_level(template : &'static str) -> Box<PrefabBuilder>
+    {
+        Box::new(PrefabBuilder { mode: PrefabMode::RexLevel { template } })
+    }
+
+    pub fn constant(level : PrefabLevel) -> Box<PrefabBuilder>
+    {
+        Box::new(PrefabBuilder { mode: PrefabMode::Constant { level } })
+    }
+
+    pub fn sectional(section : PrefabSection) -> Box<PrefabBuilder>
+    {
+        Box::new(PrefabBuilder { mode: PrefabMode::Sectional { section } })
+    }
+
+    ///Needs rooms from an earlier builder to drop the vaults into
+    pub fn vaults() -> Box<PrefabBuilder>
     {
         Box::new(PrefabBuilder { mode: PrefabMode::RoomVaults })
     }
 
     fn build(&mut self, rng : &mut RandomNumberGenerator, build_data: &mut BuilderMap)
     {
-        
+        match self.mode
+        {
+            PrefabMode::RexLevel { template } => self.load_rex_map(template, build_data),
+            PrefabMode::Constant { level } => self.load_ascii_map(&level, build_data),
+            PrefabMode::Sectional { section } => self.apply_sectional(&section, build_data),
+            PrefabMode::RoomVaults => self.apply_room_vaults(rng, build_data),
+        }
+    }
+
+    ///Turns a glyph from a template into a tile, anything that isn't terrain is spawned on top of floor
+    fn char_to_map(&mut self, ch : char, idx : usize, build_data : &mut BuilderMap)
+    {
+        let spawn = match ch
+        {
+            ' ' | '.' | '_' => None,
+            '#' => { build_data.map.map[idx] = TileType::Wall; return; }
+            '=' => { build_data.map.map[idx] = TileType::MetalGrate; return; }
+            '>' => { build_data.map.map[idx] = TileType::DownStairs; return; }
+            '<' => { build_data.map.map[idx] = TileType::UpStairs; return; }
+            '@' =>
+            {
+                let x = idx as i32 % build_data.map.map_width;
+                let y = idx as i32 / build_data.map.map_width;
+                build_data.starting_position = Some(Point::new(x, y));
+                None
+            }
+            '+' => Some("Door"),
+            '^' => Some("Bear Trap"),
+            '!' => Some("Health Injector"),
+            '%' => Some("Ration"),
+            'r' => Some("Rat"),
+            't' => Some("Street Thug"),
+            'g' => Some("X Corp Goon"),
+            'T' => Some("Sentry Turret"),
+            _ =>
+            {
+                bracket_lib::terminal::console::log(format!("Unknown glyph {} when loading prefab", ch));
+                None
+            }
+        };
+
+        build_data.map.map[idx] = TileType::Floor;
+
+        if let Some(name) = spawn
+        {
+            build_data.spawn_list.push((idx, name.to_string()));
+        }
+    }
+
+    fn load_rex_map(&mut self, path : &str, build_data : &mut BuilderMap)
+    {
+        let mut file = File::open(path).unwrap_or_else(|err| panic!("Couldn't open REX level {}: {}", path, err));
+        let xp_file = XpFile::read(&mut file).unwrap_or_else(|err| panic!("Couldn't read REX level {}: {}", path, err));
+
+        for (i, layer) in xp_file.layers.iter().enumerate()
+        {
+            for y in 0..layer.height.min(build_data.map.map_height as usize)
+            {
+                for x in 0..layer.width.min(build_data.map.map_width as usize)
+                {
+                    let cell = layer.get(x, y).unwrap();
+                    let idx = build_data.map.xy_idx(x as i32, y as i32);
+                    let unpainted = cell.ch == 0 || cell.ch == 32;
+
+                    if unpainted
+                    {
+                        //higher layers only overlay the cells that were actually drawn on
+                        if i == 0 { build_data.map.map[idx] = TileType::Wall; }
+                        continue;
+                    }
+
+                    self.char_to_map(to_char(cell.ch as u8), idx, build_data);
+                }
+            }
+        }
+    }
+
+    fn load_ascii_map(&mut self, level : &PrefabLevel, build_data : &mut BuilderMap)
+    {
+        let rows = read_ascii_to_vec(level.template, level.width, level.height);
+
+        for (y, row) in rows.iter().enumerate().take(build_data.map.map_height as usize)
+        {
+            for (x, ch) in row.iter().enumerate().take(build_data.map.map_width as usize)
+            {
+                let idx = build_data.map.xy_idx(x as i32, y as i32);
+                self.char_to_map(*ch, idx, build_data);
+            }
+        }
+    }
+
+    fn apply_sectional(&mut self, section : &PrefabSection, build_data : &mut BuilderMap)
+    {
+        let map_width = build_data.map.map_width as usize;
+        let map_height = build_data.map.map_height as usize;
+
+        //leaves room for the border wall on either side
+        if section.width + 2 > map_width || section.height + 2 > map_height
+        {
+            bracket_lib::terminal::console::log("Prefab section doesn't fit on this map, skipping it");
+            return;
+        }
+
+        let chunk_x = match section.placement.0
+        {
+            HorizontalPlacement::Left => 1,
+            HorizontalPlacement::Center => (map_width / 2) - (section.width / 2),
+            HorizontalPlacement::Right => (map_width - 1) - section.width,
+        };
+        let chunk_y = match section.placement.1
+        {
+            VerticalPlacement::Top => 1,
+            VerticalPlacement::Center => (map_height / 2) - (section.height / 2),
+            VerticalPlacement::Bottom => (map_height - 1) - section.height,
+        };
+
+        self.stamp(section.template, section.width, section.height, chunk_x, chunk_y, build_data);
+    }
+
+    fn apply_room_vaults(&mut self, rng : &mut RandomNumberGenerator, build_data : &mut BuilderMap)
+    {
+        let Some(rooms) = build_data.rooms.clone() else
+        {
+            bracket_lib::terminal::console::log("Room vaults need rooms from an earlier builder, skipping them");
+            return;
+        };
+
+        let depth = build_data.map.depth;
+        let vaults = ROOM_VAULTS.iter()
+            .filter(|vault| depth >= vault.first_depth && depth <= vault.last_depth)
+            .collect::<Vec<_>>();
+
+        if vaults.is_empty() { return; }
+
+        let num_vaults = rng.roll_dice(1, 3);
+        let mut used_tiles : HashSet<usize> = HashSet::new();
+        let start_idx = build_data.starting_position
+            .map(|pos| build_data.map.xy_idx(pos.x, pos.y));
+
+        for _ in 0..num_vaults
+        {
+            let vault = vaults[rng.range(0, vaults.len())];
+            let spots = vault_spots(vault, &rooms, &used_tiles, start_idx, build_data);
+
+            if spots.is_empty() { continue; }
+
+            let (x, y) = spots[rng.range(0, spots.len())];
+            self.stamp(vault.template, vault.width, vault.height, x, y, build_data);
+
+            for dy in 0..vault.height
+            {
+                for dx in 0..vault.width
+                {
+                    used_tiles.insert(build_data.map.xy_idx((x + dx) as i32, (y + dy) as i32));
+                }
+            }
+        }
+    }
+
+    ///Replaces the tiles and anything due to spawn in the area with the template
+    fn stamp(&mut self, template : &str, width : usize, height : usize, chunk_x : usize, chunk_y : usize, build_data : &mut BuilderMap)
+    {
+        let map_width = build_data.map.map_width as usize;
+
+        build_data.spawn_list.retain(|(idx, _name)|
+        {
+            let x = idx % map_width;
+            let y = idx / map_width;
+            x < chunk_x || x >= chunk_x + width || y < chunk_y || y >= chunk_y + height
+        });
+
+        let rows = read_ascii_to_vec(template, width, height);
+
+        for (y, row) in rows.iter().enumerate()
+        {
+            for (x, ch) in row.iter().enumerate()
+            {
+                let idx = build_data.map.xy_idx((chunk_x + x) as i32, (chunk_y + y) as i32);
+                self.char_to_map(*ch, idx, build_data);
+            }
+        }
+    }
+}
+
+///Top left corners where the vault fits on floor inside one of the rooms without covering the start or another vault
+fn vault_spots(vault : &PrefabRoom, rooms : &[Rect], used_tiles : &HashSet<usize>, start_idx : Option<usize>, build_data : &BuilderMap) -> Vec<(usize, usize)>
+{
+    let mut spots = Vec::new();
+
+    for room in rooms.iter()
+    {
+        for y in room.y1 ..= room.y2 - vault.height as i32
+        {
+            for x in room.x1 ..= room.x2 - vault.width as i32
+            {
+                let fits = (0..vault.height as i32).all(|dy| (0..vault.width as i32).all(|dx|
+                {
+                    let idx = build_data.map.xy_idx(x + dx, y + dy);
+                    build_data.map.map[idx] == TileType::Floor && !used_tiles.contains(&idx)
+                        && Some(idx) != start_idx
+                }));
+
+                if fits { spots.push((x as usize, y as usize)); }
+            }
+        }
+    }
+
+    spots
+}
+
+///Splits a template into exactly height rows of width glyphs, the template starts on the line after its opening quote
+fn read_ascii_to_vec(template : &str, width : usize, height : usize) -> Vec<Vec<char>>
+{
+    let mut rows = template.lines()
+        .skip(1)
+        .take(height)
+        .map(|line|
+        {
+            let mut row = line.chars().take(width).collect::<Vec<char>>();
+            row.resize(width, ' ');
+            row
+        })
+        .collect::<Vec<Vec<char>>>();
+
+    rows.resize(height, vec![' '; width]);
+
+    rows
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::maps::BuilderChain;
+
+    #[test]
+    fn constant_level_places_start_stairs_and_spawns()
+    {
+        let mut chain = BuilderChain::new(1, SCRAPYARD.width as i32, SCRAPYARD.height as i32);
+        let mut builder = PrefabBuilder::constant(SCRAPYARD);
+        let mut rng = RandomNumberGenerator::seeded(1);
+
+        InitialMapBuilder::build_map(builder.as_mut(), &mut rng, &mut chain.build_data);
+
+        let data = &chain.build_data;
+        assert_eq!(data.starting_position, Some(Point::new(1, 1)));
+        assert!(data.map.map.contains(&TileType::DownStairs));
+        assert!(data.spawn_list.iter().any(|(_idx, name)| name == "Door"));
+        assert_eq!(data.map.map[0], TileType::Wall);
+    }
+
+    ///Corners of the box holding every wall on the map
+    fn wall_bounds(build_data : &BuilderMap) -> (i32, i32, i32, i32)
+    {
+        let walls = build_data.map.map.iter().enumerate()
+            .filter(|(_idx, tile)| **tile == TileType::Wall)
+            .map(|(idx, _tile)| (idx as i32 % build_data.map.map_width, idx as i32 / build_data.map.map_width))
+            .collect::<Vec<_>>();
+
+        (
+            walls.iter().map(|pos| pos.0).min().unwrap(),
+            walls.iter().map(|pos| pos.1).min().unwrap(),
+            walls.iter().map(|pos| pos.0).max().unwrap(),
+            walls.iter().map(|pos| pos.1).max().unwrap(),
+        )
+    }
+
+    #[test]
+    fn sections_are_anchored_to_the_edges_and_middle()
+    {
+        let placements = [
+            ((HorizontalPlacement::Left, VerticalPlacement::Top), (1, 1)),
+            ((HorizontalPlacement::Center, VerticalPlacement::Center), (8, 6)),
+            ((HorizontalPlacement::Right, VerticalPlacement::Bottom), (15, 11)),
+        ];
+
+        for (placement, (x, y)) in placements
+        {
+            let section = PrefabSection { template : "\n####\n####\n####\n", width : 4, height : 3, placement };
+            let mut chain = BuilderChain::new(1, 20, 15);
+            let mut builder = PrefabBuilder::sectional(section);
+            let mut rng = RandomNumberGenerator::seeded(1);
+
+            MetaMapBuilder::build_map(builder.as_mut(), &mut rng, &mut chain.build_data);
+
+            assert_eq!(wall_bounds(&chain.build_data), (x, y, x + 3, y + 2));
+        }
+    }
+
+    #[test]
+    fn vaults_keep_clear_of_the_start_and_each_other()
+    {
+        //every spot a vault could go in this room covers its middle
+        for seed in 0..50
+        {
+            let mut chain = BuilderChain::new(50, 20, 15);
+            chain.build_data.rooms = Some(vec![Rect::with_size(2, 2, 5, 5)]);
+            chain.build_data.starting_position = Some(Point::new(4, 4));
+            let mut builder = PrefabBuilder::vaults();
+            let mut rng = RandomNumberGenerator::seeded(seed);
+
+            MetaMapBuilder::build_map(builder.as_mut(), &mut rng, &mut chain.build_data);
+
+            assert!(chain.build_data.spawn_list.is_empty());
+        }
+
+        let mut build_data = BuilderChain::new(50, 20, 15).build_data;
+        let rooms = [Rect::with_size(2, 2, 5, 10)];
+        let mut used_tiles = HashSet::new();
+        assert_eq!(vault_spots(&TRAPPED_CACHE, &rooms, &used_tiles, None, &build_data).len(), 6);
+
+        for y in 2..7
+        {
+            for x in 2..7 { used_tiles.insert(build_data.map.xy_idx(x, y)); }
+        }
+        assert_eq!(vault_spots(&TRAPPED_CACHE, &rooms, &used_tiles, None, &build_data), vec![(2, 7)]);
+
+        let idx = build_data.map.xy_idx(4, 9);
+        assert!(vault_spots(&TRAPPED_CACHE, &rooms, &used_tiles, Some(idx), &build_data).is_empty());
+        build_data.map.map[idx] = TileType::Wall;
+        assert!(vault_spots(&TRAPPED_CACHE, &rooms, &used_tiles, None, &build_data).is_empty());
     }
-}
\ No newline at end of file
+}
@@ -1,3 +1,39 @@
-
+///A whole hand drawn level, lines shorter than the width are padded out with floor
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct PrefabLevel {}
\ No newline at end of file
+pub struct PrefabLevel
+{
+    pub template : &'static str,
+    pub width : usize,
+    pub height : usize,
+}
+
+#[allow(dead_code)]
+pub const SCRAPYARD : PrefabLevel = PrefabLevel
+{
+    template : SCRAPYARD_TEMPLATE,
+    width : 40,
+    height : 20,
+};
+
+const SCRAPYARD_TEMPLATE : &str = "
+########################################
+#@     #         ^          #    r     #
+#      #   ####     ####    #  ####    #
+#      +   #  #  %  #  #    #  #!      #
+#      #   #  #     #  #       #   t   #
+###+####   ####     ####    #  ####    #
+#                           #          #
+#   ==========         ######  ^   r   #
+#   =        =         #    #          #
+#   =   %    =    t    #  ! ####+#######
+#   =        =         #    #          #
+#   ====  ====         ###+##     g    #
+#                                      #
+#  ####     ####    ^      ####   #### #
+#  #  #     #  #           #  #   #  # #
+#  #  #  t  #  #     r     #  #   #> # #
+#  #  #     #  #           #  #   #  # #
+#  ####     ####           ####   ## # #
+#                                      #
+########################################
+";
@@ -0,0 +1,61 @@
+///A small set piece dropped inside an existing room that is big enough to hold it
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PrefabRoom
+{
+    pub template : &'static str,
+    pub width : usize,
+    pub height : usize,
+    pub first_depth : i32,
+    pub last_depth : i32,
+}
+
+pub const ROOM_VAULTS : [PrefabRoom; 3] = [SUPPLY_STASH, RAT_NEST, TRAPPED_CACHE];
+
+pub const SUPPLY_STASH : PrefabRoom = PrefabRoom
+{
+    template : SUPPLY_STASH_TEMPLATE,
+    width : 5,
+    height : 3,
+    first_depth : 0,
+    last_depth : 100,
+};
+
+const SUPPLY_STASH_TEMPLATE : &str = "
+
+ !%!
+
+";
+
+pub const RAT_NEST : PrefabRoom = PrefabRoom
+{
+    template : RAT_NEST_TEMPLATE,
+    width : 5,
+    height : 5,
+    first_depth : 0,
+    last_depth : 4,
+};
+
+const RAT_NEST_TEMPLATE : &str = "
+
+ r r
+  %
+ r r
+
+";
+
+pub const TRAPPED_CACHE : PrefabRoom = PrefabRoom
+{
+    template : TRAPPED_CACHE_TEMPLATE,
+    width : 5,
+    height : 5,
+    first_depth : 2,
+    last_depth : 100,
+};
+
+const TRAPPED_CACHE_TEMPLATE : &str = "
+
+ ^^^
+ ^!^
+ ^^^
+
+";
@@ -1,3 +1,44 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HorizontalPlacement
+{
+    Left, Center, Right
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VerticalPlacement
+{
+    Top, Center, Bottom
+}
 
+///A chunk of hand drawn map stamped over whatever the previous builders made, anchored to an edge or the middle
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct PrefabSection {}
\ No newline at end of file
+pub struct PrefabSection
+{
+    pub template : &'static str,
+    pub width : usize,
+    pub height : usize,
+    pub placement : (HorizontalPlacement, VerticalPlacement),
+}
+
+#[allow(dead_code)]
+pub const SECURITY_CHECKPOINT : PrefabSection = PrefabSection
+{
+    template : SECURITY_CHECKPOINT_TEMPLATE,
+    width : 14,
+    height : 11,
+    placement : (HorizontalPlacement::Right, VerticalPlacement::Center),
+};
+
+const SECURITY_CHECKPOINT_TEMPLATE : &str = "
+
+ ############
+ #    ^     #
+ #  g    g  #
+ #          #
+      T     #
+ #          #
+ #  g    !  #
+ #    ^     #
+ ############
+
+";
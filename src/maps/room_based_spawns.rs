@@ -30,7 +30,7 @@ impl RoomBasedSpawns
         {
             for room in rooms.iter().skip(1)
             {
-                spawn_room( *room, build_data.map.depth, &mut build_data.spawn_list, &build_data.map);
+                spawn_room( *room, build_data.map.depth, &mut build_data.spawn_list, &build_data.map, rng);
             }
         }
         else 
@@ -3,7 +3,6 @@ use bracket_lib::prelude::Rect;
 use bracket_lib::random::RandomNumberGenerator;
 
 use crate::spawns::spawning_system::spawn_room;
-use crate::TileType;
 use crate::common::*;
 use super::BuilderMap;
 use super::InitialMapBuilder;
@@ -33,7 +32,7 @@ impl SimpleMapBuilder {
         const MAX_SIZE : i32 = 10;
 
         let mut rooms : Vec<Rect> = Vec::new();
-
+        fill_with_rock(&mut build_data.map);
 
         for i in 0..MAX_ROOMS {
             let w = rng.range(MIN_SIZE, MAX_SIZE);
@@ -1,15 +1,16 @@
+use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq,Clone, Copy, Debug, Hash, Eq)]
+#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq, Serialize, Deserialize)]
 pub enum TileType
 {
-    Floor, Wall, DownStairs, Road, Footpath, Concrete, MetalGrate, RustedMetalFloor
+    Floor, Wall, DownStairs, UpStairs, Road, Footpath, Concrete, MetalGrate, RustedMetalFloor
 }
 
 pub fn tile_walkable(tt : TileType) -> bool
 {
     match tt
     {
-        TileType::Footpath | TileType::Concrete | TileType::DownStairs | TileType::Floor | TileType::MetalGrate | TileType::Road
+        TileType::Footpath | TileType::Concrete | TileType::DownStairs | TileType::UpStairs | TileType::Floor | TileType::MetalGrate | TileType::Road
             | TileType::RustedMetalFloor
             => true,
         _ => false
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use bracket_lib::{noise::{CellularDistanceFunction, FastNoise}, random::RandomNumberGenerator};
 
@@ -27,7 +27,8 @@ impl VoronoiSpawning
 
     fn build(&mut self, rng : &mut RandomNumberGenerator, build_data : &mut BuilderMap)
     {
-        let mut noise_areas : HashMap<i32, Vec<usize>> = HashMap::new();
+        //ordered so the areas use up the rng in the same order for the same seed
+        let mut noise_areas : BTreeMap<i32, Vec<usize>> = BTreeMap::new();
         let mut noise = FastNoise::seeded(rng.roll_dice(1, 65535) as u64);
         noise.set_noise_type(bracket_lib::noise::NoiseType::Cellular);
         noise.set_frequency(0.08);
@@ -57,7 +58,7 @@ impl VoronoiSpawning
         //spawn entities
         for area in noise_areas.iter()
         {
-            spawn_region( &area.1, build_data.map.depth, &mut build_data.spawn_list);
+            spawn_region( &area.1, build_data.map.depth, &mut build_data.spawn_list, rng);
         }
     }
 
@@ -76,8 +76,9 @@ pub fn build_patterns(map : &Map, chunk_size : i32
         //Remove duplicate patterns!
         if dedupe
         {
-            let set : HashSet<Vec<TileType>> = patterns.drain(..).collect();
-            patterns.extend(set.into_iter());
+            //keeps the first of each pattern in place, collecting through a set would shuffle them differently every run
+            let mut seen : HashSet<Vec<TileType>> = HashSet::new();
+            patterns.retain(|pattern| seen.insert(pattern.clone()));
         }
 
 
@@ -241,6 +241,9 @@ impl Solver
                 }
             }
 
+            //the set hands them back in a different order every run
+            possible_options.sort_unstable();
+
             if possible_options.is_empty()
             {
                 console::log("WFC Solution not possible!");
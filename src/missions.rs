@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crafting::carried_count,
+    raws::{MissionInfo, RAWS},
+    spawns::spawning_system::spawn_item_in_backpack,
+    statistics::{self, Pools},
+    trading::add_credits,
+    State,
+};
+
+///Contract the player has taken on along with how far along it is, progress only counts kills
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mission {
+    pub name: String,
+    pub progress: i32,
+}
+
+///Every contract the player has taken on this run, kept across floors
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MissionLog {
+    pub active: Vec<Mission>,
+    pub completed: Vec<String>,
+}
+
+impl MissionLog {
+    pub fn new() -> MissionLog {
+        MissionLog::default()
+    }
+
+    pub fn status(&self, name: &str) -> MissionStatus {
+        if self.completed.iter().any(|done| done == name) {
+            MissionStatus::Done
+        } else if self.active.iter().any(|mission| mission.name == name) {
+            MissionStatus::Active
+        } else {
+            MissionStatus::Available
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissionStatus {
+    Available,
+    Active,
+    Done,
+}
+
+impl MissionStatus {
+    pub fn parse(status: &str) -> Option<MissionStatus> {
+        match status {
+            "available" => Some(MissionStatus::Available),
+            "active" => Some(MissionStatus::Active),
+            "done" => Some(MissionStatus::Done),
+            _ => None,
+        }
+    }
+}
+
+///Something the player did that might move a mission along
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissionEvent {
+    Killed(String),
+    PickedUp(String),
+    Hacked(String),
+    ReachedDepth(i32),
+}
+
+///Takes on the mission unless the player already has, it's checked straight away in case the objective is already met
+pub fn accept_mission(state: &mut State, name: &str) {
+    if state.missions.status(name) != MissionStatus::Available {
+        return;
+    }
+    let Some(info) = RAWS.lock().unwrap().get_mission(name) else {
+        return;
+    };
+
+    state.missions.active.push(Mission {
+        name: name.to_string(),
+        progress: 0,
+    });
+    state
+        .game_log
+        .add_log(format!("New mission: {}. {}", info.name, info.description));
+
+    if let Some(item) = &info.objective.retrieve {
+        let player = state.player_ent.unwrap();
+        if carried_count(state, player, item) > 0 {
+            record(state, MissionEvent::PickedUp(item.clone()));
+        }
+    }
+    record(state, MissionEvent::ReachedDepth(state.map.depth));
+}
+
+///Moves along every active mission the event counts towards, paying out the ones it completes
+pub fn record(state: &mut State, event: MissionEvent) {
+    let mut finished = Vec::new();
+
+    for mission in state.missions.active.iter_mut() {
+        let Some(info) = RAWS.lock().unwrap().get_mission(&mission.name) else {
+            continue;
+        };
+        let objective = &info.objective;
+
+        let done = match &event {
+            MissionEvent::Killed(name) if objective.kill.as_ref() == Some(name) => {
+                mission.progress += 1;
+                mission.progress >= objective.count.unwrap_or(1)
+            }
+            MissionEvent::PickedUp(name) => objective.retrieve.as_ref() == Some(name),
+            MissionEvent::Hacked(name) => objective.hack.as_ref() == Some(name),
+            MissionEvent::ReachedDepth(depth) => objective.depth.is_some_and(|goal| *depth >= goal),
+            _ => false,
+        };
+
+        if done {
+            finished.push(info);
+        }
+    }
+
+    for info in finished {
+        complete(state, &info);
+    }
+}
+
+fn complete(state: &mut State, info: &MissionInfo) {
+    state
+        .missions
+        .active
+        .retain(|mission| mission.name != info.name);
+    state.missions.completed.push(info.name.clone());
+    state
+        .game_log
+        .add_log(format!("Mission complete: {}!", info.name));
+
+    let player = state.player_ent.unwrap();
+    let reward = &info.reward;
+
+    if let Some(xp) = reward.xp {
+        if let Ok(pools) = state.world.query_one_mut::<&mut Pools>(player) {
+            pools.exp += xp;
+        }
+        state.game_log.add_log(format!("You get {} xp!", xp));
+        statistics::check_level_up(state);
+    }
+    if let Some(amount) = reward.credits {
+        add_credits(state, player, amount);
+        state
+            .game_log
+            .add_log(format!("You're paid {} credits.", amount));
+    }
+    for item in reward.items.iter().flatten() {
+        spawn_item_in_backpack(state, item, player);
+        let item = state.identification.display_name(item);
+        state.game_log.add_log(format!("You receive a {}.", item));
+    }
+}
+
+///What's left to do for the mission, shown in the journal
+pub fn objective_text(state: &State, mission: &Mission) -> String {
+    let Some(info) = RAWS.lock().unwrap().get_mission(&mission.name) else {
+        return String::new();
+    };
+    let objective = &info.objective;
+
+    if let Some(mob) = &objective.kill {
+        format!(
+            "Kill {}: {}/{}",
+            mob,
+            mission.progress,
+            objective.count.unwrap_or(1)
+        )
+    } else if let Some(item) = &objective.retrieve {
+        format!("Find a {}", state.identification.display_name(item))
+    } else if let Some(server) = &objective.hack {
+        format!("Read the records on the {}", server)
+    } else if let Some(depth) = objective.depth {
+        format!("Reach depth {} (now on {})", depth, state.map.depth)
+    } else {
+        String::new()
+    }
+}
+
+///Name and description of the mission, None if it's missing from the raws
+pub fn mission_info(name: &str) -> Option<MissionInfo> {
+    RAWS.lock().unwrap().get_mission(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{trading::credits, utils::testing::test_state};
+
+    #[test]
+    fn kill_missions_pay_out_once_enough_have_died() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+        let before = credits(&state, player);
+
+        accept_mission(&mut state, "Rat Problem");
+        assert_eq!(state.missions.status("Rat Problem"), MissionStatus::Active);
+
+        record(&mut state, MissionEvent::Killed("Rat".to_string()));
+        record(&mut state, MissionEvent::Killed("Drunkard".to_string()));
+        assert_eq!(state.missions.active[0].progress, 1);
+
+        record(&mut state, MissionEvent::Killed("Rat".to_string()));
+        record(&mut state, MissionEvent::Killed("Rat".to_string()));
+        assert_eq!(state.missions.status("Rat Problem"), MissionStatus::Done);
+        assert!(credits(&state, player) > before);
+
+        accept_mission(&mut state, "Rat Problem");
+        assert!(state.missions.active.is_empty());
+    }
+}
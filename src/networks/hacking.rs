@@ -0,0 +1,344 @@
+use hecs::Entity;
+
+use crate::{
+    components::{Disabled, Door, Name, PasswordProtected, Turret},
+    effects::{add_effect, EffectType, Targets},
+    interaction::{close_door, open_door},
+    missions::{self, MissionEvent},
+    statistics::{skill_check, Skill, StatType},
+    State,
+};
+
+use super::{Command, ControlNode, Controls, FileServer, NodeOwned, ParentNode, Records, RootNode};
+
+///Once the alert reaches this the network's ICE purges every intrusion
+pub const MAX_ALERT: i32 = 10;
+
+fn is_hackable(state: &State, ent: Entity) -> bool {
+    state.world.get::<&RootNode>(ent).is_ok()
+        || state.world.get::<&ControlNode>(ent).is_ok()
+        || state.world.get::<&FileServer>(ent).is_ok()
+}
+
+fn is_owned_by(state: &State, node: Entity, owner: Entity) -> bool {
+    state
+        .world
+        .get::<&NodeOwned>(node)
+        .is_ok_and(|owned| owned.owner == owner)
+}
+
+///Every hackable node that is either owned by the player or directly connected to one they own
+pub fn reachable_nodes(state: &State) -> Vec<Entity> {
+    let Some(player) = state.player_ent else {
+        return Vec::new();
+    };
+
+    let mut nodes = Vec::new();
+
+    for (owned, _) in state
+        .world
+        .query::<&NodeOwned>()
+        .iter()
+        .filter(|(_ent, owned)| owned.owner == player)
+    {
+        if let Some(connections) = state.network_map.get_connections(owned) {
+            nodes.extend(connections.iter().map(|conn| conn.entity));
+        }
+        nodes.push(owned);
+    }
+
+    nodes.retain(|node| is_hackable(state, *node) && state.world.get::<&Name>(*node).is_ok());
+    nodes.sort();
+    nodes.dedup();
+
+    nodes
+}
+
+///Difficulty class to infect a node, subnet nodes inherit the difficulty of their hub
+pub fn node_difficulty(state: &State, node: Entity) -> i32 {
+    if let Ok(root) = state.world.get::<&RootNode>(node) {
+        return root.difficulty;
+    }
+
+    let base = state
+        .world
+        .get::<&ParentNode>(node)
+        .ok()
+        .and_then(|parent| {
+            state
+                .world
+                .get::<&RootNode>(parent.ent)
+                .ok()
+                .map(|r| r.difficulty)
+        })
+        .unwrap_or(10);
+
+    let level = state
+        .world
+        .get::<&ControlNode>(node)
+        .map_or(0, |control| control.level);
+
+    base + level
+}
+
+///Picks the sensible command for the selected node, infecting it if it isn't owned yet or toggling what it controls if it is
+pub fn hack_node(state: &mut State, node: Entity) {
+    let player = state.player_ent.unwrap();
+
+    if !is_owned_by(state, node, player) {
+        run_hack_command(state, Command::Infect, node);
+    } else if state.world.get::<&Controls>(node).is_ok() {
+        if state.world.get::<&Disabled>(node).is_ok() {
+            run_hack_command(state, Command::Enable, node);
+        } else {
+            run_hack_command(state, Command::Disable, node);
+        }
+    } else if state.world.get::<&Records>(node).is_ok() {
+        let records = state
+            .world
+            .get::<&Records>(node)
+            .unwrap()
+            .content
+            .iter()
+            .cloned()
+            .collect::<Vec<String>>();
+
+        for record in records {
+            state.game_log.add_log(format!("[RECORD] {}", record));
+        }
+
+        if let Ok(name) = state.world.get::<&Name>(node).map(|name| name.name.clone()) {
+            missions::record(state, MissionEvent::Hacked(name));
+        }
+    } else {
+        run_hack_command(state, Command::ListNodes, node);
+    }
+}
+
+pub fn run_hack_command(state: &mut State, command: Command, node: Entity) {
+    let player = state.player_ent.unwrap();
+    let node_name = state
+        .world
+        .get::<&Name>(node)
+        .map_or("unknown node".to_string(), |name| name.name.clone());
+
+    match command {
+        Command::Infect => {
+            let difficulty = node_difficulty(state, node) + state.network_map.alert / 2;
+
+            if skill_check(
+                Skill::Hack,
+                StatType::Intelligence,
+                player,
+                state,
+                difficulty,
+            ) {
+                let _ = state.world.insert_one(node, NodeOwned { owner: player });
+                state.network_map.alert += 1;
+                state.game_log.add_log(format!(
+                    "You slip past the ICE and infect the {}.",
+                    node_name
+                ));
+            } else {
+                state.network_map.alert += 2;
+                state.game_log.add_log(format!(
+                    "The {} rejects your intrusion. Network alert rises to {}.",
+                    node_name, state.network_map.alert
+                ));
+            }
+
+            check_alert(state);
+        }
+
+        Command::Disable | Command::Enable => {
+            let Ok(controls) = state.world.get::<&Controls>(node).map(|c| *c) else {
+                state
+                    .game_log
+                    .add_log(format!("The {} doesn't control anything.", node_name));
+                return;
+            };
+
+            let devices = state
+                .network_map
+                .get_connections(node)
+                .map(|conns| conns.iter().map(|conn| conn.entity).collect::<Vec<_>>())
+                .unwrap_or_default();
+
+            let disable = command == Command::Disable;
+            let difficulty = node_difficulty(state, node);
+
+            match controls {
+                Controls::Doors => {
+                    for door in devices.iter() {
+                        let Ok(is_open) = state.world.get::<&Door>(*door).map(|d| d.open) else {
+                            continue;
+                        };
+                        if disable {
+                            let _ = state.world.remove_one::<PasswordProtected>(*door);
+                            if !is_open {
+                                open_door(state, player, *door);
+                            }
+                        } else {
+                            if is_open {
+                                close_door(state, player, *door);
+                            }
+                            let _ = state.world.insert_one(
+                                *door,
+                                PasswordProtected {
+                                    difficulty_class: difficulty,
+                                },
+                            );
+                        }
+                    }
+                }
+                Controls::Turrets => {
+                    for turret in devices.iter() {
+                        if state.world.get::<&Turret>(*turret).is_err() {
+                            continue;
+                        }
+                        if disable {
+                            let _ = state.world.insert_one(*turret, Disabled {});
+                        } else {
+                            let _ = state.world.remove_one::<Disabled>(*turret);
+                        }
+                    }
+                }
+                Controls::Map => {
+                    if disable {
+                        state
+                            .map
+                            .revealed_tiles
+                            .iter_mut()
+                            .for_each(|tile| *tile = true);
+                    }
+                }
+            }
+
+            if disable {
+                let _ = state.world.insert_one(node, Disabled {});
+                state
+                    .game_log
+                    .add_log(format!("You override the {}.", node_name));
+            } else {
+                let _ = state.world.remove_one::<Disabled>(node);
+                state.game_log.add_log(format!(
+                    "You restore the {} to normal operation.",
+                    node_name
+                ));
+            }
+        }
+
+        Command::ListNodes => {
+            state.game_log.add_log(format!(
+                "Network alert: {}/{}",
+                state.network_map.alert, MAX_ALERT
+            ));
+
+            for reachable in reachable_nodes(state) {
+                let name = state.world.get::<&Name>(reachable).unwrap().name.clone();
+                let status = if is_owned_by(state, reachable, player) {
+                    "owned"
+                } else {
+                    "locked"
+                };
+
+                state.game_log.add_log(format!("  {} [{}]", name, status));
+            }
+        }
+    }
+}
+
+///Purges all of the player's intrusions (other than their own deck) and fries them with feedback once the alert maxes out
+fn check_alert(state: &mut State) {
+    if state.network_map.alert < MAX_ALERT {
+        return;
+    }
+
+    let player = state.player_ent.unwrap();
+
+    let purged = state
+        .world
+        .query::<(&NodeOwned, &ParentNode)>()
+        .iter()
+        .filter(|(_ent, (owned, parent))| owned.owner == player && parent.ent != player)
+        .map(|(ent, _)| ent)
+        .chain(
+            state
+                .world
+                .query::<(&NodeOwned, &RootNode)>()
+                .iter()
+                .filter(|(_ent, (owned, _root))| owned.owner == player)
+                .map(|(ent, _)| ent),
+        )
+        .collect::<Vec<Entity>>();
+
+    for node in purged {
+        let _ = state.world.remove_one::<NodeOwned>(node);
+    }
+
+    state.network_map.alert = 0;
+    state.game_log.add_log(
+        "ICE traces your deck and purges every intrusion! Neural feedback burns through you."
+            .to_string(),
+    );
+
+    let feedback = state.rng.roll_dice(1, 6);
+    add_effect(
+        None,
+        //straight into the nervous system so armour doesn't help
+        EffectType::Damage {
+            amount: feedback,
+            damage_type: None,
+        },
+        Targets::Single { target: player },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::test_state;
+
+    #[test]
+    fn infected_file_servers_give_up_their_records() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+
+        let deck = state
+            .world
+            .query::<&NodeOwned>()
+            .iter()
+            .find(|(_ent, owned)| owned.owner == player)
+            .map(|(ent, _)| ent)
+            .unwrap();
+        //a hub no one could fail to get past
+        let hub = state.world.spawn((RootNode { difficulty: -100 },));
+        let server = state.world.spawn((
+            Name {
+                name: "Test Server".to_string(),
+            },
+            FileServer,
+            Records {
+                content: ["Test record".to_string()].into_iter().collect(),
+            },
+            ParentNode { ent: hub },
+        ));
+        state.network_map.add_node(server);
+        state.network_map.add_edge(deck, server);
+
+        assert!(reachable_nodes(&state).contains(&server));
+        assert_eq!(node_difficulty(&state, server), -100);
+
+        let alert = state.network_map.alert;
+        hack_node(&mut state, server);
+        assert!(is_owned_by(&state, server, player));
+        assert_eq!(state.network_map.alert, alert + 1);
+
+        hack_node(&mut state, server);
+        assert!(state
+            .game_log
+            .entries
+            .iter()
+            .any(|entry| entry.ends_with("[RECORD] Test record")));
+    }
+}
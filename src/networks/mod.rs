@@ -1,36 +1,55 @@
 use hecs::Entity;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
+mod hacking;
 mod network_builder;
 mod network_map;
 
+pub use hacking::*;
 pub use network_builder::*;
 pub use network_map::*;
 
+#[derive(Serialize, Deserialize)]
 pub struct Root;
+#[derive(Serialize, Deserialize)]
 pub struct RootNode {
     pub difficulty: i32,
 }
 
 //This component indicates the owner of the node this component is attached to
+#[derive(Serialize, Deserialize)]
 pub struct ParentNode {
     pub ent: Entity,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct ControlNode {
     pub level: i32,
 }
 
+///What the devices connected to a control node are
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Controls {
+    Doors,
+    Turrets,
+    Map,
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct FileServer;
 
+#[derive(Serialize, Deserialize)]
 pub struct Records {
     pub content: HashSet<String>,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct NodeOwned {
     pub owner: Entity,
 }
 //internal representation of possible hacking commands
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Command {
     Infect,
     Disable,
@@ -1,21 +1,134 @@
-use crate::State;
+use std::collections::HashSet;
 
-use super::{NetworkMap, Root, RootNode};
+use hecs::Entity;
 
+use crate::{components::{Door, Name, PasswordProtected, Turret}, gamelog::DEBUGLOG, raws::RAWS, Position, State};
 
+use super::{ControlNode, Controls, FileServer, NetworkMap, ParentNode, Root, RootNode};
 
+const ROOT_DIFFICULTY : i32 = 10;
 
-fn generate_network(state : &mut State)
+///Builds the network for the current level, every building network from the map builder gets its own subnet hub
+/// hanging off of the level's root node
+pub fn generate_network(state : &mut State, networks : &[(String, HashSet<usize>)])
 {
-    let root = state.world.spawn((RootNode{difficulty: 2}, Root{}));
-    let mut res = NetworkMap::new(root);
+    let root = state.world.spawn((RootNode{difficulty: ROOT_DIFFICULTY + state.map.depth}, Root{}
+        , Name{name: "Network Root".to_string()}));
+    let res = NetworkMap::new(root);
 
     if res.is_err() {panic!("Couldn't create root node of network!");}
 
     state.network_map = res.unwrap();
+
+    for (name, footprint) in networks.iter()
+    {
+        spawn_rootnodes(state, root, name, footprint);
+    }
+
+    connect_player_deck(state);
 }
 
-fn spawn_rootnodes(state : &mut State)
+///Spawns the subnet hub for a building and wires up every node prop and device within the building's footprint. A
+/// network missing from the raws is logged and left out rather than taking the level down with it
+fn spawn_rootnodes(state : &mut State, root : Entity, name : &String, footprint : &HashSet<usize>)
 {
-    
-}
\ No newline at end of file
+    let Some(template) = RAWS.lock().unwrap().get_network_from_name(name) else
+    {
+        DEBUGLOG.add_log(format!("Error: no network named {} in the raws, the building is left offline", name));
+        return;
+    };
+
+    let hub = state.world.spawn((RootNode{difficulty: template.difficulty + state.map.depth}
+        , Name{name: format!("{} subnet", template.name)}));
+    state.network_map.add_node(hub);
+    state.network_map.add_edge(root, hub);
+
+    let in_building = state.world.query::<&Position>()
+        .iter()
+        .filter(|(_ent, pos)| footprint.contains(&state.map.xy_idx(pos.x, pos.y)))
+        .map(|(ent, _pos)| ent)
+        .collect::<Vec<Entity>>();
+
+    let doors = in_building.iter()
+        .filter(|ent| state.world.get::<&Door>(**ent).is_ok())
+        .cloned()
+        .collect::<Vec<Entity>>();
+    let turrets = in_building.iter()
+        .filter(|ent| state.world.get::<&Turret>(**ent).is_ok())
+        .cloned()
+        .collect::<Vec<Entity>>();
+
+    for node in in_building.iter()
+    {
+        let is_node = state.world.get::<&ControlNode>(*node).is_ok() || state.world.get::<&FileServer>(*node).is_ok();
+        if !is_node {continue;}
+
+        let _ = state.world.insert_one(*node, ParentNode{ent: hub});
+        state.network_map.add_node(*node);
+        state.network_map.add_edge(hub, *node);
+
+        let controls = state.world.get::<&Controls>(*node).map(|c| *c);
+        let devices = match controls
+        {
+            Ok(Controls::Doors) => &doors,
+            Ok(Controls::Turrets) => &turrets,
+            _ => continue,
+        };
+
+        for device in devices.iter()
+        {
+            state.network_map.add_node(*device);
+            state.network_map.add_edge(*node, *device);
+        }
+    }
+
+    if template.locked.unwrap_or(false)
+    {
+        for door in doors.iter()
+        {
+            let _ = state.world.insert_one(*door, PasswordProtected{difficulty_class: template.difficulty});
+        }
+    }
+}
+
+///Plugs the player's cyberdeck into the root of the current network
+pub fn connect_player_deck(state : &mut State)
+{
+    let Some(player) = state.player_ent else {return;};
+
+    let deck = state.world.query::<(&ControlNode, &ParentNode)>()
+        .iter()
+        .find(|(_ent, (_control, parent))| parent.ent == player)
+        .map(|(ent, _)| ent);
+    let root = state.world.query::<&Root>()
+        .iter()
+        .next()
+        .map(|(ent, _)| ent);
+
+    if let (Some(deck), Some(root)) = (deck, root)
+    {
+        state.network_map.add_node(deck);
+        state.network_map.add_edge(deck, root);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::utils::testing::test_state;
+
+    #[test]
+    fn unknown_networks_are_left_offline()
+    {
+        let mut state = test_state(5);
+
+        let hubs = state.world.query::<&RootNode>().iter().count();
+
+        let footprint = HashSet::from([0usize]);
+        generate_network(&mut state, &[("No Such Network".to_string(), footprint)]);
+
+        //only the new level root, no subnet hub for the missing network
+        assert_eq!(state.world.query::<&RootNode>().iter().count(), hubs + 1);
+    }
+}
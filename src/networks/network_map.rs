@@ -2,21 +2,47 @@ use std::collections::{HashMap, HashSet};
 
 use bracket_lib::prelude::console;
 use hecs::Entity;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct NodeIndex {
     pub entity: Entity,
 }
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct NodeConnection {
     pub entity: Entity,
 }
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NetworkMap {
+    #[serde(with = "graph_as_pairs")]
     graph: HashMap<NodeIndex, Vec<NodeConnection>>,
     pub alert: i32,
 }
 
+///json maps can only have string keys so the graph is stored as a list of (node, edges) pairs instead
+mod graph_as_pairs {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{NodeConnection, NodeIndex};
+
+    pub fn serialize<S: Serializer>(
+        graph: &HashMap<NodeIndex, Vec<NodeConnection>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        graph.iter().collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<NodeIndex, Vec<NodeConnection>>, D::Error> {
+        let pairs = Vec::<(NodeIndex, Vec<NodeConnection>)>::deserialize(deserializer)?;
+
+        Ok(pairs.into_iter().collect())
+    }
+}
+
 impl NetworkMap {
     pub fn get_connections(&self, ent: Entity) -> Option<&Vec<NodeConnection>> {
         self.graph
@@ -27,11 +53,15 @@ impl NetworkMap {
             })
     }
 
+    ///Returns false if the node was already in the graph
     pub fn add_node(&mut self, ent: Entity) -> bool {
-        match self.graph.insert(NodeIndex { entity: ent }, Vec::new()) {
-            Some(_) => true,
-            None => false,
+        let index = NodeIndex { entity: ent };
+        if self.graph.contains_key(&index) {
+            return false;
         }
+
+        self.graph.insert(index, Vec::new());
+        true
     }
 
     fn add_connection(&mut self, node1: Entity, node2: Entity) -> bool {
@@ -79,6 +109,27 @@ impl NetworkMap {
         }
     }
 
+    ///Rewrites every node and connection using the provided old -> new entity lookup, nodes without a mapping are dropped
+    pub fn remap_entities(&mut self, remap: &HashMap<Entity, Entity>) {
+        self.graph = self
+            .graph
+            .drain()
+            .filter_map(|(index, edges)| {
+                let entity = *remap.get(&index.entity)?;
+                let edges = edges
+                    .into_iter()
+                    .filter_map(|edge| {
+                        remap
+                            .get(&edge.entity)
+                            .map(|ent| NodeConnection { entity: *ent })
+                    })
+                    .collect();
+
+                Some((NodeIndex { entity }, edges))
+            })
+            .collect();
+    }
+
     pub fn empty() -> NetworkMap {
         NetworkMap {
             graph: HashMap::new(),
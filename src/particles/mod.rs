@@ -49,4 +49,10 @@ impl ParticleBuilder
         self.requests.push(ParticleRequest {x,y,fg, bg, glyph, lifetime, target : follow_target});
     }
 
+    ///Throws away any pending requests, used when there is nothing to draw them to
+    pub fn clear(&mut self)
+    {
+        self.requests.clear();
+    }
+
 }
\ No newline at end of file
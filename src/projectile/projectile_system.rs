@@ -2,9 +2,10 @@ use bracket_lib::prelude::console;
 use hecs::Entity;
 
 use crate::{
+    ai::sneak_attack_multiplier,
     components,
-    effects::{self, add_effect, EffectType, Targets},
-    statistics::BaseStatistics,
+    effects::{self, add_effect, combat_trigger, EffectType, Targets},
+    statistics::{get_encumbrance, BaseStatistics},
     utils::get_mob_entities_at_position,
     Name, Position, RangedWeapon, Renderable, State,
 };
@@ -25,10 +26,10 @@ pub fn projectile_system(state: &mut State) {
     )>() {
         let pos = anim.path[anim.index];
 
-        proj_to_update.push((id, *proj_type, pos, anim.creator.clone(), proj.damage));
+        proj_to_update.push((id, *proj_type, pos, anim.creator.clone(), proj.clone()));
     }
 
-    for (proj, proj_type, pos, creator, damage) in proj_to_update.iter() {
+    for (proj, proj_type, pos, creator, projectile) in proj_to_update.iter() {
         let _ = state.world.remove_one::<ProjectileUpdated>(*proj);
 
         let hits = get_mob_entities_at_position(state, *pos);
@@ -46,17 +47,24 @@ pub fn projectile_system(state: &mut State) {
         let stats = *query.1;
 
         let mut roll = state.rng.roll_dice(1, 20);
-        roll += stats.dexterity.get_modifier();
+        roll += stats.dexterity.get_modifier() - get_encumbrance(state, hits[0]).dodge_penalty();
 
         if roll < 15 {
+            let mut dice = projectile.damage;
+            dice.n_dice *= sneak_attack_multiplier(state, *creator, hits[0]);
+
             //make a way of adding the original creator of the projectile to this
             add_effect(
                 Some(*creator),
                 EffectType::Damage {
-                    amount: state.rng.roll(*damage),
+                    amount: state.rng.roll(dice),
+                    damage_type: Some(projectile.damage_type),
                 },
                 Targets::Single { target: hits[0] },
             );
+            if !projectile.effects.is_empty() {
+                combat_trigger(*creator, &projectile.effects, hits[0], state);
+            }
 
             let msg = format!("{} was hit by missile", name.name.clone());
             console::log(msg.clone());
@@ -0,0 +1,188 @@
+use hecs::Entity;
+
+use crate::{
+    components::{Ammo, DamageType, Projectile},
+    EquipmentSlot, Equipped, Name, RangedWeapon, State,
+};
+
+///The first item the owner has equipped in the given slot
+fn equipped_in(state: &State, owner: Entity, slot: EquipmentSlot) -> Option<Entity> {
+    state
+        .world
+        .query::<&Equipped>()
+        .iter()
+        .find(|(_ent, equip)| equip.owner == owner && equip.slot == slot)
+        .map(|(ent, _equip)| ent)
+}
+
+pub fn equipped_ranged_weapon(state: &State, owner: Entity) -> Option<Entity> {
+    equipped_in(state, owner, EquipmentSlot::Ranged)
+}
+
+///Rounds left in the owner's ranged weapon, its magazine size and the kind of round loaded
+pub fn magazine_status(state: &State, owner: Entity) -> Option<(i32, i32, Option<String>)> {
+    let weapon = equipped_ranged_weapon(state, owner)?;
+    let ranged = state.world.get::<&RangedWeapon>(weapon).ok()?;
+
+    Some((
+        ranged.rounds,
+        ranged.magazine,
+        ranged.loaded.as_ref().map(|round| round.name.clone()),
+    ))
+}
+
+///Rounds left in the box of ammo in the owner's quiver
+pub fn quiver_rounds(state: &State, owner: Entity) -> i32 {
+    equipped_in(state, owner, EquipmentSlot::Quiver)
+        .and_then(|ammo| state.world.get::<&Ammo>(ammo).ok().map(|ammo| ammo.rounds))
+        .unwrap_or(0)
+}
+
+///Fills the owner's ranged weapon from the ammo in their quiver. Rounds of a different kind are ejected and lost to
+/// make room, the box of ammo is thrown away once it's empty. Returns whether anything was loaded
+pub fn reload(state: &mut State, owner: Entity) -> bool {
+    let is_player = Some(owner) == state.player_ent;
+
+    let Some(weapon) = equipped_ranged_weapon(state, owner) else {
+        if is_player {
+            state
+                .game_log
+                .add_log("You don't have a ranged weapon to reload.".to_string());
+        }
+        return false;
+    };
+    let Some(ammo_ent) = equipped_in(state, owner, EquipmentSlot::Quiver) else {
+        if is_player {
+            state
+                .game_log
+                .add_log("You don't have any ammo in your quiver.".to_string());
+        }
+        return false;
+    };
+
+    let weapon_name = state
+        .world
+        .get::<&Name>(weapon)
+        .map_or("weapon".to_string(), |name| name.name.clone());
+
+    let (Ok(mut ranged), Ok(mut ammo)) = (
+        state.world.get::<&mut RangedWeapon>(weapon),
+        state.world.get::<&mut Ammo>(ammo_ent),
+    ) else {
+        return false;
+    };
+
+    if ranged.caliber != ammo.caliber {
+        let round_name = ammo.round.name.clone();
+        std::mem::drop(ranged);
+        std::mem::drop(ammo);
+        if is_player {
+            state.game_log.add_log(format!(
+                "The {} doesn't fit the {}.",
+                round_name, weapon_name
+            ));
+        }
+        return false;
+    }
+
+    let mut ejected = 0;
+    if ranged.loaded.as_ref() != Some(&ammo.round) {
+        ejected = ranged.rounds;
+        ranged.rounds = 0;
+    }
+
+    let loaded = (ranged.magazine - ranged.rounds).min(ammo.rounds);
+    if loaded > 0 {
+        ranged.rounds += loaded;
+        ranged.loaded = Some(ammo.round.clone());
+        ammo.rounds -= loaded;
+    }
+
+    let round_name = ammo.round.name.clone();
+    let ammo_left = ammo.rounds;
+    std::mem::drop(ranged);
+    std::mem::drop(ammo);
+
+    if ammo_left <= 0 {
+        let _ = state.world.despawn(ammo_ent);
+    }
+
+    if is_player {
+        if ejected > 0 {
+            state
+                .game_log
+                .add_log(format!("You eject {} rounds to make room.", ejected));
+        }
+        if loaded > 0 {
+            state.game_log.add_log(format!(
+                "You load {} rounds of {} into the {}.",
+                loaded, round_name, weapon_name
+            ));
+        } else {
+            state
+                .game_log
+                .add_log(format!("The {} is already fully loaded.", weapon_name));
+        }
+    }
+
+    loaded > 0
+}
+
+///Takes a round out of the weapon's magazine and turns it into a projectile, None if the magazine is empty
+pub fn chamber_round(state: &mut State, weapon: Entity) -> Option<Projectile> {
+    let mut ranged = state.world.get::<&mut RangedWeapon>(weapon).ok()?;
+    if ranged.rounds <= 0 {
+        return None;
+    }
+    ranged.rounds -= 1;
+
+    let mut damage = ranged.damage;
+    let projectile = match &ranged.loaded {
+        Some(round) => {
+            damage.bonus += round.damage_bonus;
+            Projectile {
+                damage,
+                damage_type: round.damage_type,
+                effects: round.effects.clone(),
+            }
+        }
+        None => Projectile {
+            damage,
+            damage_type: DamageType::Kinetic,
+            effects: Default::default(),
+        },
+    };
+
+    if ranged.rounds == 0 {
+        ranged.loaded = None;
+    }
+
+    Some(projectile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{spawns::spawning_system, utils::testing::test_state};
+
+    #[test]
+    fn firing_uses_up_the_magazine_until_reloaded() {
+        let mut state = test_state(11);
+        let player = state.player_ent.unwrap();
+        let weapon = equipped_ranged_weapon(&state, player).unwrap();
+
+        assert_eq!(magazine_status(&state, player).unwrap().0, 8);
+        assert_eq!(quiver_rounds(&state, player), 8);
+
+        while chamber_round(&mut state, weapon).is_some() {}
+        assert_eq!(magazine_status(&state, player).unwrap(), (0, 8, None));
+
+        assert!(reload(&mut state, player));
+        assert_eq!(quiver_rounds(&state, player), 0);
+        assert!(!reload(&mut state, player));
+
+        spawning_system::spawn_item_equipped(&mut state, &"Taser Cartridge".to_string(), player);
+        assert!(!reload(&mut state, player));
+        assert_eq!(magazine_status(&state, player).unwrap().0, 8);
+    }
+}
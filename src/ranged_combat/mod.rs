@@ -1,10 +1,3 @@
+pub mod ammo;
 pub mod ranged_aim;
-
-
-pub enum AmmoType
-{
-    None,
-    Arrow,
-    Bullets
-}
-
+pub mod throwing;
@@ -12,8 +12,9 @@ use macroquad::{
 use crate::{
     camera,
     gui::{self, TargettingMode},
-    raws::{faction_reaction, Reaction, RAWS},
+    raws::Reaction,
     renderer::color_with_alpha,
+    reputation::faction_reaction,
     utils::get_mob_entities_at_position,
     Faction, FoV, Hidden, Map, Position, State,
 };
@@ -21,7 +22,7 @@ use crate::{
 pub enum TargettingState {
     None,
     Cancel,
-    Selected { path: Vec<Point>, end: Point },
+    Selected { end: Point },
 }
 
 pub fn aim_projectile(state: &mut State, start_pos: Point, range: i32) -> TargettingState {
@@ -59,7 +60,7 @@ pub fn aim_projectile(state: &mut State, start_pos: Point, range: i32) -> Target
             screen_pos.x -= min_x;
             screen_pos.y -= min_y;
 
-            state.renderer.draw_char_bg(
+            state.renderer().draw_char_bg(
                 screen_pos.x,
                 screen_pos.y,
                 *"*".to_cp437(&CP437_CONTROL).unwrap().first().unwrap(),
@@ -68,18 +69,14 @@ pub fn aim_projectile(state: &mut State, start_pos: Point, range: i32) -> Target
             );
         });
 
-        state.renderer.draw_square(
+        state.renderer().draw_square(
             screen_point.x,
             screen_point.y,
             color_with_alpha(macroquad::prelude::GREEN, 0.4),
         );
 
         if is_mouse_button_down(MouseButton::Left) {
-            let targets = bracket_lib::geometry::Bresenham::new(start_pos, point).collect();
-            return TargettingState::Selected {
-                path: targets,
-                end: point,
-            };
+            return TargettingState::Selected { end: point };
         }
         if is_key_pressed(KeyCode::Enter)
             || is_key_pressed(KeyCode::KpEnter)
@@ -88,14 +85,10 @@ pub fn aim_projectile(state: &mut State, start_pos: Point, range: i32) -> Target
             if point == state.player_pos {
                 return TargettingState::Cancel;
             }
-            let targets = bracket_lib::geometry::Bresenham::new(start_pos, point).collect();
-            return TargettingState::Selected {
-                path: targets,
-                end: point,
-            };
+            return TargettingState::Selected { end: point };
         }
     } else {
-        state.renderer.draw_square(
+        state.renderer().draw_square(
             screen_point.x,
             screen_point.y,
             color_with_alpha(macroquad::prelude::RED, 0.4),
@@ -130,7 +123,7 @@ fn targetting_viewshed(
                     && screen_y > 1
                     && screen_y < (max_y - min_y) - 1
                 {
-                    state.renderer.draw_square(
+                    state.renderer().draw_square(
                         idx.x - min_x,
                         idx.y - min_y,
                         color_with_alpha(macroquad::prelude::BLUE, 0.4),
@@ -162,7 +155,7 @@ pub fn select_nearest_target_pos(state: &mut State, ent: Entity, current_pos: Po
             let their_fac = state.world.get::<&Faction>(*mob).unwrap().name.clone();
             let their_pos = *state.world.get::<&Position>(*mob).unwrap().clone();
             let their_point = their_pos.into();
-            let react = faction_reaction(&their_fac, &faction, &RAWS.lock().unwrap());
+            let react = faction_reaction(&their_fac, &faction, state);
 
             if react == Reaction::Attack
                 && their_point != current_pos
@@ -0,0 +1,258 @@
+use bracket_lib::prelude::{BresenhamInclusive, DiceType, Point};
+use hecs::Entity;
+
+use crate::{
+    components::{DamageType, Weight},
+    effects::{
+        add_effect, get_aoe_tiles, Animation, EffectType, Particle, Targets, ANIMATIONQUEUE,
+    },
+    identification::item_name,
+    map_indexing::SPATIAL_INDEX,
+    maps::tile_walkable,
+    statistics::{get_encumbrance, BaseStatistics},
+    utils::get_mob_entities_at_position,
+    AoE, Consumable, Equipped, InContainer, Name, Position, RangedTargetting, Renderable, State,
+};
+
+///How far anyone can throw before their strength is taken into account
+const BASE_THROW_RANGE: i32 = 4;
+const MIN_THROW_RANGE: i32 = 2;
+///Items without a weight are treated as this heavy
+const DEFAULT_WEIGHT: f32 = 0.5;
+///Heavier items hit harder, up to this much on top of the 1d4
+const MAX_IMPACT_BONUS: i32 = 8;
+
+///Grenade-style items are consumables that have to be aimed, throwing them sets them off where they land
+pub fn is_grenade(state: &State, item: Entity) -> bool {
+    state.world.get::<&Consumable>(item).is_ok()
+        && state.world.get::<&RangedTargetting>(item).is_ok()
+}
+
+///Grenades can be thrown as far as they're meant to go, anything else depends on the thrower's strength
+pub fn throw_range(state: &State, thrower: Entity, item: Entity) -> i32 {
+    if is_grenade(state, item) {
+        if let Ok(ranged) = state.world.get::<&RangedTargetting>(item) {
+            return ranged.range;
+        }
+    }
+
+    let strength = state
+        .world
+        .get::<&BaseStatistics>(thrower)
+        .map_or(0, |stats| stats.strength.get_modifier());
+
+    (BASE_THROW_RANGE + strength).max(MIN_THROW_RANGE)
+}
+
+///1d4 plus a point for every full kilogram the item weighs
+pub fn impact_damage(weight: f32) -> DiceType {
+    DiceType::new(1, 4, (weight as i32).min(MAX_IMPACT_BONUS))
+}
+
+///Where a thrown item comes down on the way to the target, and who it hits if something's in the way. Walls and
+/// anything else that blocks the tile stop it short
+fn landing_point(state: &mut State, start: Point, target: Point) -> (Point, Option<Entity>) {
+    let mut landing = start;
+
+    for point in BresenhamInclusive::new(start, target).skip(1) {
+        let idx = state.map.xy_idx(point.x, point.y);
+        if !tile_walkable(state.map.map[idx]) {
+            break;
+        }
+        if let Some(hit) = get_mob_entities_at_position(state, point).first() {
+            return (point, Some(*hit));
+        }
+        if SPATIAL_INDEX.lock().unwrap().is_tile_blocked(idx) {
+            break;
+        }
+        landing = point;
+    }
+
+    (landing, None)
+}
+
+///Throws an item from the thrower's inventory at the target. Grenades go off where they land, anything else hits
+/// whoever is in the way and ends up on the floor
+pub fn throw_item(state: &mut State, thrower: Entity, item: Entity, target: Point) {
+    let Ok(start) = state
+        .world
+        .get::<&Position>(thrower)
+        .map(|pos| Into::<Point>::into(*pos))
+    else {
+        return;
+    };
+    let (landing, hit) = landing_point(state, start, target);
+
+    let _ = state.world.remove_one::<InContainer>(item);
+    let _ = state.world.remove_one::<Equipped>(item);
+
+    animate_throw(state, thrower, item, start, landing);
+
+    let item_name = item_name(state, item);
+
+    if is_grenade(state, item) {
+        let targets = match state.world.get::<&AoE>(item).map(|aoe| aoe.radius) {
+            Ok(radius) => Targets::Tiles {
+                tiles: get_aoe_tiles(state, radius, landing),
+            },
+            Err(_) => Targets::Tile {
+                tile_idx: state.map.xy_idx(landing.x, landing.y) as i32,
+            },
+        };
+        add_effect(Some(thrower), EffectType::ItemUse { item }, targets);
+        return;
+    }
+
+    if let Some(target) = hit {
+        hit_with_item(state, thrower, target, &item_name, item);
+    }
+
+    let _ = state.world.insert_one(item, Position::from(landing));
+}
+
+fn hit_with_item(
+    state: &mut State,
+    thrower: Entity,
+    target: Entity,
+    item_name: &str,
+    item: Entity,
+) {
+    let target_name = state
+        .world
+        .get::<&Name>(target)
+        .map_or("something".to_string(), |name| name.name.clone());
+    let dodge = state
+        .world
+        .get::<&BaseStatistics>(target)
+        .map_or(0, |stats| stats.dexterity.get_modifier())
+        - get_encumbrance(state, target).dodge_penalty();
+
+    //same odds of getting out of the way as a missile
+    if state.rng.roll_dice(1, 20) + dodge >= 15 {
+        state
+            .game_log
+            .add_log(format!("{} dodges the {}!", target_name, item_name));
+        return;
+    }
+
+    let weight = state
+        .world
+        .get::<&Weight>(item)
+        .map_or(DEFAULT_WEIGHT, |weight| weight.value);
+    let amount = state.rng.roll(impact_damage(weight));
+
+    state
+        .game_log
+        .add_log(format!("The {} hits {}!", item_name, target_name));
+    add_effect(
+        Some(thrower),
+        EffectType::Damage {
+            amount,
+            damage_type: Some(DamageType::Kinetic),
+        },
+        Targets::Single { target },
+    );
+}
+
+///Sends the item's glyph along the path it was thrown
+fn animate_throw(state: &State, thrower: Entity, item: Entity, start: Point, landing: Point) {
+    let path = BresenhamInclusive::new(start, landing)
+        .skip(1)
+        .collect::<Vec<_>>();
+    if path.is_empty() {
+        return;
+    }
+
+    let Ok(renderable) = state.world.get::<&Renderable>(item) else {
+        return;
+    };
+    let particle = Particle {
+        glyph: renderable.glyph.clone(),
+        fg: renderable.fg,
+        bg: renderable.bg,
+        lifetime: 60.,
+    };
+
+    ANIMATIONQUEUE.lock().unwrap().push((
+        Animation {
+            step_time: particle.lifetime - 20.,
+            current_step_time: particle.lifetime - 20.,
+            particle,
+            path,
+            index: 0,
+            creator: thrower,
+        },
+        None,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        effects::run_effect_queue,
+        map_indexing_system::MapIndexingSystem,
+        maps::TileType,
+        spawns::spawning_system::spawn_item_in_backpack,
+        statistics::Pools,
+        utils::testing::{spawn_test_mob, test_state},
+    };
+
+    fn carried(state: &State, owner: Entity, name: &str) -> Entity {
+        state
+            .world
+            .query::<(&Name, &InContainer)>()
+            .iter()
+            .find(|(_ent, (item, backpack))| item.name == name && backpack.owner == owner)
+            .map(|(ent, _)| ent)
+            .unwrap()
+    }
+
+    #[test]
+    fn thrown_items_stop_at_walls_and_mobs() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+        let start = state.player_pos;
+
+        //a clear lane with a wall at the end of it
+        for x in start.x + 1..start.x + 6 {
+            let idx = state.map.xy_idx(x, start.y);
+            state.map.map[idx] = TileType::Floor;
+        }
+        let wall = state.map.xy_idx(start.x + 6, start.y);
+        state.map.map[wall] = TileType::Wall;
+        MapIndexingSystem::run(&mut state);
+
+        let far = Point::new(start.x + 9, start.y);
+        assert_eq!(
+            landing_point(&mut state, start, far),
+            (Point::new(start.x + 5, start.y), None)
+        );
+
+        let thug = spawn_test_mob(&mut state, "Street Thug", start.x + 3, start.y);
+        MapIndexingSystem::run(&mut state);
+        assert_eq!(
+            landing_point(&mut state, start, far),
+            (Point::new(start.x + 3, start.y), Some(thug))
+        );
+
+        //anything that isn't a grenade is left lying where it lands
+        spawn_item_in_backpack(&mut state, &"Katana".to_string(), player);
+        let katana = carried(&state, player, "Katana");
+        throw_item(&mut state, player, katana, Point::new(start.x + 2, start.y));
+        assert!(state.world.get::<&InContainer>(katana).is_err());
+        assert_eq!(
+            state.world.get::<&Position>(katana).unwrap().as_tuple(),
+            (start.x + 2, start.y)
+        );
+
+        //grenades go off where they land instead
+        spawn_item_in_backpack(&mut state, &"HE Grenade".to_string(), player);
+        let grenade = carried(&state, player, "HE Grenade");
+        throw_item(&mut state, player, grenade, far);
+        run_effect_queue(&mut state);
+        assert!(state.world.get::<&Position>(grenade).is_err());
+        let hp = state.world.get::<&Pools>(thug).unwrap().hitpoints;
+        assert!(hp.current_value < hp.max_value);
+    }
+}
@@ -8,7 +8,10 @@ use serde::{Deserialize, Serialize};
 pub struct FactionInfo
 {
     pub name : String,
-    pub responses : HashMap<String, String>
+    pub responses : HashMap<String, String>,
+    ///factions that share this one's grudges and gratitude towards the player
+    #[serde(default)]
+    pub allies : Vec<String>
 }
 
 #[derive(PartialEq, Eq, Hash, Copy, Clone)]
@@ -0,0 +1,193 @@
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::gamelog::DEBUGLOG;
+
+use super::{Raws, RAWS};
+
+pub const DEFAULT_RAWS_DIR: &str = "./src/raws/data";
+
+///Set this environment variable to load raws from somewhere other than the default directory
+pub const RAWS_DIR_VAR: &str = "RAWS_DIR";
+
+///Lives in the root of the raws directory and is loaded separately by the scripting command loader
+pub const COMMANDS_FILE: &str = "commands.json";
+
+lazy_static! {
+    static ref RAWS_DIR: Mutex<PathBuf> = Mutex::new(PathBuf::from(
+        std::env::var(RAWS_DIR_VAR).unwrap_or(DEFAULT_RAWS_DIR.to_string())
+    ));
+}
+
+#[derive(Debug)]
+pub enum RawError {
+    Io {
+        path: PathBuf,
+        err: std::io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        err: serde_json::Error,
+    },
+    Duplicate {
+        kind: &'static str,
+        name: String,
+    },
+}
+
+impl fmt::Display for RawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawError::Io { path, err } => write!(f, "couldn't read {}: {}", path.display(), err),
+            RawError::Parse { path, err } => {
+                write!(f, "{} is malformed: {}", path.display(), err)
+            }
+            RawError::Duplicate { kind, name } => {
+                write!(f, "{} {} is defined more than once", kind, name)
+            }
+        }
+    }
+}
+
+pub fn raws_dir() -> PathBuf {
+    RAWS_DIR.lock().unwrap().clone()
+}
+
+///Points every later load and reload at another directory, set from `--raws DIR`
+pub fn set_raws_dir<P: AsRef<Path>>(dir: P) {
+    *RAWS_DIR.lock().unwrap() = dir.as_ref().to_path_buf();
+}
+
+///Reads a single json file from the raws directory
+pub fn read_raw_file<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, RawError> {
+    let data = fs::read_to_string(path).map_err(|err| RawError::Io {
+        path: path.to_path_buf(),
+        err,
+    })?;
+
+    serde_json::from_str(&data).map_err(|err| RawError::Parse {
+        path: path.to_path_buf(),
+        err,
+    })
+}
+
+///Merges every json file under the directory (other than the scripting commands) into one set of raws,
+/// files are read in path order so spawn tables come out the same every time
+pub fn load_raws_dir(dir: &Path) -> Result<Raws, RawError> {
+    let mut files = Vec::new();
+    collect_json_files(dir, &mut files)?;
+    files.retain(|path| path != &dir.join(COMMANDS_FILE));
+    files.sort();
+
+    let mut raws = Raws::default();
+
+    for path in files.iter() {
+        let partial: Raws = read_raw_file(path)?;
+        raws.merge(partial);
+    }
+
+    check_duplicates(&raws)?;
+
+    Ok(raws)
+}
+
+pub(super) fn collect_json_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), RawError> {
+    let io_err = |err| RawError::Io {
+        path: dir.to_path_buf(),
+        err,
+    };
+
+    for entry in fs::read_dir(dir).map_err(io_err)? {
+        let path = entry.map_err(io_err)?.path();
+
+        if path.is_dir() {
+            collect_json_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn check_duplicates(raws: &Raws) -> Result<(), RawError> {
+    fn check<'a>(
+        kind: &'static str,
+        names: impl Iterator<Item = &'a String>,
+    ) -> Result<(), RawError> {
+        let mut seen = HashSet::new();
+        for name in names {
+            if !seen.insert(name) {
+                return Err(RawError::Duplicate {
+                    kind,
+                    name: name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    check("item", raws.items.iter().map(|item| &item.name))?;
+    check("mob", raws.mobs.iter().map(|mob| &mob.name))?;
+    check("prop", raws.props.iter().map(|prop| &prop.name))?;
+    check(
+        "faction",
+        raws.faction_table.iter().map(|faction| &faction.name),
+    )?;
+    check("building", raws.buildings.iter().map(|build| &build.name))?;
+    check("network", raws.networks.iter().map(|network| &network.name))?;
+    check(
+        "level profile",
+        raws.level_profiles.iter().map(|profile| &profile.name),
+    )?;
+    check("theme", raws.themes.iter().map(|theme| &theme.name))?;
+    check("recipe", raws.recipes.iter().map(|recipe| &recipe.name))?;
+    check(
+        "stock table",
+        raws.stock_tables.iter().map(|table| &table.name),
+    )?;
+    check(
+        "dialogue",
+        raws.dialogues.iter().map(|dialogue| &dialogue.name),
+    )?;
+    check("mission", raws.missions.iter().map(|mission| &mission.name))?;
+
+    Ok(())
+}
+
+///Loads the raws from the configured directory into RAWS, the previous raws are kept if anything fails
+pub fn run() -> Result<(), RawError> {
+    let dir = raws_dir();
+    let raws = load_raws_dir(&dir)?;
+    RAWS.lock().unwrap().load(raws);
+
+    Ok(())
+}
+
+///Reloads RAWS while the game is running, only affects things spawned from now on
+pub fn reload() {
+    match run() {
+        Ok(()) => DEBUGLOG.add_log(format!("Reloaded raws from {}", raws_dir().display())),
+        Err(err) => DEBUGLOG.add_log(format!("Error: couldn't reload raws, {}", err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_raws_merge_from_every_file() {
+        let raws = load_raws_dir(Path::new(DEFAULT_RAWS_DIR)).unwrap();
+
+        assert!(!raws.items.is_empty());
+        assert!(!raws.mobs.is_empty());
+        assert!(!raws.props.is_empty());
+        assert!(!raws.spawn_table.is_empty());
+        assert!(!raws.networks.is_empty());
+    }
+}
@@ -1,9 +1,13 @@
-use serde::Deserialize;
-use std::{collections::HashMap, fs};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+mod loader;
+pub use loader::*;
+mod validation;
+pub use validation::*;
 mod rawmaster;
 pub use rawmaster::*;
 mod spawn_table_structs;
-use crate::{lazy_static::LazyStatic, Faction};
+use crate::{lazy_static::LazyStatic, Faction, TileType};
 use spawn_table_structs::*;
 use std::sync::Mutex;
 mod faction_structs;
@@ -15,7 +19,9 @@ lazy_static! {
     pub static ref RAWS: Mutex<RawMaster> = Mutex::new(RawMaster::empty());
 }
 
-#[derive(Deserialize, Debug)]
+///Every file in the raws directory deserializes into one of these, sections a file doesn't have are left empty
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
 pub struct Raws {
     pub items: Vec<Item>,
     pub mobs: Vec<Mob>,
@@ -23,33 +29,103 @@ pub struct Raws {
     pub spawn_table: Vec<SpawnTableEntry>,
     pub faction_table: Vec<FactionInfo>,
     pub buildings: Vec<Building>,
+    pub networks: Vec<Network>,
+    pub level_profiles: Vec<LevelProfile>,
+    pub themes: Vec<Theme>,
+    pub recipes: Vec<Recipe>,
+    pub stock_tables: Vec<StockTable>,
+    pub dialogues: Vec<DialogueTree>,
+    pub missions: Vec<MissionInfo>,
+}
+
+impl Raws {
+    pub fn merge(&mut self, other: Raws) {
+        self.items.extend(other.items);
+        self.mobs.extend(other.mobs);
+        self.props.extend(other.props);
+        self.spawn_table.extend(other.spawn_table);
+        self.faction_table.extend(other.faction_table);
+        self.buildings.extend(other.buildings);
+        self.networks.extend(other.networks);
+        self.level_profiles.extend(other.level_profiles);
+        self.themes.extend(other.themes);
+        self.recipes.extend(other.recipes);
+        self.stock_tables.extend(other.stock_tables);
+        self.dialogues.extend(other.dialogues);
+        self.missions.extend(other.missions);
+    }
 }
 
+///Ranged weapons spawn unloaded, magazine is how many rounds a reload puts in
 #[derive(Deserialize, Debug)]
 pub struct RangedWeapon {
     pub range: i32,
     pub damage: String,
+    pub caliber: String,
+    pub magazine: i32,
+}
+///A box of rounds for the quiver, on_hit effects fire at whoever each round hits
+#[derive(Deserialize, Debug)]
+pub struct Ammo {
+    pub caliber: String,
+    pub rounds: i32,
+    pub damage_bonus: Option<i32>,
+    pub damage_type: Option<String>,
+    pub on_hit: Option<HashMap<String, String>>,
 }
 #[derive(Deserialize, Debug)]
 pub struct Item {
     pub name: String,
+    ///In kilograms
+    pub weight: Option<f32>,
+    ///Base price in credits, vendors won't buy items without one
+    pub value: Option<i32>,
+    ///Items with this go by a made up name each run until they're identified, e.g. "injector" or "chip"
+    pub unidentified: Option<String>,
     pub renderable: Option<Renderable>,
     pub consumable: Option<Consumable>,
     pub equippable: Option<EquipmentStats>,
     pub weapon: Option<Weapon>,
     pub ranged: Option<RangedWeapon>,
+    pub ammo: Option<Ammo>,
     pub wearable: Option<Wearable>,
     pub rangedprojectile: Option<String>,
+    pub implant: Option<Implant>,
+}
+///Stats and side effects are keyed by stat name, e.g. "mentalfortitude": -1
+#[derive(Deserialize, Debug)]
+pub struct Implant {
+    pub slot: String,
+    pub stats: Option<BTreeMap<String, i32>>,
+    pub side_effects: Option<BTreeMap<String, i32>>,
+    pub ability: Option<ImplantAbility>,
 }
+
+///Effects fired when the implant is activated, using the same keys as a consumable
+#[derive(Deserialize, Debug)]
+pub struct ImplantAbility {
+    pub name: String,
+    pub cooldown: i32,
+    pub effects: HashMap<String, String>,
+}
+
+///on_hit effects fire at whoever gets hit, on_damaged effects fire back at the attacker
 #[derive(Debug, Deserialize, Clone)]
 pub struct Weapon {
     pub statistic: String,
     pub damage_die: String,
     pub to_hit_bonus: i32,
+    pub crit_multiplier: Option<i32>,
+    pub damage_type: Option<String>,
+    pub on_hit: Option<HashMap<String, String>>,
+    pub on_damaged: Option<HashMap<String, String>>,
 }
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct Wearable {
     pub ac_bonus: i32,
+    pub resistances: Option<HashMap<String, i32>>,
+    pub on_hit: Option<HashMap<String, String>>,
+    pub on_damaged: Option<HashMap<String, String>>,
 }
 #[derive(Deserialize, Debug)]
 pub struct EquipmentStats {
@@ -67,6 +143,14 @@ pub struct Mob {
     pub faction: Option<String>,
     pub movement_mode: Option<String>,
     pub quips: Option<Vec<String>>,
+    pub turret: Option<bool>,
+    ///Name of the stock table the mob trades from
+    pub vendor: Option<String>,
+    ///Name of the dialogue tree used when the player talks to the mob
+    pub dialogue: Option<String>,
+    pub resistances: Option<HashMap<String, i32>>,
+    pub on_hit: Option<HashMap<String, String>>,
+    pub on_damaged: Option<HashMap<String, String>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -105,6 +189,13 @@ pub struct Prop {
     pub blocks_tile: Option<bool>,
     pub interactable: Option<Interactable>,
     pub inventory: Option<Vec<String>>,
+    pub network_node: Option<NetworkNode>,
+    ///Item names to the dice rolled for how many of each are found when the prop is picked through
+    pub salvage: Option<BTreeMap<String, String>>,
+    ///Name of the stock table the prop trades from
+    pub vendor: Option<String>,
+    ///Name of the dialogue tree used when the player talks to or walks into the prop
+    pub dialogue: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -114,17 +205,138 @@ pub struct Building {
     pub network: Option<String>,
 }
 
+///A building's local network, the listed node props are spawned inside the building and hang off a subnet hub
+#[derive(Deserialize, Debug, Clone)]
+pub struct Network {
+    pub name: String,
+    pub difficulty: i32,
+    pub locked: Option<bool>,
+    pub nodes: Vec<String>,
+}
+
+///Makes a prop hackable, controls can be "doors", "turrets" or "map"
+#[derive(Deserialize, Debug, Clone)]
+pub struct NetworkNode {
+    pub level: i32,
+    pub controls: Option<String>,
+    pub records: Option<Vec<String>>,
+}
+
+///Which builders make the levels within a depth range and how they look, the first matching profile is used
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelProfile {
+    pub name: String,
+    pub min_depth: i32,
+    pub max_depth: i32,
+    pub starter: String,
+    pub builders: Vec<String>,
+    pub theme: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+}
+
+///Overrides for how each tile type is drawn, tiles that aren't listed keep the default look
+#[derive(Deserialize, Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub tiles: HashMap<TileType, TileTheme>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TileTheme {
+    pub glyph: Option<String>,
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+}
+
 ///Effects that apply when the machine is interacted with
 #[derive(Debug, Deserialize)]
 pub struct Interactable {
     pub consumable: Option<Consumable>,
+    ///Implants can be installed here
+    pub ripperdoc: Option<bool>,
+    ///Unidentified items can be worked out here
+    pub diagnostic: Option<bool>,
+    ///Recipes that need a workbench can only be made next to one of these
+    pub workbench: Option<bool>,
+}
+
+///Turns the ingredients in the crafter's backpack into count of the result. Known recipes can be made from the start,
+/// anything else has to be learnt from a schematic first
+#[derive(Deserialize, Debug, Clone)]
+pub struct Recipe {
+    pub name: String,
+    pub result: String,
+    pub count: Option<i32>,
+    pub ingredients: BTreeMap<String, i32>,
+    pub difficulty: i32,
+    pub workbench: Option<bool>,
+    pub known: Option<bool>,
+}
+
+///What a vendor has for sale, rolled again every time the player changes floor
+#[derive(Deserialize, Debug, Clone)]
+pub struct StockTable {
+    pub name: String,
+    pub stock: Vec<StockEntry>,
+}
+
+///Percentage chance of the item being stocked and the dice for how many of it there are
+#[derive(Deserialize, Debug, Clone)]
+pub struct StockEntry {
+    pub item: String,
+    pub count: String,
+    pub chance: Option<i32>,
+}
+
+///A conversation, starting from the start node and following whichever choices the player makes
+#[derive(Deserialize, Debug, Clone)]
+pub struct DialogueTree {
+    pub name: String,
+    pub start: String,
+    pub nodes: BTreeMap<String, DialogueNode>,
+}
+
+///What the speaker says and the replies the player has to it
+#[derive(Deserialize, Debug, Clone)]
+pub struct DialogueNode {
+    pub text: String,
+    pub choices: Vec<DialogueChoice>,
+}
+
+///A reply the player can give, only offered if all of its conditions hold. The outcomes happen when it's picked and
+/// the conversation moves on to the next node, or ends if there isn't one
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DialogueChoice {
+    pub text: String,
+    pub next: Option<String>,
+    pub conditions: Option<BTreeMap<String, String>>,
+    pub outcomes: Option<BTreeMap<String, String>>,
 }
 
-pub fn run() {
-    let data = fs::read_to_string(std::path::Path::new("./src/raws/spawns.json"))
-        .expect("Unable to read spawns.json");
-    //println!("{}", data);
-    let decoder: Raws = serde_json::from_str(&data).expect("Unable to parse JSON");
-    //bracket_lib::terminal::console::log(format!("{:?}", decoder));
-    RAWS.lock().unwrap().load(decoder);
+///A contract NPCs and terminals can hand out, the reward is paid out as soon as the objective is met
+#[derive(Deserialize, Debug, Clone)]
+pub struct MissionInfo {
+    pub name: String,
+    pub description: String,
+    pub objective: Objective,
+    pub reward: Reward,
+}
+
+///Exactly one of kill, retrieve, hack or depth is set. Count is how many of the named mob have to die
+#[derive(Deserialize, Debug, Clone)]
+pub struct Objective {
+    pub kill: Option<String>,
+    pub count: Option<i32>,
+    pub retrieve: Option<String>,
+    ///Name of the file server whose records have to be read
+    pub hack: Option<String>,
+    pub depth: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Reward {
+    pub xp: Option<i32>,
+    pub credits: Option<i32>,
+    pub items: Option<Vec<String>>,
 }
@@ -7,22 +7,62 @@ use bracket_lib::{
 };
 use hecs::{BuiltEntity, Entity, EntityBuilder, EntityBuilderClone};
 
-use super::{Building, Consumable, Mob, MobStats, Raws, Reaction, Renderable};
+use super::{
+    Building, Consumable, DialogueTree, Implant, LevelProfile, MissionInfo, Mob, MobStats, Network,
+    NetworkNode, Raws, Reaction, Recipe, Renderable, StockTable, Theme,
+};
 use crate::{
     ai::Energy,
     components::{
-        self, AIQuips, DescendFloors, EffectSpawner, EffectSpawnerPrefab, GrantStat, GrantsStatus,
-        MovementType,
+        self, AIQuips, AmmoRound, Blinded, Confused, DamageOverTime, DamageType, DescendFloors,
+        EffectSpawner, EffectSpawnerPrefab, EnergyDrain, GrantStat, GrantsStatus, MovementType,
+        NamedStatus, OnDamaged, OnExpire, OnHit, Resistances, StackingPolicy, Stunned, Turret,
     },
+    crafting::{Salvage, TeachesRecipe, Workbench},
+    cybernetics::{self, ImplantAbility, ImplantSlot, RipperDoc},
+    dialogue::Dialogue,
     effects::{Particle, ParticleAnimation, ParticleBurst, ParticleLine},
+    identification::DiagnosticTerminal,
+    networks::{ControlNode, Controls, FileServer, Records},
     randomtable::RandomTable,
     statistics::{self, Pools, StatPool, StatType},
+    trading::Vendor,
     AoE, Attribute, BlocksTiles, BlocksVisibility, DamageEffect, Door, EquipmentDirty,
     EquipmentSlot, Equippable, Faction, FoV, GivesFood, HealingEffect, Hidden, Monster, Name,
     Naturals, Position, RangedTargetting, RangedWeapon, SingleActivation, Trigger, TriggerOnEnter,
     Usable, WeaponStat,
 };
 
+///Every effect name add_effects_comps knows how to turn into components
+pub const EFFECT_KEYS: [&str; 21] = [
+    "provides_healing",
+    "ranged",
+    "damage",
+    "aoe",
+    "food",
+    "particle",
+    "particleline",
+    "descendfloor",
+    "grantstat",
+    "spawnstatus",
+    "duration",
+    "damage_type",
+    "teaches_recipe",
+    "status",
+    "stacking",
+    "dot",
+    "drain",
+    "stun",
+    "blind",
+    "confuse",
+    "expire",
+];
+
+///Effects that only mean something on a status effect, so they can only be used inside spawnstatus
+pub const STATUS_KEYS: [&str; 8] = [
+    "status", "stacking", "dot", "drain", "stun", "blind", "confuse", "expire",
+];
+
 pub enum SpawnType {
     AtPosition { x: i32, y: i32 },
     Equipped { target: Entity },
@@ -35,31 +75,37 @@ pub struct RawMaster {
     prop_index: HashMap<String, usize>,
     faction_index: HashMap<String, HashMap<String, Reaction>>,
     building_index: HashMap<String, usize>,
+    network_index: HashMap<String, usize>,
+    theme_index: HashMap<String, usize>,
+    recipe_index: HashMap<String, usize>,
+    stock_table_index: HashMap<String, usize>,
+    dialogue_index: HashMap<String, usize>,
+    mission_index: HashMap<String, usize>,
 }
 
 impl RawMaster {
     pub fn empty() -> RawMaster {
         RawMaster {
-            raws: Raws {
-                items: Vec::new(),
-                mobs: Vec::new(),
-                spawn_table: Vec::new(),
-                props: Vec::new(),
-                faction_table: Vec::new(),
-                buildings: Vec::new(),
-            },
+            raws: Raws::default(),
 
             item_index: HashMap::new(),
             mob_index: HashMap::new(),
             prop_index: HashMap::new(),
             faction_index: HashMap::new(),
             building_index: HashMap::new(),
+            network_index: HashMap::new(),
+            theme_index: HashMap::new(),
+            recipe_index: HashMap::new(),
+            stock_table_index: HashMap::new(),
+            dialogue_index: HashMap::new(),
+            mission_index: HashMap::new(),
         }
     }
 
     pub fn load(&mut self, raws: Raws) {
+        //start from scratch so reloading doesn't leave stale indexes behind
+        *self = RawMaster::empty();
         self.raws = raws;
-        self.item_index = HashMap::new();
         for (i, item) in self.raws.items.iter().enumerate() {
             self.item_index.insert(item.name.clone(), i);
         }
@@ -90,6 +136,30 @@ impl RawMaster {
         for (l, build) in self.raws.buildings.iter().enumerate() {
             self.building_index.insert(build.name.clone(), l);
         }
+
+        for (m, network) in self.raws.networks.iter().enumerate() {
+            self.network_index.insert(network.name.clone(), m);
+        }
+
+        for (n, theme) in self.raws.themes.iter().enumerate() {
+            self.theme_index.insert(theme.name.clone(), n);
+        }
+
+        for (o, recipe) in self.raws.recipes.iter().enumerate() {
+            self.recipe_index.insert(recipe.name.clone(), o);
+        }
+
+        for (p, table) in self.raws.stock_tables.iter().enumerate() {
+            self.stock_table_index.insert(table.name.clone(), p);
+        }
+
+        for (q, dialogue) in self.raws.dialogues.iter().enumerate() {
+            self.dialogue_index.insert(dialogue.name.clone(), q);
+        }
+
+        for (r, mission) in self.raws.missions.iter().enumerate() {
+            self.mission_index.insert(mission.name.clone(), r);
+        }
     }
 
     fn add_renderable_comp(
@@ -132,6 +202,10 @@ impl RawMaster {
                 "damage" => {
                     eb.add(DamageEffect {
                         damage_amount: effect.1.parse::<i32>().unwrap(),
+                        damage_type: effects
+                            .get("damage_type")
+                            .and_then(|t| DamageType::parse(t))
+                            .unwrap_or(DamageType::Kinetic),
                     });
                 }
                 "aoe" => {
@@ -177,12 +251,62 @@ impl RawMaster {
                         effect_map.insert(pair[0].into(), pair[1].into());
                     }
 
-                    //todo: actually figure out how to handle duration in this fucking awful mess wtf am I doing  :( ¬¬¬
                     eb.add(GrantsStatus {
                         effects: effect_map,
-                        duration: None,
+                        duration: effects.get("duration").and_then(|d| d.parse::<i32>().ok()),
+                    });
+                }
+                "teaches_recipe" => {
+                    eb.add(TeachesRecipe {
+                        recipe: effect.1.clone(),
+                    });
+                }
+                "status" => {
+                    eb.add(NamedStatus {
+                        name: effect.1.clone(),
+                        stacking: effects
+                            .get("stacking")
+                            .and_then(|s| StackingPolicy::parse(s))
+                            .unwrap_or(StackingPolicy::Refresh),
+                        stacks: 1,
+                    });
+                }
+                "dot" => {
+                    let (amount, damage_type) = match effect.1.split_once(':') {
+                        Some((amount, damage_type)) => (amount, DamageType::parse(damage_type)),
+                        None => (effect.1.as_str(), None),
+                    };
+
+                    eb.add(DamageOverTime {
+                        amount: amount.parse::<i32>().unwrap_or(0),
+                        damage_type,
                     });
                 }
+                "drain" => {
+                    eb.add(EnergyDrain {
+                        amount: effect.1.parse::<i32>().unwrap_or(0),
+                    });
+                }
+                "stun" => {
+                    eb.add(Stunned);
+                }
+                "blind" => {
+                    eb.add(Blinded {
+                        range: effect.1.parse::<i32>().unwrap_or(0),
+                    });
+                }
+                "confuse" => {
+                    eb.add(Confused);
+                }
+                "expire" => {
+                    if let Some((key, value)) = effect.1.split_once(':') {
+                        eb.add(OnExpire {
+                            effects: HashMap::from([(key.to_string(), value.to_string())]),
+                        });
+                    }
+                }
+                //read by spawnstatus, a status without one lasts until it's removed. Stacking is read by status
+                "duration" | "damage_type" | "stacking" => {}
                 _ => {
                     bracket_lib::terminal::console::log(format!(
                         "Warning: effect {} not implemented.",
@@ -205,6 +329,56 @@ impl RawMaster {
         }
     }
 
+    pub fn get_network_from_name(&self, name: &str) -> Option<Network> {
+        self.network_index
+            .get(name)
+            .map(|index| self.raws.networks[*index].clone())
+    }
+
+    pub fn get_level_profile(&self, depth: i32) -> Option<LevelProfile> {
+        self.raws
+            .level_profiles
+            .iter()
+            .find(|profile| depth >= profile.min_depth && depth <= profile.max_depth)
+            .cloned()
+    }
+
+    pub fn get_theme_for_depth(&self, depth: i32) -> Option<Theme> {
+        let profile = self
+            .raws
+            .level_profiles
+            .iter()
+            .find(|profile| depth >= profile.min_depth && depth <= profile.max_depth)?;
+
+        self.theme_index
+            .get(&profile.theme)
+            .map(|index| self.raws.themes[*index].clone())
+    }
+
+    pub fn get_recipe(&self, name: &str) -> Option<Recipe> {
+        self.recipe_index
+            .get(name)
+            .map(|index| self.raws.recipes[*index].clone())
+    }
+
+    pub fn get_stock_table(&self, name: &str) -> Option<StockTable> {
+        self.stock_table_index
+            .get(name)
+            .map(|index| self.raws.stock_tables[*index].clone())
+    }
+
+    pub fn get_dialogue(&self, name: &str) -> Option<DialogueTree> {
+        self.dialogue_index
+            .get(name)
+            .map(|index| self.raws.dialogues[*index].clone())
+    }
+
+    pub fn get_mission(&self, name: &str) -> Option<MissionInfo> {
+        self.mission_index
+            .get(name)
+            .map(|index| self.raws.missions[*index].clone())
+    }
+
     pub fn get_item_name_list(&self) -> Vec<String> {
         self.item_index.keys().map(|key| key.clone()).collect()
     }
@@ -218,22 +392,74 @@ impl RawMaster {
     }
 
     pub fn parse_particle_string(particle_string: String) -> Particle {
+        RawMaster::try_parse_particle_string(&particle_string)
+            .unwrap_or_else(|err| panic!("{} in particle {}", err, particle_string))
+    }
+
+    ///Particles are written as glyph;fg;bg;lifetime
+    pub fn try_parse_particle_string(particle_string: &str) -> Result<Particle, String> {
         let parts = particle_string.split(';').collect::<Vec<_>>();
 
-        let mut glyph_str = parts[0].clone().to_string();
+        if parts.len() != 4 {
+            return Err("particle needs to be glyph;fg;bg;lifetime".to_string());
+        }
 
-        let glyph = glyph_str;
-        let fg = RGB::from_hex(parts[1]).expect("not valid hex rgb for particle fg");
-        let bg = RGB::from_hex(parts[2]).expect("not valid hex rgb for particle bg");
+        let glyph = parts[0].to_string();
+        let fg = RGB::from_hex(parts[1]).map_err(|_| "not valid hex rgb for particle fg")?;
+        let bg = RGB::from_hex(parts[2]).map_err(|_| "not valid hex rgb for particle bg")?;
         let lifetime = parts[3]
             .parse::<f32>()
-            .expect("not valid f32 for particle lifetime");
+            .map_err(|_| "not valid f32 for particle lifetime")?;
 
-        Particle {
+        Ok(Particle {
             fg,
             glyph,
             bg,
             lifetime,
+        })
+    }
+
+    pub fn parse_equipment_slot(slot: &str) -> Option<EquipmentSlot> {
+        match slot {
+            "head" => Some(EquipmentSlot::Head),
+            "hands" => Some(EquipmentSlot::Hands),
+            "boots" => Some(EquipmentSlot::Boots),
+            "body" => Some(EquipmentSlot::Body),
+            "legs" => Some(EquipmentSlot::Legs),
+            "mainhand" => Some(EquipmentSlot::MainHand),
+            "offhand" => Some(EquipmentSlot::OffHand),
+            "ranged" => Some(EquipmentSlot::Ranged),
+            "quiver" => Some(EquipmentSlot::Quiver),
+            _ => None,
+        }
+    }
+
+    pub fn parse_implant_slot(slot: &str) -> Option<ImplantSlot> {
+        match slot {
+            "head" => Some(ImplantSlot::Head),
+            "eyes" => Some(ImplantSlot::Eyes),
+            "torso" => Some(ImplantSlot::Torso),
+            "arms" => Some(ImplantSlot::Arms),
+            "legs" => Some(ImplantSlot::Legs),
+            "nervous" => Some(ImplantSlot::Nervous),
+            _ => None,
+        }
+    }
+
+    pub fn parse_weapon_stat(statistic: &str) -> Option<WeaponStat> {
+        match statistic {
+            "strength" => Some(WeaponStat::Strength),
+            "dexterity" => Some(WeaponStat::Dexterity),
+            _ => None,
+        }
+    }
+
+    pub fn parse_controls(controls: &str) -> Option<Controls> {
+        match controls {
+            "doors" => Some(Controls::Doors),
+            "turrets" => Some(Controls::Turrets),
+            "map" => Some(Controls::Map),
+            _ => None,
         }
     }
 
@@ -257,18 +483,8 @@ impl RawMaster {
                 panic!("Error: could parse damage dice string correctly! {0}", e);
             }
         }
-        let mut stat = WeaponStat::Strength;
-        match slotname {
-            "strength" => {
-                stat = WeaponStat::Strength;
-            }
-            "dexterity" => {
-                stat = WeaponStat::Dexterity;
-            }
-            _ => {
-                panic!("error that wasn't a valid weapon statistic!");
-            }
-        }
+        let stat = RawMaster::parse_weapon_stat(slotname)
+            .expect("error that wasn't a valid weapon statistic!");
 
         components::Weapon {
             uses_statistic: stat,
@@ -276,6 +492,13 @@ impl RawMaster {
             to_hit_bonus: weapon.to_hit_bonus,
             dmg_bonus: dmg_die.bonus,
             num_dmg_dice: dmg_die.n_dice,
+            crit_multiplier: weapon.crit_multiplier.unwrap_or(2),
+            damage_type: weapon
+                .damage_type
+                .as_deref()
+                .map_or(DamageType::Kinetic, |t| {
+                    DamageType::parse(t).unwrap_or_else(|| panic!("Unknown damage type {}", t))
+                }),
         }
     }
 
@@ -339,6 +562,24 @@ impl RawMaster {
                 weps.push(Self::parse_weapon_comp(wep.clone()));
             }
 
+            //natural weapons can't carry components of their own so their triggers belong to the mob
+            let mut on_hit = mob_template.on_hit.clone();
+            let mut on_damaged = mob_template.on_damaged.clone();
+            for wep in naturals {
+                if let Some(effects) = &wep.on_hit {
+                    on_hit
+                        .get_or_insert_with(HashMap::new)
+                        .extend(effects.clone());
+                }
+                if let Some(effects) = &wep.on_damaged {
+                    on_damaged
+                        .get_or_insert_with(HashMap::new)
+                        .extend(effects.clone());
+                }
+            }
+            eb = RawMaster::add_trigger_comps(eb, &on_hit, &on_damaged);
+            eb = RawMaster::add_resistance_comp(eb, &mob_template.resistances);
+
             eb.add(Naturals {
                 weapons: weps.clone(),
             });
@@ -388,6 +629,20 @@ impl RawMaster {
                 });
             }
 
+            if let Some(true) = mob_template.turret {
+                eb.add(Turret {});
+            }
+
+            if let Some(table) = &mob_template.vendor {
+                eb.add(Vendor {
+                    table: table.clone(),
+                });
+            }
+
+            if let Some(tree) = &mob_template.dialogue {
+                eb.add(Dialogue { tree: tree.clone() });
+            }
+
             return (Some((Box::new(eb))), equip_list);
         }
 
@@ -423,10 +678,20 @@ impl RawMaster {
 
             eb.add(components::Item {});
 
+            if let Some(weight) = item_template.weight {
+                eb.add(components::Weight { value: weight });
+            }
+
+            if let Some(value) = item_template.value {
+                eb.add(components::Value { credits: value });
+            }
+
             if let Some(wearable) = &item_template.wearable {
                 eb.add(components::Wearable {
                     ac_bonus: wearable.ac_bonus,
                 });
+                eb = RawMaster::add_trigger_comps(eb, &wearable.on_hit, &wearable.on_damaged);
+                eb = RawMaster::add_resistance_comp(eb, &wearable.resistances);
             }
 
             if let Some(range) = &item_template.ranged {
@@ -434,6 +699,26 @@ impl RawMaster {
                     range: range.range,
                     damage: parse_dice_string(&range.damage)
                         .expect("Not valid dice string for ranged weapon!"),
+                    caliber: range.caliber.clone(),
+                    magazine: range.magazine,
+                    rounds: 0,
+                    loaded: None,
+                });
+            }
+
+            if let Some(ammo) = &item_template.ammo {
+                eb.add(components::Ammo {
+                    caliber: ammo.caliber.clone(),
+                    rounds: ammo.rounds,
+                    round: AmmoRound {
+                        name: item_template.name.clone(),
+                        damage_bonus: ammo.damage_bonus.unwrap_or(0),
+                        damage_type: ammo.damage_type.as_ref().map_or(DamageType::Kinetic, |t| {
+                            DamageType::parse(t)
+                                .unwrap_or_else(|| panic!("Unknown damage type {}", t))
+                        }),
+                        effects: ammo.on_hit.clone().unwrap_or_default(),
+                    },
                 });
             }
 
@@ -446,27 +731,14 @@ impl RawMaster {
                 eb = RawMaster::add_effects_comps(eb, effects);
             }
             if let Some(equipment) = &item_template.equippable {
-                let slot: EquipmentSlot;
-                let slotname = equipment.slot.as_str();
-                match slotname {
-                    "head" => slot = EquipmentSlot::Head,
-                    "hands" => slot = EquipmentSlot::Hands,
-                    "boots" => slot = EquipmentSlot::Boots,
-                    "body" => slot = EquipmentSlot::Body,
-                    "legs" => slot = EquipmentSlot::Legs,
-                    "mainhand" => slot = EquipmentSlot::MainHand,
-                    "offhand" => slot = EquipmentSlot::OffHand,
-                    "ranged" => slot = EquipmentSlot::Ranged,
-                    "quiver" => slot = EquipmentSlot::Quiver,
-                    _ => {
-                        panic!("Equipment slot incorrect in json!");
-                    }
-                }
+                let slot = RawMaster::parse_equipment_slot(&equipment.slot)
+                    .expect("Equipment slot incorrect in json!");
                 eb.add(Equippable { slot: slot });
             }
 
             if let Some(weapon) = &item_template.weapon {
                 eb.add(Self::parse_weapon_comp(weapon.clone()));
+                eb = RawMaster::add_trigger_comps(eb, &weapon.on_hit, &weapon.on_damaged);
             }
 
             if let Some(proj) = &item_template.rangedprojectile {
@@ -475,6 +747,10 @@ impl RawMaster {
                 });
             }
 
+            if let Some(implant) = &item_template.implant {
+                eb = RawMaster::add_implant_comps(eb, implant);
+            }
+
             return Some(Box::new(eb));
         }
 
@@ -519,11 +795,56 @@ impl RawMaster {
                 eb = RawMaster::add_effects_comps(eb, effects);
             }
 
-            //todo! add options for interactable to not have an effect spawner mayhaps?
             if let Some(interactable) = &prop_template.interactable {
-                let prefab = interactable.consumable.as_ref().unwrap().effects.clone();
+                if let Some(consumable) = &interactable.consumable {
+                    eb.add(EffectSpawnerPrefab {
+                        prefab: consumable.effects.clone(),
+                    });
+                }
 
-                eb.add(EffectSpawnerPrefab { prefab });
+                if let Some(true) = interactable.ripperdoc {
+                    eb.add(components::Interactable);
+                    eb.add(RipperDoc);
+                }
+
+                if let Some(true) = interactable.diagnostic {
+                    eb.add(components::Interactable);
+                    eb.add(DiagnosticTerminal);
+                }
+
+                if let Some(true) = interactable.workbench {
+                    eb.add(components::Interactable);
+                    eb.add(Workbench);
+                }
+            }
+
+            if let Some(salvage) = &prop_template.salvage {
+                eb.add(Salvage {
+                    yields: salvage
+                        .iter()
+                        .map(|(item, dice)| {
+                            (
+                                item.clone(),
+                                parse_dice_string(dice)
+                                    .expect("Not valid dice string for salvage!"),
+                            )
+                        })
+                        .collect(),
+                });
+            }
+
+            if let Some(table) = &prop_template.vendor {
+                eb.add(Vendor {
+                    table: table.clone(),
+                });
+            }
+
+            if let Some(tree) = &prop_template.dialogue {
+                eb.add(Dialogue { tree: tree.clone() });
+            }
+
+            if let Some(node) = &prop_template.network_node {
+                eb = RawMaster::add_network_node_comps(eb, node);
             }
 
             match pos {
@@ -539,6 +860,102 @@ impl RawMaster {
         None {}
     }
 
+    fn add_trigger_comps(
+        entity_builder: EntityBuilder,
+        on_hit: &Option<HashMap<String, String>>,
+        on_damaged: &Option<HashMap<String, String>>,
+    ) -> EntityBuilder {
+        let mut eb = entity_builder;
+
+        if let Some(effects) = on_hit {
+            eb.add(OnHit {
+                effects: effects.clone(),
+            });
+        }
+        if let Some(effects) = on_damaged {
+            eb.add(OnDamaged {
+                effects: effects.clone(),
+            });
+        }
+
+        eb
+    }
+
+    fn add_resistance_comp(
+        entity_builder: EntityBuilder,
+        resistances: &Option<HashMap<String, i32>>,
+    ) -> EntityBuilder {
+        let mut eb = entity_builder;
+
+        if let Some(resistances) = resistances {
+            eb.add(Resistances {
+                values: resistances
+                    .iter()
+                    .map(|(damage_type, amount)| {
+                        (
+                            DamageType::parse(damage_type)
+                                .unwrap_or_else(|| panic!("Unknown damage type {}", damage_type)),
+                            *amount,
+                        )
+                    })
+                    .collect(),
+            });
+        }
+
+        eb
+    }
+
+    fn add_implant_comps(entity_builder: EntityBuilder, implant: &Implant) -> EntityBuilder {
+        let mut eb = entity_builder;
+
+        let slot = RawMaster::parse_implant_slot(&implant.slot)
+            .unwrap_or_else(|| panic!("Unknown implant slot {}", implant.slot));
+        let stats = implant
+            .stats
+            .iter()
+            .chain(implant.side_effects.iter())
+            .flatten()
+            .map(|(stat, amount)| GrantStat {
+                stat: StatType::from_string(stat.as_str()),
+                amount: *amount,
+            })
+            .collect();
+
+        eb.add(cybernetics::Implant { slot, stats });
+
+        if let Some(ability) = &implant.ability {
+            eb.add(ImplantAbility {
+                name: ability.name.clone(),
+                cooldown: ability.cooldown,
+                remaining: 0,
+            });
+            eb = RawMaster::add_effects_comps(eb, ability.effects.clone());
+        }
+
+        eb
+    }
+
+    fn add_network_node_comps(entity_builder: EntityBuilder, node: &NetworkNode) -> EntityBuilder {
+        let mut eb = entity_builder;
+
+        if let Some(controls) = &node.controls {
+            eb.add(ControlNode { level: node.level });
+            eb.add(
+                RawMaster::parse_controls(controls)
+                    .unwrap_or_else(|| panic!("Unknown network control type {}", controls)),
+            );
+        }
+
+        if let Some(records) = &node.records {
+            eb.add(FileServer);
+            eb.add(Records {
+                content: records.iter().cloned().collect(),
+            });
+        }
+
+        eb
+    }
+
     pub fn spawn_effect_spawner<'a>(
         _raws: &'a RawMaster,
         new_entity: hecs::EntityBuilder,
@@ -592,3 +1009,45 @@ pub fn faction_reaction(my_faction: &str, their_faction: &str, raws: &RawMaster)
 
     Reaction::Ignore
 }
+
+///Every item that has to be identified along with what kind of thing it looks like, in the order they were loaded
+pub fn unidentified_items(raws: &RawMaster) -> Vec<(String, String)> {
+    raws.raws
+        .items
+        .iter()
+        .filter_map(|item| {
+            item.unidentified
+                .as_ref()
+                .map(|kind| (item.name.clone(), kind.clone()))
+        })
+        .collect()
+}
+
+///Recipes the player knows how to make at the start of a run
+pub fn known_recipes(raws: &RawMaster) -> Vec<String> {
+    raws.raws
+        .recipes
+        .iter()
+        .filter(|recipe| recipe.known.unwrap_or(false))
+        .map(|recipe| recipe.name.clone())
+        .collect()
+}
+
+pub fn faction_allies(faction: &str, raws: &RawMaster) -> Vec<String> {
+    raws.raws
+        .faction_table
+        .iter()
+        .find(|info| info.name == faction)
+        .map_or(Vec::new(), |info| info.allies.clone())
+}
+
+///Factions that are set to attack the given one by name rather than through their default response
+pub fn faction_enemies(faction: &str, raws: &RawMaster) -> Vec<String> {
+    raws.raws
+        .faction_table
+        .iter()
+        .filter(|info| info.name != faction)
+        .filter(|info| info.responses.get(faction).is_some_and(|r| r == "attack"))
+        .map(|info| info.name.clone())
+        .collect()
+}
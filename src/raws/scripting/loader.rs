@@ -1,15 +1,23 @@
 use crate::{
+    camera::forget_level_theme,
     effects::{add_effect, EffectType, Targets},
     gamelog::DEBUGLOG,
+    raws::{raws_dir, read_raw_file, reload, RawError, COMMANDS_FILE},
     scripting::ScriptingEngine,
 };
 
 use super::{Command, ScriptCommands};
 use rhai::ImmutableString;
 use serde::Deserialize;
-use std::{fs, sync::LazyLock};
+use std::sync::{LazyLock, Mutex};
 
-static COMMANDS: LazyLock<ScriptingCommandLoader> = LazyLock::new(ScriptingCommandLoader::new);
+static COMMANDS: LazyLock<Mutex<ScriptingCommandLoader>> =
+    LazyLock::new(|| Mutex::new(ScriptingCommandLoader::empty()));
+
+///Console commands that are handled directly rather than being looked up in commands.json
+const RELOAD_COMMAND: &str = "reload";
+///`seed` prints the current seed, `seed N` starts a new run from seed N
+const SEED_COMMAND: &str = "seed";
 
 #[derive(Debug, Deserialize)]
 pub struct ScriptingCommandLoader {
@@ -17,14 +25,16 @@ pub struct ScriptingCommandLoader {
 }
 
 impl ScriptingCommandLoader {
-    pub fn new() -> Self {
-        let data = fs::read_to_string(std::path::Path::new("./src/raws/commands.json"))
-            .expect("Unable to read commands.json");
-        //println!("{}", data);
-        let decoder: ScriptingCommandLoader =
-            serde_json::from_str(&data).expect("Unable to parse JSON");
+    pub fn empty() -> Self {
+        Self {
+            script_commands: ScriptCommands {
+                commands: Vec::new(),
+            },
+        }
+    }
 
-        decoder
+    pub fn new() -> Result<Self, RawError> {
+        read_raw_file(&raws_dir().join(COMMANDS_FILE))
     }
 
     pub fn register_commands(&self, engine: &mut ScriptingEngine) {
@@ -43,12 +53,59 @@ impl ScriptingCommandLoader {
 }
 
 pub fn load_scripting_commands(engine: &mut ScriptingEngine) {
-    COMMANDS.register_commands(engine);
-    DEBUGLOG.add_log(String::from("Scripting commands loaded."));
+    let mut commands = COMMANDS.lock().unwrap();
+    commands.register_commands(engine);
+
+    match ScriptingCommandLoader::new() {
+        Ok(loaded) => {
+            *commands = loaded;
+            DEBUGLOG.add_log(String::from("Scripting commands loaded."));
+        }
+        Err(err) => DEBUGLOG.add_log(format!("Error: couldn't load scripting commands, {}", err)),
+    }
+}
+
+///Reloads the raws along with the console commands so designers can tweak them without restarting
+fn reload_all() {
+    reload();
+    forget_level_theme();
+
+    match ScriptingCommandLoader::new() {
+        Ok(loaded) => *COMMANDS.lock().unwrap() = loaded,
+        Err(err) => DEBUGLOG.add_log(format!(
+            "Error: couldn't reload scripting commands, {}",
+            err
+        )),
+    }
 }
 
 fn invoke_command(command: ImmutableString) {
-    match COMMANDS.find_command(&command) {
+    if command == RELOAD_COMMAND {
+        reload_all();
+        return;
+    }
+
+    let mut words = command.split_whitespace();
+    if words.next() == Some(SEED_COMMAND) {
+        let seed = match words.next().map(|seed| seed.parse::<u64>()) {
+            None => None,
+            Some(Ok(seed)) => Some(seed),
+            Some(Err(_)) => {
+                DEBUGLOG.add_log(format!("Error: {} isn't a valid seed", command));
+                return;
+            }
+        };
+
+        add_effect(
+            None,
+            EffectType::Seed { seed },
+            Targets::Tile { tile_idx: 0 },
+        );
+        return;
+    }
+
+    let found = COMMANDS.lock().unwrap().find_command(&command);
+    match found {
         None => DEBUGLOG.add_log(format!("Error: No such command as {}", command)),
 
         Some(command) => add_effect(
@@ -6,7 +6,7 @@ use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct ScriptCommands {
-    commands: Vec<Command>,
+    pub commands: Vec<Command>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq, Clone)]
@@ -0,0 +1,882 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use bracket_lib::{color::RGB, random::parse_dice_string};
+use codepage_437::{ToCp437, CP437_WINGDINGS};
+use serde::Deserialize;
+
+use crate::{
+    components::{DamageType, StackingPolicy},
+    dialogue::{CONDITION_KEYS, OUTCOME_KEYS},
+    maps::{meta_builder_from_name, starter_from_name},
+    missions::MissionStatus,
+    statistics::{Skill, StatType},
+};
+
+use super::{
+    collect_json_files, read_raw_file, scripting::ScriptCommands, Ammo, Implant, RawError,
+    RawMaster, Raws, Renderable, Weapon, COMMANDS_FILE, EFFECT_KEYS, STATUS_KEYS,
+};
+
+///Faction responses can name these without them being in the faction table
+const BUILTIN_FACTIONS: [&str; 2] = ["Default", "Player"];
+
+#[derive(Debug)]
+pub struct RawProblem {
+    pub file: PathBuf,
+    pub entry: String,
+    pub message: String,
+}
+
+impl fmt::Display for RawProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {}",
+            self.file.display(),
+            self.entry,
+            self.message
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct CommandsFile {
+    script_commands: ScriptCommands,
+}
+
+///Names of everything defined across all of the raw files, used to check references between them
+#[derive(Default)]
+struct KnownNames {
+    items: HashSet<String>,
+    mobs: HashSet<String>,
+    props: HashSet<String>,
+    factions: HashSet<String>,
+    networks: HashSet<String>,
+    level_profiles: HashSet<String>,
+    themes: HashSet<String>,
+    recipes: HashSet<String>,
+    stock_tables: HashSet<String>,
+    dialogues: HashSet<String>,
+    missions: HashSet<String>,
+    ///Props with records on them that a mission can send the player to hack
+    file_servers: HashSet<String>,
+}
+
+impl KnownNames {
+    fn is_spawnable(&self, name: &String) -> bool {
+        self.items.contains(name) || self.mobs.contains(name) || self.props.contains(name)
+    }
+}
+
+///Reads every file in the raws directory and checks all of them, an error is only returned if a file can't be read
+/// or parsed at all
+pub fn validate_raws_dir(dir: &Path) -> Result<Vec<RawProblem>, RawError> {
+    let mut paths = Vec::new();
+    collect_json_files(dir, &mut paths)?;
+    paths.sort();
+
+    let commands_path = dir.join(COMMANDS_FILE);
+    let mut files = Vec::new();
+    let mut commands = None;
+
+    for path in paths {
+        if path == commands_path {
+            let loaded: CommandsFile = read_raw_file(&path)?;
+            commands = Some((path, loaded.script_commands));
+        } else {
+            let raws: Raws = read_raw_file(&path)?;
+            files.push((path, raws));
+        }
+    }
+
+    Ok(validate(&files, commands.as_ref()))
+}
+
+fn validate(
+    files: &[(PathBuf, Raws)],
+    commands: Option<&(PathBuf, ScriptCommands)>,
+) -> Vec<RawProblem> {
+    let mut problems = Vec::new();
+    let names = collect_names(files, &mut problems);
+
+    for (file, raws) in files.iter() {
+        let mut report = |entry: &str, message: String| {
+            problems.push(RawProblem {
+                file: file.clone(),
+                entry: entry.to_string(),
+                message,
+            })
+        };
+
+        for item in raws.items.iter() {
+            let entry = format!("item {}", item.name);
+
+            if let Some(renderable) = &item.renderable {
+                check_renderable(renderable, &mut |msg| report(&entry, msg));
+            }
+            if item.weight.is_some_and(|weight| weight < 0.) {
+                report(&entry, "weight can't be negative".to_string());
+            }
+            if item.value.is_some_and(|value| value < 0) {
+                report(&entry, "value can't be negative".to_string());
+            }
+            if item
+                .unidentified
+                .as_ref()
+                .is_some_and(|kind| kind.trim().is_empty())
+            {
+                report(
+                    &entry,
+                    "unidentified needs a kind of item to look like".to_string(),
+                );
+            }
+            if let Some(consumable) = &item.consumable {
+                check_effects(&consumable.effects, &mut |msg| report(&entry, msg));
+                if let Some(recipe) = consumable.effects.get("teaches_recipe") {
+                    if !names.recipes.contains(recipe) {
+                        report(&entry, format!("teaches unknown recipe {}", recipe));
+                    }
+                }
+            }
+            if let Some(equippable) = &item.equippable {
+                if RawMaster::parse_equipment_slot(&equippable.slot).is_none() {
+                    report(
+                        &entry,
+                        format!("unknown equipment slot {}", equippable.slot),
+                    );
+                }
+            }
+            if let Some(weapon) = &item.weapon {
+                check_weapon(weapon, &mut |msg| report(&entry, msg));
+            }
+            if let Some(wearable) = &item.wearable {
+                check_triggers(&wearable.on_hit, &wearable.on_damaged, &mut |msg| {
+                    report(&entry, msg)
+                });
+                check_resistances(&wearable.resistances, &mut |msg| report(&entry, msg));
+            }
+            if let Some(ranged) = &item.ranged {
+                if parse_dice_string(&ranged.damage).is_err() {
+                    report(&entry, format!("bad ranged damage dice {}", ranged.damage));
+                }
+                if ranged.magazine < 1 {
+                    report(&entry, "magazine must hold at least 1 round".to_string());
+                }
+            }
+            if let Some(ammo) = &item.ammo {
+                check_ammo(ammo, &mut |msg| report(&entry, msg));
+                if item.equippable.as_ref().map(|e| e.slot.as_str()) != Some("quiver") {
+                    report(
+                        &entry,
+                        "ammo has to be equippable in the quiver".to_string(),
+                    );
+                }
+            }
+            if let Some(projectile) = &item.rangedprojectile {
+                if let Err(err) = RawMaster::try_parse_particle_string(projectile) {
+                    report(&entry, format!("bad projectile {}: {}", projectile, err));
+                }
+            }
+            if let Some(implant) = &item.implant {
+                check_implant(implant, &mut |msg| report(&entry, msg));
+            }
+        }
+
+        for mob in raws.mobs.iter() {
+            let entry = format!("mob {}", mob.name);
+
+            check_renderable(&mob.renderable, &mut |msg| report(&entry, msg));
+
+            for weapon in mob.naturals.iter().flatten() {
+                check_weapon(weapon, &mut |msg| report(&entry, msg));
+            }
+            check_triggers(&mob.on_hit, &mob.on_damaged, &mut |msg| report(&entry, msg));
+            check_resistances(&mob.resistances, &mut |msg| report(&entry, msg));
+            for equipment in mob.equipment.iter().flatten() {
+                if !names.items.contains(equipment) {
+                    report(&entry, format!("equips unknown item {}", equipment));
+                }
+            }
+            if let Some(faction) = &mob.faction {
+                if !names.factions.contains(faction) {
+                    report(&entry, format!("belongs to unknown faction {}", faction));
+                }
+            }
+            if let Some(table) = &mob.vendor {
+                if !names.stock_tables.contains(table) {
+                    report(&entry, format!("trades from unknown stock table {}", table));
+                }
+            }
+            if let Some(dialogue) = &mob.dialogue {
+                if !names.dialogues.contains(dialogue) {
+                    report(&entry, format!("talks using unknown dialogue {}", dialogue));
+                }
+            }
+            if let Some(movement) = &mob.movement_mode {
+                if movement != "random" && movement != "drunk" {
+                    report(&entry, format!("unknown movement mode {}", movement));
+                }
+            }
+        }
+
+        for prop in raws.props.iter() {
+            let entry = format!("prop {}", prop.name);
+
+            check_renderable(&prop.renderable, &mut |msg| report(&entry, msg));
+
+            if let Some(consumable) = &prop.consumable {
+                check_effects(&consumable.effects, &mut |msg| report(&entry, msg));
+            }
+            if let Some(consumable) = prop
+                .interactable
+                .as_ref()
+                .and_then(|i| i.consumable.as_ref())
+            {
+                check_effects(&consumable.effects, &mut |msg| report(&entry, msg));
+            }
+            for item in prop.inventory.iter().flatten() {
+                if !names.items.contains(item) {
+                    report(&entry, format!("holds unknown item {}", item));
+                }
+            }
+            if let Some(controls) = prop.network_node.as_ref().and_then(|n| n.controls.as_ref()) {
+                if RawMaster::parse_controls(controls).is_none() {
+                    report(&entry, format!("controls unknown device type {}", controls));
+                }
+            }
+            if let Some(table) = &prop.vendor {
+                if !names.stock_tables.contains(table) {
+                    report(&entry, format!("trades from unknown stock table {}", table));
+                }
+            }
+            if let Some(dialogue) = &prop.dialogue {
+                if !names.dialogues.contains(dialogue) {
+                    report(&entry, format!("talks using unknown dialogue {}", dialogue));
+                }
+            }
+            for (item, dice) in prop.salvage.iter().flatten() {
+                if !names.items.contains(item) {
+                    report(&entry, format!("salvages into unknown item {}", item));
+                }
+                if parse_dice_string(dice).is_err() {
+                    report(&entry, format!("bad salvage dice {} for {}", dice, item));
+                }
+            }
+        }
+
+        for recipe in raws.recipes.iter() {
+            let entry = format!("recipe {}", recipe.name);
+
+            if !names.items.contains(&recipe.result) {
+                report(&entry, format!("makes unknown item {}", recipe.result));
+            }
+            if recipe.count.is_some_and(|count| count < 1) {
+                report(&entry, "has to make at least 1 item".to_string());
+            }
+            if recipe.ingredients.is_empty() {
+                report(&entry, "needs at least 1 ingredient".to_string());
+            }
+            for (ingredient, amount) in recipe.ingredients.iter() {
+                if !names.items.contains(ingredient) {
+                    report(&entry, format!("needs unknown item {}", ingredient));
+                }
+                if *amount < 1 {
+                    report(&entry, format!("needs at least 1 {}", ingredient));
+                }
+            }
+        }
+
+        for table in raws.stock_tables.iter() {
+            let entry = format!("stock table {}", table.name);
+
+            for stock in table.stock.iter() {
+                if !names.items.contains(&stock.item) {
+                    report(&entry, format!("stocks unknown item {}", stock.item));
+                }
+                if parse_dice_string(&stock.count).is_err() {
+                    report(
+                        &entry,
+                        format!("bad count dice {} for {}", stock.count, stock.item),
+                    );
+                }
+                if stock
+                    .chance
+                    .is_some_and(|chance| !(1..=100).contains(&chance))
+                {
+                    report(
+                        &entry,
+                        format!("chance for {} has to be between 1 and 100", stock.item),
+                    );
+                }
+            }
+        }
+
+        for dialogue in raws.dialogues.iter() {
+            let entry = format!("dialogue {}", dialogue.name);
+
+            if !dialogue.nodes.contains_key(&dialogue.start) {
+                report(&entry, format!("starts at unknown node {}", dialogue.start));
+            }
+            for (name, node) in dialogue.nodes.iter() {
+                for choice in node.choices.iter() {
+                    let mut report = |msg: String| {
+                        report(&entry, format!("node {}: {}: {}", name, choice.text, msg))
+                    };
+
+                    if let Some(next) = &choice.next {
+                        if !dialogue.nodes.contains_key(next) {
+                            report(format!("leads to unknown node {}", next));
+                        }
+                    }
+                    for (key, value) in choice.conditions.iter().flatten() {
+                        check_condition(key, value, &names, &mut report);
+                    }
+                    for (key, value) in choice.outcomes.iter().flatten() {
+                        check_outcome(key, value, &names, &mut report);
+                    }
+                }
+            }
+        }
+
+        for mission in raws.missions.iter() {
+            let entry = format!("mission {}", mission.name);
+            let objective = &mission.objective;
+
+            let objectives = [
+                objective.kill.is_some(),
+                objective.retrieve.is_some(),
+                objective.hack.is_some(),
+                objective.depth.is_some(),
+            ];
+            if objectives.iter().filter(|set| **set).count() != 1 {
+                report(
+                    &entry,
+                    "needs exactly one of kill, retrieve, hack or depth".to_string(),
+                );
+            }
+            if let Some(mob) = &objective.kill {
+                if !names.mobs.contains(mob) {
+                    report(&entry, format!("asks for unknown mob {} to be killed", mob));
+                }
+            }
+            if objective.count.is_some_and(|count| count < 1) {
+                report(&entry, "has to ask for at least 1 kill".to_string());
+            }
+            if objective.count.is_some() && objective.kill.is_none() {
+                report(&entry, "only kill objectives have a count".to_string());
+            }
+            if let Some(item) = &objective.retrieve {
+                if !names.items.contains(item) {
+                    report(&entry, format!("asks for unknown item {}", item));
+                }
+            }
+            if let Some(server) = &objective.hack {
+                if !names.file_servers.contains(server) {
+                    report(
+                        &entry,
+                        format!("{} isn't a prop with records on it", server),
+                    );
+                }
+            }
+            if objective.depth.is_some_and(|depth| depth < 1) {
+                report(
+                    &entry,
+                    "has to send the player at least 1 floor down".to_string(),
+                );
+            }
+            for item in mission.reward.items.iter().flatten() {
+                if !names.items.contains(item) {
+                    report(&entry, format!("rewards unknown item {}", item));
+                }
+            }
+        }
+
+        for spawn in raws.spawn_table.iter() {
+            let entry = format!("spawn table entry {}", spawn.name);
+
+            if !names.is_spawnable(&spawn.name) {
+                report(&entry, "isn't an item, mob or prop".to_string());
+            }
+            if spawn.min_depth > spawn.max_depth {
+                report(
+                    &entry,
+                    format!(
+                        "min depth {} is deeper than max depth {}",
+                        spawn.min_depth, spawn.max_depth
+                    ),
+                );
+            }
+        }
+
+        for faction in raws.faction_table.iter() {
+            let entry = format!("faction {}", faction.name);
+
+            for (other, reaction) in faction.responses.iter() {
+                if !names.factions.contains(other) && !BUILTIN_FACTIONS.contains(&other.as_str()) {
+                    report(&entry, format!("responds to unknown faction {}", other));
+                }
+                if !["ignore", "flee", "attack"].contains(&reaction.as_str()) {
+                    report(
+                        &entry,
+                        format!("unknown reaction {} to {}", reaction, other),
+                    );
+                }
+            }
+
+            for ally in faction.allies.iter() {
+                if !names.factions.contains(ally) {
+                    report(&entry, format!("is allied to unknown faction {}", ally));
+                }
+            }
+        }
+
+        for building in raws.buildings.iter() {
+            let entry = format!("building {}", building.name);
+
+            for content in building.contents.iter() {
+                if !names.is_spawnable(content) {
+                    report(
+                        &entry,
+                        format!("contains unknown item, mob or prop {}", content),
+                    );
+                }
+            }
+            if let Some(network) = &building.network {
+                if !names.networks.contains(network) {
+                    report(&entry, format!("uses unknown network {}", network));
+                }
+            }
+        }
+
+        for network in raws.networks.iter() {
+            let entry = format!("network {}", network.name);
+
+            for node in network.nodes.iter() {
+                if !names.props.contains(node) {
+                    report(&entry, format!("has unknown node prop {}", node));
+                }
+            }
+        }
+
+        for profile in raws.level_profiles.iter() {
+            let entry = format!("level profile {}", profile.name);
+
+            if starter_from_name(&profile.starter, profile.min_depth).is_none() {
+                report(
+                    &entry,
+                    format!("unknown starting builder {}", profile.starter),
+                );
+            }
+            for builder in profile.builders.iter() {
+                if meta_builder_from_name(builder).is_none() {
+                    report(&entry, format!("unknown builder {}", builder));
+                }
+            }
+            if !names.themes.contains(&profile.theme) {
+                report(&entry, format!("uses unknown theme {}", profile.theme));
+            }
+            if profile.min_depth > profile.max_depth {
+                report(
+                    &entry,
+                    format!(
+                        "min depth {} is deeper than max depth {}",
+                        profile.min_depth, profile.max_depth
+                    ),
+                );
+            }
+        }
+
+        for theme in raws.themes.iter() {
+            let entry = format!("theme {}", theme.name);
+
+            for (tile, style) in theme.tiles.iter() {
+                let glyph_ok = style.glyph.as_ref().is_none_or(|glyph| {
+                    glyph.chars().count() == 1 && glyph.to_cp437(&CP437_WINGDINGS).is_ok()
+                });
+                if !glyph_ok {
+                    report(
+                        &entry,
+                        format!("{:?} glyph isn't a single drawable character", tile),
+                    );
+                }
+                for colour in [&style.fg, &style.bg].into_iter().flatten() {
+                    if RGB::from_hex(colour).is_err() {
+                        report(&entry, format!("bad {:?} colour {}", tile, colour));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some((file, commands)) = commands {
+        for command in commands.commands.iter() {
+            check_effects(&command.consumable.effects, &mut |message| {
+                problems.push(RawProblem {
+                    file: file.clone(),
+                    entry: format!("command {}", command.name),
+                    message,
+                })
+            });
+        }
+    }
+
+    problems
+}
+
+fn collect_names(files: &[(PathBuf, Raws)], problems: &mut Vec<RawProblem>) -> KnownNames {
+    let mut names = KnownNames::default();
+    //remembers where each name was first defined so duplicates can point at both files
+    let mut defined_in: HashMap<(&str, &String), &PathBuf> = HashMap::new();
+
+    for (file, raws) in files.iter() {
+        let mut add = |kind: &'static str, name: &'static str, set: &mut HashSet<String>, entry| {
+            if !set.insert(String::clone(entry)) {
+                problems.push(RawProblem {
+                    file: file.clone(),
+                    entry: format!("{} {}", name, entry),
+                    message: format!(
+                        "is already defined in {}",
+                        defined_in[&(kind, entry)].display()
+                    ),
+                });
+            } else {
+                defined_in.insert((kind, entry), file);
+            }
+        };
+
+        for item in raws.items.iter() {
+            add("items", "item", &mut names.items, &item.name);
+        }
+        for mob in raws.mobs.iter() {
+            add("mobs", "mob", &mut names.mobs, &mob.name);
+        }
+        for prop in raws.props.iter() {
+            add("props", "prop", &mut names.props, &prop.name);
+            if prop
+                .network_node
+                .as_ref()
+                .is_some_and(|n| n.records.is_some())
+            {
+                names.file_servers.insert(prop.name.clone());
+            }
+        }
+        for faction in raws.faction_table.iter() {
+            add("factions", "faction", &mut names.factions, &faction.name);
+        }
+        for network in raws.networks.iter() {
+            add("networks", "network", &mut names.networks, &network.name);
+        }
+        for profile in raws.level_profiles.iter() {
+            add(
+                "level_profiles",
+                "level profile",
+                &mut names.level_profiles,
+                &profile.name,
+            );
+        }
+        for theme in raws.themes.iter() {
+            add("themes", "theme", &mut names.themes, &theme.name);
+        }
+        for recipe in raws.recipes.iter() {
+            add("recipes", "recipe", &mut names.recipes, &recipe.name);
+        }
+        for dialogue in raws.dialogues.iter() {
+            add(
+                "dialogues",
+                "dialogue",
+                &mut names.dialogues,
+                &dialogue.name,
+            );
+        }
+        for table in raws.stock_tables.iter() {
+            add(
+                "stock_tables",
+                "stock table",
+                &mut names.stock_tables,
+                &table.name,
+            );
+        }
+        for mission in raws.missions.iter() {
+            add("missions", "mission", &mut names.missions, &mission.name);
+        }
+    }
+
+    names
+}
+
+///Checks a condition on a dialogue choice, amounts come after the name, e.g. "hack:2"
+fn check_condition(key: &str, value: &str, names: &KnownNames, report: &mut dyn FnMut(String)) {
+    if !CONDITION_KEYS.contains(&key) {
+        report(format!("unknown condition {}", key));
+        return;
+    }
+
+    let amount = value.split_once(':');
+    let valid = match key {
+        "stat" => amount.is_some_and(|(stat, amount)| {
+            StatType::parse(stat).is_some() && amount.parse::<i32>().is_ok()
+        }),
+        "skill" => amount.is_some_and(|(skill, amount)| {
+            Skill::parse(skill).is_some() && amount.parse::<i32>().is_ok()
+        }),
+        "standing" => amount.is_some_and(|(faction, amount)| {
+            names.factions.contains(faction) && amount.parse::<i32>().is_ok()
+        }),
+        "has_item" => names.items.contains(value),
+        "mission" => amount.is_some_and(|(mission, status)| {
+            names.missions.contains(mission) && MissionStatus::parse(status).is_some()
+        }),
+        _ => value.parse::<i32>().is_ok(),
+    };
+
+    if !valid {
+        report(format!("bad value {} for condition {}", value, key));
+    }
+}
+
+fn check_outcome(key: &str, value: &str, names: &KnownNames, report: &mut dyn FnMut(String)) {
+    if !OUTCOME_KEYS.contains(&key) {
+        report(format!("unknown outcome {}", key));
+        return;
+    }
+
+    let valid = match key {
+        "give_item" | "take_item" => names.items.contains(value),
+        "reputation" => value.split_once(':').is_some_and(|(faction, amount)| {
+            names.factions.contains(faction) && amount.parse::<i32>().is_ok()
+        }),
+        "fight" => names.factions.contains(value),
+        "trade" => value == "true",
+        "offer_mission" => names.missions.contains(value),
+        _ => value.parse::<i32>().is_ok(),
+    };
+
+    if !valid {
+        report(format!("bad value {} for outcome {}", value, key));
+    }
+}
+
+fn check_renderable(renderable: &Renderable, report: &mut dyn FnMut(String)) {
+    if RGB::from_hex(&renderable.fg).is_err() {
+        report(format!("bad foreground colour {}", renderable.fg));
+    }
+    if RGB::from_hex(&renderable.bg).is_err() {
+        report(format!("bad background colour {}", renderable.bg));
+    }
+}
+
+fn check_weapon(weapon: &Weapon, report: &mut dyn FnMut(String)) {
+    if parse_dice_string(&weapon.damage_die).is_err() {
+        report(format!("bad damage dice {}", weapon.damage_die));
+    }
+    if RawMaster::parse_weapon_stat(&weapon.statistic).is_none() {
+        report(format!("unknown weapon statistic {}", weapon.statistic));
+    }
+    if weapon.crit_multiplier.is_some_and(|mult| mult < 1) {
+        report("crit_multiplier must be at least 1".to_string());
+    }
+    if let Some(damage_type) = &weapon.damage_type {
+        if DamageType::parse(damage_type).is_none() {
+            report(format!("unknown damage type {}", damage_type));
+        }
+    }
+    check_triggers(&weapon.on_hit, &weapon.on_damaged, report);
+}
+
+fn check_ammo(ammo: &Ammo, report: &mut dyn FnMut(String)) {
+    if ammo.rounds < 1 {
+        report("ammo needs at least 1 round".to_string());
+    }
+    if let Some(damage_type) = &ammo.damage_type {
+        if DamageType::parse(damage_type).is_none() {
+            report(format!("unknown damage type {}", damage_type));
+        }
+    }
+    check_triggers(&ammo.on_hit, &None, report);
+}
+
+fn check_triggers(
+    on_hit: &Option<HashMap<String, String>>,
+    on_damaged: &Option<HashMap<String, String>>,
+    report: &mut dyn FnMut(String),
+) {
+    if let Some(effects) = on_hit {
+        check_effects(effects, &mut |msg| report(format!("on_hit: {}", msg)));
+    }
+    if let Some(effects) = on_damaged {
+        check_effects(effects, &mut |msg| report(format!("on_damaged: {}", msg)));
+    }
+}
+
+fn check_resistances(resistances: &Option<HashMap<String, i32>>, report: &mut dyn FnMut(String)) {
+    for damage_type in resistances.iter().flat_map(|r| r.keys()) {
+        if DamageType::parse(damage_type).is_none() {
+            report(format!("resistance to unknown damage type {}", damage_type));
+        }
+    }
+}
+
+fn check_implant(implant: &Implant, report: &mut dyn FnMut(String)) {
+    if RawMaster::parse_implant_slot(&implant.slot).is_none() {
+        report(format!("unknown implant slot {}", implant.slot));
+    }
+    for stat in implant
+        .stats
+        .iter()
+        .chain(implant.side_effects.iter())
+        .flat_map(|stats| stats.keys())
+    {
+        if StatType::parse(stat).is_none() {
+            report(format!("implant changes unknown stat {}", stat));
+        }
+    }
+    if let Some(ability) = &implant.ability {
+        if ability.cooldown < 0 {
+            report(format!("ability {} has a negative cooldown", ability.name));
+        }
+        check_effects(&ability.effects, report);
+    }
+}
+
+fn check_effects(effects: &HashMap<String, String>, report: &mut dyn FnMut(String)) {
+    for (effect, value) in effects.iter() {
+        if !EFFECT_KEYS.contains(&effect.as_str()) {
+            report(format!("unknown effect {}", effect));
+            continue;
+        }
+        if STATUS_KEYS.contains(&effect.as_str()) {
+            report(format!("{} only works inside spawnstatus", effect));
+            continue;
+        }
+
+        if effect == "spawnstatus" {
+            check_status(value, report);
+        } else if !effect_value_is_valid(effect, value) {
+            report(format!("bad value {} for effect {}", value, effect));
+        }
+    }
+}
+
+///The effects a status is made of, written as key.value pairs joined with underscores
+fn check_status(value: &str, report: &mut dyn FnMut(String)) {
+    for status in value.split('_') {
+        let Some((effect, value)) = status.split_once('.') else {
+            report(format!("status effect {} needs a value", status));
+            continue;
+        };
+
+        if !EFFECT_KEYS.contains(&effect) || effect == "spawnstatus" {
+            report(format!("unknown status effect {}", effect));
+        } else if !effect_value_is_valid(effect, value) {
+            report(format!("bad value {} for status effect {}", value, effect));
+        }
+    }
+}
+
+fn effect_value_is_valid(effect: &str, value: &str) -> bool {
+    match effect {
+        "particle" | "particleline" => RawMaster::try_parse_particle_string(value).is_ok(),
+        "descendfloor" => value.parse::<u32>().is_ok(),
+        "damage_type" => DamageType::parse(value).is_some(),
+        //which recipes exist is checked along with the rest of the names
+        "teaches_recipe" => !value.is_empty(),
+        "grantstat" => value.split_once(':').is_some_and(|(stat, amount)| {
+            StatType::parse(stat).is_some() && amount.parse::<i32>().is_ok()
+        }),
+        "spawnstatus" => {
+            let mut valid = true;
+            check_status(value, &mut |_| valid = false);
+            valid
+        }
+        "status" => !value.is_empty(),
+        "stacking" => StackingPolicy::parse(value).is_some(),
+        "dot" => match value.split_once(':') {
+            Some((amount, damage_type)) => {
+                amount.parse::<i32>().is_ok() && DamageType::parse(damage_type).is_some()
+            }
+            None => value.parse::<i32>().is_ok(),
+        },
+        //fires a single effect, statuses can't start more statuses
+        "expire" => value.split_once(':').is_some_and(|(effect, value)| {
+            EFFECT_KEYS.contains(&effect)
+                && !STATUS_KEYS.contains(&effect)
+                && effect != "spawnstatus"
+                && effect_value_is_valid(effect, value)
+        }),
+        _ => value.parse::<i32>().is_ok(),
+    }
+}
+
+///Prints every problem found in the configured raws directory, returns false if there were any
+pub fn print_validation_report(dir: &Path) -> bool {
+    match validate_raws_dir(dir) {
+        Ok(problems) if problems.is_empty() => {
+            println!("No problems found in {}", dir.display());
+            true
+        }
+        Ok(problems) => {
+            for problem in problems.iter() {
+                println!("{}", problem);
+            }
+            println!("{} problems found in {}", problems.len(), dir.display());
+            false
+        }
+        Err(err) => {
+            println!("Couldn't validate raws: {}", err);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raws::DEFAULT_RAWS_DIR;
+
+    #[test]
+    fn default_raws_are_valid() {
+        let problems = validate_raws_dir(Path::new(DEFAULT_RAWS_DIR)).unwrap();
+
+        assert!(
+            problems.is_empty(),
+            "{}",
+            problems
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    #[test]
+    fn broken_references_are_reported() {
+        let raws: Raws = serde_json::from_str(
+            r##"{
+                "mobs": [{
+                    "name": "Thug",
+                    "renderable": {"glyph": "t", "fg": "#FF0000", "bg": "#notacolour", "order": 1},
+                    "stats": {"max_hp": 5, "hp": 5},
+                    "vision_range": 8,
+                    "blocks_tiles": true,
+                    "naturals": [{"statistic": "luck", "damage_die": "1d", "to_hit_bonus": 0}],
+                    "faction": "nobody"
+                }],
+                "spawn_table": [{"name": "Missing Item", "weight": 1, "min_depth": 0, "max_depth": 1}],
+                "buildings": [{"name": "Shack", "contents": ["Thug", "Ghost"]}]
+            }"##,
+        )
+        .unwrap();
+
+        let problems = validate(&[(PathBuf::from("test.json"), raws)], None);
+        let messages = problems.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(problems.len(), 6, "{:?}", messages);
+        assert!(messages.contains(
+            &"test.json: building Shack: contains unknown item, mob or prop Ghost".to_string()
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    raws::{self, Reaction, RAWS},
+    Faction, State,
+};
+
+///At or above this the faction leaves the player alone whatever it thinks of them by default
+pub const FRIENDLY_STANDING: i32 = 25;
+///Below this factions that would ignore the player keep their distance instead
+pub const WARY_STANDING: i32 = -25;
+///Below this the faction attacks on sight, unless it's the kind that runs from the player anyway
+pub const HOSTILE_STANDING: i32 = -50;
+
+const KILL_PENALTY: i32 = 10;
+const ENEMY_KILL_BONUS: i32 = 4;
+const HELP_BONUS: i32 = 3;
+
+const PLAYER_FACTION: &str = "Player";
+
+///How each faction feels about the player this run, everyone starts out at 0
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Reputation {
+    standing: HashMap<String, i32>,
+}
+
+impl Reputation {
+    pub fn new() -> Reputation {
+        Reputation::default()
+    }
+
+    pub fn standing(&self, faction: &str) -> i32 {
+        *self.standing.get(faction).unwrap_or(&0)
+    }
+
+    pub fn adjust(&mut self, faction: &str, amount: i32) {
+        *self.standing.entry(faction.to_string()).or_insert(0) += amount;
+    }
+
+    ///Every faction the player has had dealings with, sorted by name
+    pub fn standings(&self) -> Vec<(String, i32)> {
+        let mut standings = self
+            .standing
+            .iter()
+            .map(|(faction, standing)| (faction.clone(), *standing))
+            .collect::<Vec<_>>();
+        standings.sort();
+        standings
+    }
+
+    ///Applies the faction's standing with the player on top of its reaction from the raws
+    pub fn reaction(&self, faction: &str, base: Reaction) -> Reaction {
+        let standing = self.standing(faction);
+
+        if standing >= FRIENDLY_STANDING {
+            Reaction::Ignore
+        } else if standing < HOSTILE_STANDING && base != Reaction::Flee {
+            Reaction::Attack
+        } else if standing < WARY_STANDING && base == Reaction::Ignore {
+            Reaction::Flee
+        } else {
+            base
+        }
+    }
+}
+
+pub fn standing_label(standing: i32) -> &'static str {
+    if standing >= FRIENDLY_STANDING {
+        "Friendly"
+    } else if standing < HOSTILE_STANDING {
+        "Hostile"
+    } else if standing < WARY_STANDING {
+        "Wary"
+    } else {
+        "Neutral"
+    }
+}
+
+///How my_faction reacts to their_faction, reactions towards the player depend on the player's reputation
+pub fn faction_reaction(my_faction: &str, their_faction: &str, state: &State) -> Reaction {
+    let base = raws::faction_reaction(my_faction, their_faction, &RAWS.lock().unwrap());
+
+    if their_faction == PLAYER_FACTION {
+        state.reputation.reaction(my_faction, base)
+    } else {
+        base
+    }
+}
+
+///Whether the entity would attack the player on sight, anything without a faction is harmless
+pub fn hostile_to_player(state: &State, ent: Entity) -> bool {
+    state.world.get::<&Faction>(ent).is_ok_and(|faction| {
+        faction_reaction(&faction.name, PLAYER_FACTION, state) == Reaction::Attack
+    })
+}
+
+///Changes the player's standing with a faction, its allies are affected by half as much
+pub fn change_standing(state: &mut State, faction: &str, amount: i32) {
+    let allies = raws::faction_allies(faction, &RAWS.lock().unwrap());
+
+    adjust_and_report(state, faction, amount);
+    for ally in allies.iter() {
+        adjust_and_report(state, ally, amount / 2);
+    }
+}
+
+///The player killed a member of the faction, its enemies are grateful for it
+pub fn player_killed(state: &mut State, victim_faction: &str) {
+    let enemies = raws::faction_enemies(victim_faction, &RAWS.lock().unwrap());
+
+    change_standing(state, victim_faction, -KILL_PENALTY);
+    for enemy in enemies.iter() {
+        change_standing(state, enemy, ENEMY_KILL_BONUS);
+    }
+}
+
+///The player patched up a member of the faction, its allies appreciate it too
+pub fn player_helped(state: &mut State, faction: &str) {
+    change_standing(state, faction, HELP_BONUS);
+}
+
+fn adjust_and_report(state: &mut State, faction: &str, amount: i32) {
+    if amount == 0 || faction == PLAYER_FACTION {
+        return;
+    }
+
+    let before = standing_label(state.reputation.standing(faction));
+    state.reputation.adjust(faction, amount);
+    let after = standing_label(state.reputation.standing(faction));
+
+    if before != after {
+        state.game_log.add_log(format!(
+            "The {} now consider you {}.",
+            faction,
+            after.to_lowercase()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        effects::{add_effect, run_effect_queue, EffectType, Targets},
+        statistics::Pools,
+        utils::testing::{spawn_test_mob, test_state},
+    };
+
+    #[test]
+    fn standing_flips_reactions_at_thresholds() {
+        let mut rep = Reputation::new();
+        assert!(rep.reaction("townsfolk", Reaction::Ignore) == Reaction::Ignore);
+
+        rep.adjust("townsfolk", WARY_STANDING - 1);
+        assert!(rep.reaction("townsfolk", Reaction::Ignore) == Reaction::Flee);
+
+        rep.adjust("townsfolk", HOSTILE_STANDING - WARY_STANDING);
+        assert!(rep.reaction("townsfolk", Reaction::Ignore) == Reaction::Attack);
+        assert!(rep.reaction("townsfolk", Reaction::Flee) == Reaction::Flee);
+
+        rep.adjust("peacekeeper", FRIENDLY_STANDING);
+        assert!(rep.reaction("peacekeeper", Reaction::Attack) == Reaction::Ignore);
+    }
+
+    #[test]
+    fn healing_wounded_members_improves_standing() {
+        let mut state = test_state(5);
+        let player = state.player_ent.unwrap();
+        let pos = state.player_pos;
+
+        let thug = spawn_test_mob(&mut state, "Street Thug", pos.x + 1, pos.y);
+
+        let heal = || {
+            add_effect(
+                Some(player),
+                EffectType::Healing { amount: 5 },
+                Targets::Single { target: thug },
+            )
+        };
+
+        heal();
+        run_effect_queue(&mut state);
+        assert_eq!(state.reputation.standing("criminals"), 0);
+
+        state
+            .world
+            .get::<&mut Pools>(thug)
+            .unwrap()
+            .hitpoints
+            .damage(3);
+        heal();
+        run_effect_queue(&mut state);
+        assert_eq!(state.reputation.standing("criminals"), HELP_BONUS);
+    }
+}
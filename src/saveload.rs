@@ -0,0 +1,518 @@
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use bracket_lib::{
+    prelude::{Algorithm2D, Point},
+    random::RandomNumberGenerator,
+};
+use hecs::{
+    serialize::row::{self, try_serialize, DeserializeContext, SerializeContext},
+    Entity, EntityBuilder, EntityRef, World,
+};
+use serde::{de::MapAccess, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    ai::{Chasing, Energy, InCombat, LastKnownPosition, MyTurn},
+    components::*,
+    crafting::{KnownRecipes, Salvage, TeachesRecipe, Workbench},
+    cybernetics::{Implant, ImplantAbility, Installed, RipperDoc},
+    dialogue::Dialogue,
+    dungeon_master::{DungeonMaster, StoredLevel},
+    effects::{Particle, ParticleAnimation, ParticleBurst, ParticleLine},
+    gamelog::{GameLog, DEBUGLOG},
+    hunger::HungerLevel,
+    identification::{DiagnosticTerminal, Identification},
+    map_indexing::SPATIAL_INDEX,
+    map_indexing_system::MapIndexingSystem,
+    maps::map::Map,
+    missions::MissionLog,
+    networks::{
+        ControlNode, Controls, FileServer, NetworkMap, NodeOwned, ParentNode, Records, Root,
+        RootNode,
+    },
+    player::Player,
+    reputation::Reputation,
+    statistics::{BaseStatistics, Pools, Skills},
+    trading::{Credits, Vendor},
+    Position, ProgramState, State,
+};
+
+pub const SAVE_PATH: &str = "./savegame.json";
+
+///Bump this whenever a change to components or State makes older saves unloadable
+pub const SAVE_VERSION: u32 = 13;
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Serde(serde_json::Error),
+    WrongVersion { found: u32, expected: u32 },
+    NoPlayer,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "couldn't access save file: {}", err),
+            SaveError::Serde(err) => write!(f, "save file is malformed: {}", err),
+            SaveError::WrongVersion { found, expected } => write!(
+                f,
+                "save file is version {} but version {} is required",
+                found, expected
+            ),
+            SaveError::NoPlayer => write!(f, "save file doesn't contain a player"),
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(value: std::io::Error) -> Self {
+        SaveError::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(value: serde_json::Error) -> Self {
+        SaveError::Serde(value)
+    }
+}
+
+///Generates the ComponentId enum along with the hecs row (de)serialization contexts for every listed component,
+/// any component not in this list is discarded when saving
+macro_rules! saved_components {
+    ($($comp:ident),* $(,)?) => {
+        #[derive(Serialize, Deserialize)]
+        enum ComponentId {
+            $($comp),*
+        }
+
+        struct SaveContext;
+
+        impl SerializeContext for SaveContext {
+            fn serialize_entity<S>(&mut self, entity: EntityRef<'_>, mut map: S) -> Result<S::Ok, S::Error>
+            where
+                S: SerializeMap,
+            {
+                $(try_serialize::<$comp, _, _>(&entity, &ComponentId::$comp, &mut map)?;)*
+                map.end()
+            }
+        }
+
+        struct LoadContext;
+
+        impl DeserializeContext for LoadContext {
+            fn deserialize_entity<'de, M>(&mut self, mut map: M, entity: &mut EntityBuilder) -> Result<(), M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        $(ComponentId::$comp => {
+                            entity.add::<$comp>(map.next_value()?);
+                        })*
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+saved_components!(
+    Position,
+    Player,
+    Name,
+    Renderable,
+    FoV,
+    Pools,
+    BaseStatistics,
+    Skills,
+    KnownRecipes,
+    HungerLevel,
+    Energy,
+    MyTurn,
+    Faction,
+    HurtByPlayer,
+    Monster,
+    Turret,
+    Disabled,
+    MovementType,
+    AIQuips,
+    InCombat,
+    Chasing,
+    LastKnownPosition,
+    BlocksTiles,
+    BlocksVisibility,
+    Door,
+    Prop,
+    Hidden,
+    DescendFloors,
+    TriggerOnEnter,
+    SingleActivation,
+    Trigger,
+    Password,
+    PasswordProtected,
+    Interactable,
+    Item,
+    Consumable,
+    Usable,
+    GivesFood,
+    Equippable,
+    Equipped,
+    EquipmentDirty,
+    InContainer,
+    Wearable,
+    Naturals,
+    Weapon,
+    RangedWeapon,
+    Ammo,
+    Weight,
+    Projectile,
+    EffectSpawner,
+    EffectSpawnerPrefab,
+    GrantsAttribute,
+    StatusEffect,
+    GrantsStatus,
+    EffectDuration,
+    NamedStatus,
+    DamageOverTime,
+    EnergyDrain,
+    Stunned,
+    Blinded,
+    Confused,
+    OnExpire,
+    AoE,
+    RangedTargetting,
+    DamageEffect,
+    HealingEffect,
+    GrantStat,
+    OnHit,
+    OnDamaged,
+    Resistances,
+    Particle,
+    ParticleBurst,
+    ParticleLine,
+    ParticleAnimation,
+    Root,
+    RootNode,
+    ParentNode,
+    ControlNode,
+    Controls,
+    FileServer,
+    Records,
+    NodeOwned,
+    Implant,
+    Installed,
+    ImplantAbility,
+    RipperDoc,
+    DiagnosticTerminal,
+    Workbench,
+    Salvage,
+    TeachesRecipe,
+    Value,
+    Credits,
+    Vendor,
+    Dialogue,
+);
+
+struct SavedWorld<'a>(&'a World);
+
+impl Serialize for SavedWorld<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        row::serialize(self.0, &mut SaveContext, serializer)
+    }
+}
+
+struct LoadedWorld(World);
+
+impl<'de> Deserialize<'de> for LoadedWorld {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        row::deserialize(&mut LoadContext, deserializer).map(LoadedWorld)
+    }
+}
+
+#[derive(Serialize)]
+struct SavedLevel<'a> {
+    depth: i32,
+    map: &'a Map,
+    network_map: &'a NetworkMap,
+    player_pos: Point,
+    world: SavedWorld<'a>,
+}
+
+#[derive(Deserialize)]
+struct LoadedLevel {
+    depth: i32,
+    map: Map,
+    network_map: NetworkMap,
+    player_pos: Point,
+    world: LoadedWorld,
+}
+
+#[derive(Serialize)]
+struct SaveGame<'a> {
+    version: u32,
+    depth: i32,
+    turn_number: i32,
+    player_ent: Option<Entity>,
+    seed: u64,
+    rng: &'a RandomNumberGenerator,
+    map: &'a Map,
+    network_map: &'a NetworkMap,
+    reputation: &'a Reputation,
+    identification: &'a Identification,
+    missions: &'a MissionLog,
+    game_log: &'a GameLog,
+    world: SavedWorld<'a>,
+    levels: Vec<SavedLevel<'a>>,
+}
+
+#[derive(Deserialize)]
+struct LoadGame {
+    version: u32,
+    depth: i32,
+    turn_number: i32,
+    player_ent: Option<Entity>,
+    seed: u64,
+    rng: RandomNumberGenerator,
+    map: Map,
+    network_map: NetworkMap,
+    reputation: Reputation,
+    identification: Identification,
+    missions: MissionLog,
+    game_log: GameLog,
+    world: LoadedWorld,
+    levels: Vec<LoadedLevel>,
+}
+
+pub fn does_save_exist() -> bool {
+    Path::new(SAVE_PATH).exists()
+}
+
+pub fn save_game(state: &State) -> Result<(), SaveError> {
+    let save = SaveGame {
+        version: SAVE_VERSION,
+        depth: state.map.depth,
+        turn_number: state.turn_number,
+        player_ent: state.player_ent,
+        seed: state.seed,
+        rng: &state.rng,
+        map: &state.map,
+        network_map: &state.network_map,
+        reputation: &state.reputation,
+        identification: &state.identification,
+        missions: &state.missions,
+        game_log: &state.game_log,
+        world: SavedWorld(&state.world),
+        levels: state
+            .dungeon
+            .levels()
+            .map(|(depth, level)| SavedLevel {
+                depth,
+                map: &level.map,
+                network_map: &level.network_map,
+                player_pos: level.player_pos,
+                world: SavedWorld(&level.world),
+            })
+            .collect(),
+    };
+
+    let data = serde_json::to_string(&save)?;
+    fs::write(SAVE_PATH, data)?;
+
+    Ok(())
+}
+
+///Replaces the current game with the one stored in the save file, state is left untouched if loading fails
+pub fn load_game(state: &mut State) -> Result<(), SaveError> {
+    let data = fs::read_to_string(SAVE_PATH)?;
+    let save: LoadGame = serde_json::from_str(&data)?;
+
+    if save.version != SAVE_VERSION {
+        return Err(SaveError::WrongVersion {
+            found: save.version,
+            expected: SAVE_VERSION,
+        });
+    }
+
+    let mut world = World::new();
+    let mut remap = HashMap::new();
+    move_entities(save.world.0, &mut world, &mut remap);
+
+    //stored floors get placeholders in the main world first so their handles can't be handed out to anything else
+    let mut levels = Vec::new();
+    for level in save.levels {
+        let mut stored = World::new();
+        let old_entities: Vec<Entity> = level.world.0.iter().map(|ent| ent.entity()).collect();
+        let mut loaded = level.world.0;
+
+        for old in old_entities {
+            let new = world.spawn(());
+            stored.spawn_at(new, loaded.take(old).unwrap());
+            remap.insert(old, new);
+        }
+
+        levels.push((
+            level.depth,
+            level.map,
+            level.network_map,
+            level.player_pos,
+            stored,
+        ));
+    }
+
+    fix_entity_refs(&mut world, &remap);
+
+    let mut dungeon = DungeonMaster::new();
+    for (depth, map, mut network_map, player_pos, mut stored) in levels {
+        fix_entity_refs(&mut stored, &remap);
+        network_map.remap_entities(&remap);
+        dungeon.insert(
+            depth,
+            StoredLevel {
+                map,
+                network_map,
+                world: stored,
+                player_pos,
+            },
+        );
+    }
+
+    let player = save
+        .player_ent
+        .and_then(|ent| remap.get(&ent).copied())
+        .ok_or(SaveError::NoPlayer)?;
+    let player_pos = *world
+        .get::<&Position>(player)
+        .map_err(|_| SaveError::NoPlayer)?;
+
+    let mut network_map = save.network_map;
+    network_map.remap_entities(&remap);
+
+    state.world = world;
+    state.map = save.map;
+    state.map.depth = save.depth;
+    state.rng = save.rng;
+    state.seed = save.seed;
+    state.game_log = save.game_log;
+    state.turn_number = save.turn_number;
+    state.network_map = network_map;
+    state.dungeon = dungeon;
+    state.reputation = save.reputation;
+    state.identification = save.identification;
+    state.missions = save.missions;
+    state.player_ent = Some(player);
+    state.player_pos = player_pos.into();
+
+    if let Ok(mut fov) = state.world.get::<&mut FoV>(player) {
+        fov.visible_tiles.clear();
+        fov.dirty = true;
+    }
+
+    SPATIAL_INDEX
+        .lock()
+        .unwrap()
+        .resize(state.map.dimensions().to_unsigned_tuple());
+    MapIndexingSystem::run(state);
+
+    DEBUGLOG.add_log(format!("Loaded save from {}", SAVE_PATH));
+    state.current_state = ProgramState::Ticking;
+
+    Ok(())
+}
+
+///Spawns everything from the loaded world into the new one, recording the old -> new handle of each entity
+fn move_entities(mut loaded: World, world: &mut World, remap: &mut HashMap<Entity, Entity>) {
+    let old_entities: Vec<Entity> = loaded.iter().map(|ent| ent.entity()).collect();
+
+    for old in old_entities {
+        //entities made up entirely of unsaved components (e.g. intents) come back empty
+        if loaded
+            .entity(old)
+            .unwrap()
+            .component_types()
+            .next()
+            .is_none()
+        {
+            continue;
+        }
+
+        let components = loaded.take(old).unwrap();
+        remap.insert(old, world.spawn(components));
+    }
+}
+
+///Points every component holding an entity reference at the entity's new handle
+fn fix_entity_refs(world: &mut World, remap: &HashMap<Entity, Entity>) {
+    let fix = |ent: &mut Entity| {
+        if let Some(new) = remap.get(ent) {
+            *ent = *new;
+        }
+    };
+
+    for (_ent, equipped) in world.query_mut::<&mut Equipped>() {
+        fix(&mut equipped.owner);
+    }
+    for (_ent, cont) in world.query_mut::<&mut InContainer>() {
+        fix(&mut cont.owner);
+    }
+    for (_ent, status) in world.query_mut::<&mut StatusEffect>() {
+        fix(&mut status.target);
+        fix(&mut status.source);
+    }
+    for (_ent, password) in world.query_mut::<&mut Password>() {
+        fix(&mut password.controls_target);
+        fix(&mut password.owner);
+    }
+    for (_ent, spawner) in world.query_mut::<&mut EffectSpawner>() {
+        fix(&mut spawner.owner);
+    }
+    for (_ent, combat) in world.query_mut::<&mut InCombat>() {
+        fix(&mut combat.target);
+    }
+    for (_ent, chasing) in world.query_mut::<&mut Chasing>() {
+        fix(&mut chasing.target);
+    }
+    for (_ent, parent) in world.query_mut::<&mut ParentNode>() {
+        fix(&mut parent.ent);
+    }
+    for (_ent, owned) in world.query_mut::<&mut NodeOwned>() {
+        fix(&mut owned.owner);
+    }
+    for (_ent, installed) in world.query_mut::<&mut Installed>() {
+        fix(&mut installed.owner);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entity_references_survive_reload() {
+        let mut world = World::new();
+        //despawned entity leaves a gap so the reloaded ids can't line up by accident
+        let gap = world.spawn((Hidden {},));
+        let owner = world.spawn((Position::new(1, 2), Player {}));
+        world.despawn(gap).unwrap();
+        let item = world.spawn((
+            Item {},
+            Equipped {
+                owner,
+                slot: EquipmentSlot::MainHand,
+            },
+        ));
+
+        let json = serde_json::to_string(&SavedWorld(&world)).unwrap();
+        let loaded: LoadedWorld = serde_json::from_str(&json).unwrap();
+        let mut world = World::new();
+        let mut remap = HashMap::new();
+        move_entities(loaded.0, &mut world, &mut remap);
+        fix_entity_refs(&mut world, &remap);
+
+        let new_owner = remap[&owner];
+        let equipped = world.get::<&Equipped>(remap[&item]).unwrap();
+
+        assert_eq!(equipped.owner, new_owner);
+        assert!(world.get::<&Player>(new_owner).is_ok());
+    }
+}
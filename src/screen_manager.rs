@@ -1,6 +1,11 @@
 use crate::dev_console::Terminal;
-use crate::gui::mqui::{show_tooltip_window, DevConsole, ItemWindow, ItemWindowMode};
+use crate::gui::mqui::{
+    show_character_window, show_journal_window, show_tooltip_window, CraftingWindow, DevConsole,
+    DialogueWindow, ItemWindow, ItemWindowMode,
+};
 use crate::gui::TargettingMode;
+use crate::input::{PlayerInput, INPUT};
+use crate::raws::Recipe;
 use crate::{camera, ProgramState, State};
 use hecs::Entity;
 use new_egui_macroquad::egui::{self as egui};
@@ -10,8 +15,12 @@ use std::sync::{Arc, LazyLock, Mutex};
 pub static MANAGER: LazyLock<Mutex<ScreenManager>> = LazyLock::new(|| {
     Mutex::new(ScreenManager {
         current_menu: None,
+        crafting_menu: None,
+        dialogue_menu: None,
         tooltip_active: false,
         dev_console_open: false,
+        character_screen_open: false,
+        journal_open: false,
     })
 });
 
@@ -19,8 +28,15 @@ pub static MANAGER: LazyLock<Mutex<ScreenManager>> = LazyLock::new(|| {
 pub enum MenuType {
     Pickup,
     Drop,
+    Throw,
     Unequip,
     Inventory,
+    Hack,
+    Install,
+    Identify,
+    Implants,
+    Trade { vendor: Entity },
+    Talk,
 }
 
 pub struct MenuScreen {
@@ -32,8 +48,12 @@ pub struct MenuScreen {
 
 pub struct ScreenManager {
     pub current_menu: Option<MenuScreen>,
+    pub crafting_menu: Option<CraftingWindow>,
+    pub dialogue_menu: Option<DialogueWindow>,
     pub tooltip_active: bool,
     pub dev_console_open: bool,
+    pub character_screen_open: bool,
+    pub journal_open: bool,
 }
 
 impl MenuScreen {
@@ -66,6 +86,14 @@ impl ScreenManager {
     pub fn show(&mut self, ctx: &egui::Context, state: &mut State, console: &mut DevConsole) {
         console.show(ctx, state, &mut self.dev_console_open);
 
+        if self.character_screen_open {
+            show_character_window(ctx, state, &mut self.character_screen_open);
+        }
+
+        if self.journal_open {
+            show_journal_window(ctx, state, &mut self.journal_open);
+        }
+
         if self.tooltip_active {
             if let TargettingMode::Keyboard { cursor_pos } = state.target_mode {
                 let (min_x, _max_x, min_y, _max_y) = camera::get_screen_bounds(state);
@@ -77,6 +105,13 @@ impl ScreenManager {
             Some(ref mut menu) => {
                 let res = menu.show(ctx, state);
 
+                //whatever the player picks is recorded so a replay can pick the same
+                if res.is_some() || menu.exit_called {
+                    INPUT
+                        .lock()
+                        .record(state.turn_number, PlayerInput::menu(res.as_ref()));
+                }
+
                 if res.is_some() {
                     state.current_state = ProgramState::AwaitingMenu {
                         response: Some(res.unwrap()),
@@ -94,6 +129,45 @@ impl ScreenManager {
             }
             None => {}
         }
+
+        if let Some(ref mut crafting) = self.crafting_menu {
+            let (recipe, exit_called) = crafting.show(ctx, state);
+
+            if recipe.is_some() || exit_called {
+                INPUT
+                    .lock()
+                    .record(state.turn_number, PlayerInput::Recipe(recipe.clone()));
+            }
+
+            if recipe.is_some() {
+                state.current_state = ProgramState::Crafting { recipe };
+                self.crafting_menu = None;
+            } else if exit_called {
+                self.crafting_menu = None;
+                state.current_state = ProgramState::AwaitingInput;
+            }
+        }
+
+        if let Some(ref mut dialogue) = self.dialogue_menu {
+            let (choice, exit_called) = dialogue.show(ctx);
+
+            if choice.is_some() || exit_called {
+                INPUT
+                    .lock()
+                    .record(state.turn_number, PlayerInput::Reply(choice.clone()));
+            }
+
+            if choice.is_some() {
+                state.current_state = ProgramState::Talking {
+                    speaker: dialogue.speaker,
+                    choice,
+                };
+                self.dialogue_menu = None;
+            } else if exit_called {
+                self.dialogue_menu = None;
+                state.current_state = ProgramState::AwaitingInput;
+            }
+        }
     }
 
     pub fn create_menu(
@@ -112,7 +186,61 @@ impl ScreenManager {
         };
     }
 
+    ///Opens a menu of the goods with their prices listed beside them
+    pub fn open_trade(
+        &mut self,
+        vendor: Entity,
+        goods: Vec<(Entity, i32)>,
+        title: String,
+        state: &mut State,
+    ) {
+        let menu_type = MenuType::Trade { vendor };
+        let mut menu = MenuScreen::new(
+            goods.iter().map(|(item, _)| (*item, false)).collect(),
+            title,
+            ItemWindowMode::Single,
+            menu_type,
+        );
+        menu.window.prices = goods.into_iter().collect();
+        self.current_menu = Some(menu);
+
+        state.current_state = ProgramState::AwaitingMenu {
+            response: None,
+            menu_type,
+        };
+    }
+
+    pub fn open_dialogue(&mut self, window: DialogueWindow, state: &mut State) {
+        state.current_state = ProgramState::Talking {
+            speaker: window.speaker,
+            choice: None,
+        };
+
+        self.dialogue_menu = Some(window);
+    }
+
+    pub fn open_crafting(&mut self, recipes: Vec<Recipe>, state: &mut State) {
+        self.crafting_menu = Some(CraftingWindow::new(recipes));
+
+        state.current_state = ProgramState::Crafting { recipe: None };
+    }
+
+    ///Closes anything waiting on an answer from the player
+    pub fn close_menus(&mut self) {
+        self.current_menu = None;
+        self.crafting_menu = None;
+        self.dialogue_menu = None;
+    }
+
     pub fn toggle_view(&mut self) {
         self.dev_console_open = !self.dev_console_open;
     }
+
+    pub fn toggle_character_screen(&mut self) {
+        self.character_screen_open = !self.character_screen_open;
+    }
+
+    pub fn toggle_journal(&mut self) {
+        self.journal_open = !self.journal_open;
+    }
 }
@@ -1,5 +1,5 @@
 use core::panic;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::ai::Energy;
 use crate::components::{
@@ -223,15 +223,14 @@ fn room_table(state: &mut State) -> RandomTable {
     get_spawn_table_for_depth(&RAWS.lock().unwrap(), state.map.depth)
 }
 
-pub fn roll_spawn_table(depth: i32) -> (String, EntityType) {
+pub fn roll_spawn_table(depth: i32, rng: &mut RandomNumberGenerator) -> (String, EntityType) {
     let mob_names = RAWS.lock().unwrap().get_mob_name_list();
     let item_names = RAWS.lock().unwrap().get_item_name_list();
     let prop_names = RAWS.lock().unwrap().get_prop_name_list();
 
     let table = get_spawn_table_for_depth(&RAWS.lock().unwrap(), depth);
 
-    let mut rng = RandomNumberGenerator::new();
-    let name = table.roll(&mut rng);
+    let name = table.roll(rng);
     let entity_type = get_entity_type(&name);
 
     (name.clone(), entity_type)
@@ -260,13 +259,17 @@ pub fn get_entity_type(name: &String) -> EntityType {
     entity_type
 }
 
-pub fn spawn_room(room: Rect, depth: i32, spawn_list: &mut Vec<(usize, String)>, map: &Map) {
+pub fn spawn_room(
+    room: Rect,
+    depth: i32,
+    spawn_list: &mut Vec<(usize, String)>,
+    map: &Map,
+    rng: &mut RandomNumberGenerator,
+) {
     let mut num_mobs = 0;
     let mut num_items = 0;
     let mut ent_type = EntityType::Mob;
 
-    let mut rng = RandomNumberGenerator::new();
-
     let mut attempts = 20;
 
     let mut num_spawns = rng.range(0, MAXMOBS + 1);
@@ -274,10 +277,12 @@ pub fn spawn_room(room: Rect, depth: i32, spawn_list: &mut Vec<(usize, String)>,
     let mut spawn_points: HashSet<usize> = HashSet::new();
 
     while attempts > 0 && num_spawns > 0 {
-        let (name, _) = roll_spawn_table(depth);
+        let (name, _) = roll_spawn_table(depth, rng);
 
-        let pos_set = room.point_set();
-        let point = pos_set.iter().next().unwrap();
+        let point = Point::new(
+            rng.range(room.x1 + 1, room.x2 + 1),
+            rng.range(room.y1 + 1, room.y2 + 1),
+        );
         let pos = map.xy_idx(point.x, point.y);
         if !spawn_points.contains(&pos) && map.map[pos] != TileType::Wall {
             //spawn_entity(state, &(&0, &name), point.x, point.y, ent_type);
@@ -292,16 +297,20 @@ pub fn spawn_room(room: Rect, depth: i32, spawn_list: &mut Vec<(usize, String)>,
     }
 }
 
-pub fn spawn_region(area: &[usize], map_depth: i32, spawn_list: &mut Vec<(usize, String)>) {
+pub fn spawn_region(
+    area: &[usize],
+    map_depth: i32,
+    spawn_list: &mut Vec<(usize, String)>,
+    rng: &mut RandomNumberGenerator,
+) {
     let mut areas = Vec::from(area);
 
-    let mut rng = RandomNumberGenerator::new();
-
     let mut attempts = 20;
 
     let mut num_spawns = std::cmp::min(rng.range(0, MAXMOBS + 1), area.len() as i32);
 
-    let mut spawn_points: HashMap<usize, String> = HashMap::new();
+    //ordered so the spawn list comes out the same for the same seed
+    let mut spawn_points: BTreeMap<usize, String> = BTreeMap::new();
 
     for _i in 0..num_spawns {
         let array_index = if areas.len() == 1 {
@@ -312,7 +321,7 @@ pub fn spawn_region(area: &[usize], map_depth: i32, spawn_list: &mut Vec<(usize,
 
         let map_idx = areas[array_index];
         areas.remove(array_index);
-        let (name, _) = roll_spawn_table(map_depth);
+        let (name, _) = roll_spawn_table(map_depth, rng);
         spawn_points.insert(map_idx, name);
     }
 
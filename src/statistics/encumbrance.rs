@@ -0,0 +1,132 @@
+use hecs::Entity;
+
+use crate::{
+    components::{Equipped, InContainer, Weight},
+    State,
+};
+
+use super::BaseStatistics;
+
+///Kilograms anyone can carry before their strength is taken into account
+const BASE_CAPACITY: f32 = 5.;
+const CAPACITY_PER_STRENGTH: f32 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Encumbrance {
+    Unburdened,
+    Burdened,
+    Strained,
+    Overloaded,
+}
+
+impl Encumbrance {
+    ///Tier for carrying the given weight, every half of the capacity over it is another tier
+    pub fn from_load(carried: f32, capacity: f32) -> Encumbrance {
+        let load = carried / capacity.max(1.);
+
+        if load <= 1. {
+            Encumbrance::Unburdened
+        } else if load <= 1.5 {
+            Encumbrance::Burdened
+        } else if load <= 2. {
+            Encumbrance::Strained
+        } else {
+            Encumbrance::Overloaded
+        }
+    }
+
+    ///Percentage of the normal energy cost every action takes
+    pub fn energy_cost_percent(&self) -> i32 {
+        match *self {
+            Encumbrance::Unburdened => 100,
+            Encumbrance::Burdened => 125,
+            Encumbrance::Strained => 150,
+            Encumbrance::Overloaded => 200,
+        }
+    }
+
+    ///Taken off dodge rolls
+    pub fn dodge_penalty(&self) -> i32 {
+        match *self {
+            Encumbrance::Unburdened => 0,
+            Encumbrance::Burdened => 2,
+            Encumbrance::Strained => 4,
+            Encumbrance::Overloaded => 8,
+        }
+    }
+}
+
+impl std::fmt::Display for Encumbrance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Encumbrance::Unburdened => write!(f, "Unburdened"),
+            Encumbrance::Burdened => write!(f, "Burdened"),
+            Encumbrance::Strained => write!(f, "Strained"),
+            Encumbrance::Overloaded => write!(f, "Overloaded"),
+        }
+    }
+}
+
+pub fn carrying_capacity(stats: &BaseStatistics) -> f32 {
+    BASE_CAPACITY + stats.strength.total as f32 * CAPACITY_PER_STRENGTH
+}
+
+///Everything in the owner's backpack and everything they have equipped
+pub fn carried_weight(state: &State, owner: Entity) -> f32 {
+    let in_backpack = state
+        .world
+        .query::<(&Weight, &InContainer)>()
+        .iter()
+        .filter(|(_ent, (_weight, cont))| cont.owner == owner)
+        .map(|(_ent, (weight, _cont))| weight.value)
+        .sum::<f32>();
+    let equipped = state
+        .world
+        .query::<(&Weight, &Equipped)>()
+        .iter()
+        .filter(|(_ent, (_weight, equip))| equip.owner == owner)
+        .map(|(_ent, (weight, _equip))| weight.value)
+        .sum::<f32>();
+
+    in_backpack + equipped
+}
+
+///Entities without statistics never slow down
+pub fn get_encumbrance(state: &State, owner: Entity) -> Encumbrance {
+    let Ok(capacity) = state
+        .world
+        .get::<&BaseStatistics>(owner)
+        .map(|stats| carrying_capacity(&stats))
+    else {
+        return Encumbrance::Unburdened;
+    };
+
+    Encumbrance::from_load(carried_weight(state, owner), capacity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heavier_loads_cost_more_and_dodge_less() {
+        let tiers = [0., 10., 13., 17., 25.]
+            .iter()
+            .map(|carried| Encumbrance::from_load(*carried, 10.))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            tiers,
+            vec![
+                Encumbrance::Unburdened,
+                Encumbrance::Unburdened,
+                Encumbrance::Burdened,
+                Encumbrance::Strained,
+                Encumbrance::Overloaded
+            ]
+        );
+        assert!(tiers.windows(2).all(|pair| pair[0].energy_cost_percent()
+            <= pair[1].energy_cost_percent()
+            && pair[0].dodge_penalty() <= pair[1].dodge_penalty()));
+    }
+}
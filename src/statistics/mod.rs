@@ -1,17 +1,20 @@
 use bracket_lib::random::DiceType;
 use hecs::Entity;
+use serde::{Deserialize, Serialize};
 
 use crate::{gamelog::DEBUGLOG, State};
 
 use super::Attribute;
+mod encumbrance;
 mod leveling;
 mod skills;
 mod stat_calculation_system;
+pub use encumbrance::*;
 pub use leveling::*;
 pub use skills::*;
 pub use stat_calculation_system::*;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StatType {
     Strength,
     Dexterity,
@@ -23,13 +26,17 @@ pub enum StatType {
 impl StatType {
     pub fn from_string<S: Into<String>>(value: S) -> Self {
         let val = value.into();
-        match val.as_str() {
-            "strength" => StatType::Strength,
-            "dexterity" => StatType::Dexterity,
-            "toughness" => StatType::Toughness,
-            "intelligence" => StatType::Intelligence,
-            "mentalfortitude" => StatType::MentalFortitude,
-            _ => panic!("{} is not a valid StatType", val),
+        StatType::parse(&val).unwrap_or_else(|| panic!("{} is not a valid StatType", val))
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "strength" => Some(StatType::Strength),
+            "dexterity" => Some(StatType::Dexterity),
+            "toughness" => Some(StatType::Toughness),
+            "intelligence" => Some(StatType::Intelligence),
+            "mentalfortitude" => Some(StatType::MentalFortitude),
+            _ => None,
         }
     }
 }
@@ -46,7 +53,7 @@ impl std::fmt::Display for StatType {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct BaseStatistics {
     pub strength: Attribute,
     pub dexterity: Attribute,
@@ -55,8 +62,10 @@ pub struct BaseStatistics {
     pub mental_fortitude: Attribute,
 }
 impl BaseStatistics {
-    pub fn roll_stats(num_dice: i32) -> BaseStatistics {
-        let mut rng = bracket_lib::random::RandomNumberGenerator::new();
+    pub fn roll_stats(
+        num_dice: i32,
+        rng: &mut bracket_lib::random::RandomNumberGenerator,
+    ) -> BaseStatistics {
         let dice_pool = DiceType::new(num_dice, 6, 1);
 
         BaseStatistics {
@@ -114,8 +123,20 @@ impl BaseStatistics {
         self.strength.bonuses = 0;
         self.toughness.bonuses = 0;
     }
+
+    pub fn recalculate_totals(&mut self) {
+        for stat in [
+            &mut self.strength,
+            &mut self.dexterity,
+            &mut self.toughness,
+            &mut self.intelligence,
+            &mut self.mental_fortitude,
+        ] {
+            stat.total = stat.base + stat.bonuses;
+        }
+    }
 }
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Pools {
     pub hitpoints: StatPool,
     pub exp: i32,
@@ -123,7 +144,7 @@ pub struct Pools {
     pub armour_class: Attribute,
     pub hit_die: DiceType,
 }
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct StatPool {
     pub current_value: i32,
     pub max_value: i32,
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use hecs::Entity;
@@ -8,8 +9,32 @@ use crate::{
     State,
 };
 
+///Experience a skill needs for each level it already has before it improves again
+const SKILL_EXP_PER_LEVEL: i32 = 5;
+
+#[derive(Serialize, Deserialize)]
 pub struct Skills {
     skill_map: HashMap<Skill, i32>,
+    ///experience earned towards the next level of each skill
+    #[serde(default)]
+    exp_map: HashMap<Skill, i32>,
+}
+
+impl Skills {
+    pub fn new(skills: &[(Skill, i32)]) -> Skills {
+        Skills {
+            skill_map: skills.iter().cloned().collect(),
+            exp_map: HashMap::new(),
+        }
+    }
+
+    pub fn level(&self, skill: Skill) -> i32 {
+        *self.skill_map.get(&skill).unwrap_or(&0)
+    }
+
+    pub fn exp(&self, skill: Skill) -> i32 {
+        *self.exp_map.get(&skill).unwrap_or(&0)
+    }
 }
 
 impl std::fmt::Display for Skill {
@@ -20,24 +45,114 @@ impl std::fmt::Display for Skill {
             Skill::Dodge => write!(f, "Dodge"),
             Skill::Melee => write!(f, "Melee"),
             Skill::Ranged => write!(f, "Ranged"),
+            Skill::Crafting => write!(f, "Crafting"),
+            Skill::Stealth => write!(f, "Stealth"),
         }
     }
 }
 
-#[allow(dead_code)]
-#[derive(Hash, Debug, PartialEq, PartialOrd, Ord, Eq)]
+#[derive(Hash, Debug, Clone, Copy, PartialEq, PartialOrd, Ord, Eq, Serialize, Deserialize)]
 pub enum Skill {
     Melee,
     Ranged,
     Dodge,
     Block,
     Hack,
+    Crafting,
+    Stealth,
+}
+
+impl Skill {
+    pub const ALL: [Skill; 7] = [
+        Skill::Melee,
+        Skill::Ranged,
+        Skill::Dodge,
+        Skill::Block,
+        Skill::Hack,
+        Skill::Crafting,
+        Skill::Stealth,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "melee" => Some(Skill::Melee),
+            "ranged" => Some(Skill::Ranged),
+            "dodge" => Some(Skill::Dodge),
+            "block" => Some(Skill::Block),
+            "hack" => Some(Skill::Hack),
+            "crafting" => Some(Skill::Crafting),
+            "stealth" => Some(Skill::Stealth),
+            _ => None,
+        }
+    }
+
+    ///The statistic that limits how far the skill can be trained
+    pub fn governing_stat(&self) -> StatType {
+        match *self {
+            Skill::Melee => StatType::Strength,
+            Skill::Ranged => StatType::Dexterity,
+            Skill::Dodge => StatType::Dexterity,
+            Skill::Block => StatType::Toughness,
+            Skill::Hack => StatType::Intelligence,
+            Skill::Crafting => StatType::Intelligence,
+            Skill::Stealth => StatType::Dexterity,
+        }
+    }
+}
+
+///Experience needed to go from the given skill level to the next one
+pub fn skill_exp_to_next_level(level: i32) -> i32 {
+    (level + 1) * SKILL_EXP_PER_LEVEL
+}
+
+///Highest level a skill can be trained to, half of its governing statistic
+pub fn skill_cap(skill: Skill, stats: &BaseStatistics) -> i32 {
+    stats.get_stat(skill.governing_stat()).total / 2
 }
 
+///Entities without Skills are treated as untrained
 pub fn get_skill_value(skill: Skill, entity: Entity, state: &mut State) -> i32 {
-    let skills = state.world.query_one_mut::<&Skills>(entity).unwrap();
+    state
+        .world
+        .query_one_mut::<&Skills>(entity)
+        .map_or(0, |skills| skills.level(skill))
+}
+
+///Gives experience to a skill that has just been used, levelling it up until it reaches its cap. Does nothing for
+/// entities without Skills
+pub fn gain_skill_exp(skill: Skill, amount: i32, entity: Entity, state: &mut State) {
+    let Ok((skills, stats)) = state
+        .world
+        .query_one_mut::<(&mut Skills, &BaseStatistics)>(entity)
+    else {
+        return;
+    };
+
+    let cap = skill_cap(skill, stats);
+    let mut level = skills.level(skill);
+    if level >= cap {
+        return;
+    }
+
+    let mut exp = skills.exp(skill) + amount;
+    let mut improved = false;
+    while level < cap && exp >= skill_exp_to_next_level(level) {
+        exp -= skill_exp_to_next_level(level);
+        level += 1;
+        improved = true;
+    }
+    if level >= cap {
+        exp = 0;
+    }
+
+    skills.skill_map.insert(skill, level);
+    skills.exp_map.insert(skill, exp);
 
-    *skills.skill_map.get(&skill).unwrap_or(&0)
+    if improved && Some(entity) == state.player_ent {
+        state
+            .game_log
+            .add_log(format!("Your {} skill improves to {}.", skill, level));
+    }
 }
 
 ///Rolls a skill check using a combination of skill level, stat modifier, and 1d20. Need to meet or beat the
@@ -56,7 +171,7 @@ pub fn skill_check(
 
     let (skills, _pools, stats) = query.get().unwrap();
 
-    let skill_mod = *skills.skill_map.get(&skill).unwrap_or(&0);
+    let skill_mod = skills.level(skill);
     let stat_mod = stats.get_stat(stat).get_modifier();
     let roll = state.rng.roll_dice(1, 20);
     let res = roll + skill_mod + stat_mod;
@@ -85,5 +200,35 @@ pub fn skill_check(
     DEBUGLOG.add_log(msg.clone());
     state.game_log.add_log(msg);
 
+    std::mem::drop(query);
+    gain_skill_exp(skill, 1, entity, state);
+
     is_success
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testing::test_state;
+
+    #[test]
+    fn skills_level_with_use_up_to_their_cap() {
+        let mut state = test_state(11);
+        let player = state.player_ent.unwrap();
+
+        let cap = skill_cap(
+            Skill::Melee,
+            &state.world.get::<&BaseStatistics>(player).unwrap(),
+        );
+        gain_skill_exp(Skill::Melee, skill_exp_to_next_level(0), player, &mut state);
+        assert_eq!(
+            get_skill_value(Skill::Melee, player, &mut state),
+            1.min(cap)
+        );
+
+        for _ in 0..1000 {
+            gain_skill_exp(Skill::Melee, 1, player, &mut state);
+        }
+        assert_eq!(get_skill_value(Skill::Melee, player, &mut state), cap);
+    }
+}
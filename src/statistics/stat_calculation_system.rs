@@ -4,6 +4,7 @@ use hecs::Entity;
 
 use crate::{
     components::{GrantStat, StatusEffect},
+    effects::status_stacks,
     statistics::BaseStatistics,
     State,
 };
@@ -15,16 +16,20 @@ pub fn stat_calculation_system(state: &mut State) {
 
     let stat_granting_effects = state
         .world
-        .query_mut::<(&StatusEffect, &GrantStat)>()
-        .into_iter()
-        .map(|(_, (status, stat))| (*status, *stat))
+        .query::<(&StatusEffect, &GrantStat)>()
+        .iter()
+        .map(|(ent, (status, stat))| (*status, *stat, status_stacks(state, ent)))
         .collect::<Vec<_>>();
 
-    for (status, stat) in stat_granting_effects.iter() {
+    for (status, stat, stacks) in stat_granting_effects.iter() {
         state
             .world
             .query_one_mut::<&mut BaseStatistics>(status.target)
             .unwrap()
-            .change_stat_bonus(stat.stat, stat.amount);
+            .change_stat_bonus(stat.stat, stat.amount * stacks);
+    }
+
+    for (_, stats) in state.world.query_mut::<&mut BaseStatistics>() {
+        stats.recalculate_totals();
     }
 }
@@ -1,25 +1,9 @@
-use crate::{
-    components::{EffectDuration, StatusEffect},
-    State,
-};
+use crate::{cybernetics::tick_implant_cooldowns, effects::tick_status_effects, State};
 
 pub fn time_system(state: &mut State) {
     state.turn_number += 1;
 
-    let mut effects_to_despawn = Vec::new();
+    tick_status_effects(state);
 
-    for (ent, (status, duration)) in state
-        .world
-        .query_mut::<(&StatusEffect, &mut EffectDuration)>()
-    {
-        duration.rounds -= 1;
-
-        if duration.rounds <= 0 {
-            effects_to_despawn.push((ent, status.target));
-        }
-    }
-
-    for (effect, _target) in effects_to_despawn.iter() {
-        let _ = state.world.despawn(*effect);
-    }
+    tick_implant_cooldowns(state);
 }
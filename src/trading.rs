@@ -0,0 +1,235 @@
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{Equipped, Faction, Value},
+    identification::item_name,
+    raws::RAWS,
+    reputation::hostile_to_player,
+    spawns::spawning_system::spawn_item_in_backpack,
+    statistics::BaseStatistics,
+    InContainer, Name, Position, State,
+};
+
+///Credits the player starts the run with
+pub const STARTING_CREDITS: i32 = 100;
+
+///Percentage of an item's value vendors sell it for before any haggling
+const BUY_MARKUP: i32 = 150;
+///Percentage of an item's value vendors pay for it before any haggling
+const SELL_MARKDOWN: i32 = 50;
+///Most a good or bad deal can move prices by, in percent. Keeps buying something back dearer than it sold for
+const MAX_PRICE_SHIFT: i32 = 40;
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct Credits {
+    pub amount: i32,
+}
+
+///Mob or prop that trades, its stock is rolled from the named stock table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vendor {
+    pub table: String,
+}
+
+///How much of a percent the player's standing with the vendor's faction and their Intelligence shift prices in their
+/// favour, 5 points of standing or 1 point of Intelligence modifier is worth 1% and 5% respectively
+pub fn price_shift(standing: i32, int_modifier: i32) -> i32 {
+    (standing / 5 + int_modifier * 5).clamp(-MAX_PRICE_SHIFT, MAX_PRICE_SHIFT)
+}
+
+///What the vendor charges for something worth value, never less than 1 credit
+pub fn buy_price(value: i32, shift: i32) -> i32 {
+    (value * (BUY_MARKUP - shift) / 100).max(1)
+}
+
+///What the vendor pays for something worth value
+pub fn sell_price(value: i32, shift: i32) -> i32 {
+    (value * (SELL_MARKDOWN + shift) / 100).max(0)
+}
+
+pub fn credits(state: &State, ent: Entity) -> i32 {
+    state
+        .world
+        .get::<&Credits>(ent)
+        .map_or(0, |credits| credits.amount)
+}
+
+pub fn add_credits(state: &mut State, ent: Entity, amount: i32) {
+    if let Ok(mut credits) = state.world.get::<&mut Credits>(ent) {
+        credits.amount += amount;
+    }
+}
+
+///Price shift for the trader dealing with the vendor
+fn trade_shift(state: &State, trader: Entity, vendor: Entity) -> i32 {
+    let standing = state
+        .world
+        .get::<&Faction>(vendor)
+        .map_or(0, |faction| state.reputation.standing(&faction.name));
+    let int_modifier = state
+        .world
+        .get::<&BaseStatistics>(trader)
+        .map_or(0, |stats| stats.intelligence.get_modifier());
+
+    price_shift(standing, int_modifier)
+}
+
+///What the item goes for in a deal between the trader and vendor, None if the vendor has no interest in it
+pub fn trade_price(state: &State, trader: Entity, vendor: Entity, item: Entity) -> Option<i32> {
+    let value = state.world.get::<&Value>(item).ok()?.credits;
+    let shift = trade_shift(state, trader, vendor);
+
+    if owner(state, item) == Some(vendor) {
+        Some(buy_price(value, shift))
+    } else {
+        Some(sell_price(value, shift)).filter(|price| *price > 0)
+    }
+}
+
+fn owner(state: &State, item: Entity) -> Option<Entity> {
+    state
+        .world
+        .get::<&InContainer>(item)
+        .ok()
+        .map(|cont| cont.owner)
+}
+
+///Vendor the player is walking into at the given tile, as long as they're willing to deal with the player
+pub fn vendor_at(state: &mut State, idx: usize) -> Option<Entity> {
+    let width = state.map.map_width;
+
+    let vendor = state
+        .world
+        .query_mut::<(&Vendor, &Position)>()
+        .into_iter()
+        .find(|(_ent, (_, pos))| (pos.y * width + pos.x) as usize == idx)
+        .map(|(ent, _)| ent)?;
+
+    (!hostile_to_player(state, vendor)).then_some(vendor)
+}
+
+///The vendor's stock followed by everything in the trader's backpack the vendor would buy
+pub fn trade_goods(state: &State, trader: Entity, vendor: Entity) -> Vec<Entity> {
+    let carried_by = |owner: Entity| {
+        let mut items = state
+            .world
+            .query::<(&InContainer, &Value)>()
+            .iter()
+            .filter(|(_ent, (cont, _))| cont.owner == owner)
+            .map(|(ent, _)| ent)
+            .collect::<Vec<_>>();
+        items.sort_by_key(|item| item_name(state, *item));
+        items
+    };
+
+    let mut goods = carried_by(vendor);
+    goods.extend(
+        carried_by(trader)
+            .into_iter()
+            .filter(|item| trade_price(state, trader, vendor, *item).is_some()),
+    );
+    goods
+}
+
+///Buys the item if the vendor owns it, otherwise sells it to them. Returns whether anything changed hands
+pub fn trade(state: &mut State, trader: Entity, vendor: Entity, item: Entity) -> bool {
+    let Some(price) = trade_price(state, trader, vendor, item) else {
+        return false;
+    };
+    let name = item_name(state, item);
+    let buying = owner(state, item) == Some(vendor);
+
+    if buying {
+        if credits(state, trader) < price {
+            state.game_log.add_log(format!(
+                "You can't afford the {} for {} credits.",
+                name, price
+            ));
+            return false;
+        }
+
+        add_credits(state, trader, -price);
+        let _ = state.world.insert_one(item, InContainer { owner: trader });
+        state
+            .game_log
+            .add_log(format!("You buy the {} for {} credits.", name, price));
+    } else {
+        add_credits(state, trader, price);
+        let _ = state.world.insert_one(item, InContainer { owner: vendor });
+        state
+            .game_log
+            .add_log(format!("You sell the {} for {} credits.", name, price));
+    }
+
+    true
+}
+
+///Throws out whatever every vendor on the floor has left and rolls them fresh stock
+pub fn restock_vendors(state: &mut State) {
+    let vendors = state
+        .world
+        .query::<(&Vendor, &Position)>()
+        .iter()
+        .map(|(ent, (vendor, _))| (ent, vendor.table.clone()))
+        .collect::<Vec<_>>();
+
+    for (vendor, table) in vendors {
+        let old_stock = state
+            .world
+            .query::<&InContainer>()
+            .without::<&Equipped>()
+            .iter()
+            .filter(|(_ent, cont)| cont.owner == vendor)
+            .map(|(ent, _)| ent)
+            .collect::<Vec<_>>();
+        for item in old_stock {
+            let _ = state.world.despawn(item);
+        }
+
+        let Some(table) = RAWS.lock().unwrap().get_stock_table(&table) else {
+            continue;
+        };
+        for entry in table.stock.iter() {
+            if state.rng.roll_dice(1, 100) > entry.chance.unwrap_or(100) {
+                continue;
+            }
+
+            let count = state.rng.roll_str(&entry.count).unwrap_or(1);
+            for _ in 0..count {
+                spawn_item_in_backpack(state, &entry.item, vendor);
+            }
+        }
+    }
+}
+
+///Name of the vendor along with how many credits the trader has to spend, used as the trade window's title
+pub fn trade_title(state: &State, trader: Entity, vendor: Entity) -> String {
+    let vendor_name = state
+        .world
+        .get::<&Name>(vendor)
+        .map_or("Vendor".to_string(), |name| name.name.clone());
+
+    format!("{} ({} credits)", vendor_name, credits(state, trader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vendors_never_buy_back_for_more_than_they_sell() {
+        for standing in [-100, -25, 0, 25, 100] {
+            for int_modifier in -4..=5 {
+                let shift = price_shift(standing, int_modifier);
+                assert!(sell_price(20, shift) < buy_price(20, shift));
+            }
+        }
+
+        assert_eq!(buy_price(20, 0), 30);
+        assert_eq!(sell_price(20, 0), 10);
+        assert!(buy_price(20, price_shift(50, 2)) < buy_price(20, 0));
+        assert!(sell_price(20, price_shift(-50, 0)) < sell_price(20, 0));
+        assert_eq!(buy_price(0, 0), 1);
+    }
+}
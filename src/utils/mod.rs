@@ -3,6 +3,8 @@ use hecs::Entity;
 
 use crate::{map_indexing::SPATIAL_INDEX, statistics::Pools, State};
 
+#[cfg(test)]
+pub mod testing;
 pub mod timer;
 
 pub fn get_mob_entities_at_position(state: &mut State, pos: Point) -> Vec<Entity> {
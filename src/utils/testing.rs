@@ -0,0 +1,28 @@
+use hecs::Entity;
+
+use crate::{
+    game_init, new_state, raws,
+    spawns::spawning_system::{spawn_entity, EntityType},
+    Name, Position, State,
+};
+
+///A new run started from the seed with the raws loaded, what most tests start from
+pub fn test_state(seed: u64) -> State {
+    let mut state = new_state(None, seed);
+    raws::run().unwrap();
+    game_init(&mut state);
+    state
+}
+
+///Spawns the named mob from the raws at x, y and returns it
+pub fn spawn_test_mob(state: &mut State, name: &str, x: i32, y: i32) -> Entity {
+    spawn_entity(state, &(&0, &name.to_string()), x, y, EntityType::Mob);
+
+    state
+        .world
+        .query::<(&Name, &Position)>()
+        .iter()
+        .find(|(_ent, (mob, pos))| mob.name == name && pos.as_tuple() == (x, y))
+        .map(|(ent, _)| ent)
+        .unwrap_or_else(|| panic!("Couldn't spawn a {} at {}, {}", name, x, y))
+}
@@ -1,6 +1,8 @@
 use std::arch::x86_64;
 
-use crate::{networks::{ControlNode, NodeOwned}, BlocksVisibility, Map, Player, State};
+use std::collections::HashMap;
+
+use crate::{components::Blinded, effects::{affected_by, blindness}, networks::{ControlNode, NodeOwned}, BlocksVisibility, Map, Player, State};
 
 
 use super::{FoV, Position};
@@ -20,13 +22,20 @@ impl VisibilitySystem
             state.map.view_blocked.insert(idx);
         }
 
-        for(_id ,(fov,pos, player )) in state.world.query_mut::<(&mut FoV,&Position, Option<&Player>)>()
+        //blinding statuses cut down how far their targets can see
+        let blinded = affected_by::<Blinded>(state).into_iter()
+            .map(|ent| (ent, blindness(state, ent)))
+            .collect::<HashMap<_,_>>();
+
+        for(id ,(fov,pos, player )) in state.world.query_mut::<(&mut FoV,&Position, Option<&Player>)>()
         {
             if fov.dirty
             {
+                let range = (fov.range - blinded.get(&id).unwrap_or(&0)).max(1);
+
                 fov.dirty = false;
                 fov.visible_tiles.clear();
-                fov.visible_tiles = field_of_view(Point::new(pos.x,pos.y), fov.range, &state.map);
+                fov.visible_tiles = field_of_view(Point::new(pos.x,pos.y), range, &state.map);
                 fov.visible_tiles.retain(|p| p.x >= 0 && p.x < state.map.map_width && p.y >= 0 && p.y < state.map.map_height );
 
             //let p: Option<&Player> = state.world.entity(_id).